@@ -0,0 +1,251 @@
+//! Tens of thousands of particles with a live frame-time graph, to compare backends and catch
+//! performance regressions. Run with `trunk serve --release` in this directory, and compare
+//! results at the same window size, since the canvas has a fixed resolution.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::str::FromStr;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{window, HtmlInputElement, HtmlSelectElement};
+use yew::{
+    function_component, html, use_effect_with, use_state_eq, Callback, Event, Html, InputEvent,
+    TargetCast,
+};
+use yew_confetti::{use_confetti, Backend, Cannon, Confetti, Mode};
+
+/// Frame times kept for the graph and statistics.
+const SAMPLES: usize = 240;
+/// Frames between updates of the readouts, so re-rendering them doesn't skew frame times.
+const UPDATE_INTERVAL: u32 = 30;
+/// Frame time at the top of the graph, in milliseconds.
+const GRAPH_MS: f64 = 50.0;
+/// Seconds between bursts, short enough that the particle count is nearly steady.
+const PERIOD: f32 = 0.1;
+/// Seconds each particle lasts, as in the default props.
+const LIFESPAN: f32 = 2.5;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Choice {
+    WebGl,
+    Canvas2d,
+    Dom,
+}
+
+impl Choice {
+    const ALL: [Self; 3] = [Self::WebGl, Self::Canvas2d, Self::Dom];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::WebGl => "WebGL",
+            Self::Canvas2d => "Canvas 2D",
+            Self::Dom => "DOM",
+        }
+    }
+
+    fn backend(self, particles: usize) -> Backend {
+        match self {
+            // Falls back to the 2D context if WebGL2 is unavailable.
+            Self::WebGl => Backend::Canvas,
+            Self::Canvas2d => Backend::Canvas2d,
+            Self::Dom => Backend::Dom {
+                max_particles: particles,
+            },
+        }
+    }
+}
+
+/// Frame times, in milliseconds, oldest first.
+#[derive(Default, PartialEq)]
+struct Samples(Vec<f64>);
+
+impl Samples {
+    fn average(&self) -> f64 {
+        self.0.iter().sum::<f64>() / self.0.len().max(1) as f64
+    }
+
+    fn percentile(&self, fraction: f64) -> f64 {
+        let mut sorted = self.0.clone();
+        sorted.sort_by(f64::total_cmp);
+        let index = ((sorted.len() as f64 * fraction) as usize).min(sorted.len().saturating_sub(1));
+        sorted.get(index).copied().unwrap_or(0.0)
+    }
+
+    /// SVG polyline points, with `x` in samples and `y` in milliseconds from the top.
+    fn points(&self) -> String {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(i, ms)| format!("{i},{:.2}", GRAPH_MS - ms.min(GRAPH_MS)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[function_component(App)]
+fn app() -> Html {
+    let confetti = use_confetti();
+    let particles = use_state_eq(|| 20_000usize);
+    let choice = use_state_eq(|| Choice::WebGl);
+    let samples = use_state_eq(Samples::default);
+    let alive = use_state_eq(|| 0usize);
+
+    {
+        let confetti = confetti.clone();
+        let samples = samples.clone();
+        let alive = alive.clone();
+        use_effect_with((), move |_| {
+            let callback = Rc::new(RefCell::new(None::<Closure<dyn FnMut(f64)>>));
+            let id = Rc::new(Cell::new(None));
+            let request = {
+                let callback = Rc::clone(&callback);
+                let id = Rc::clone(&id);
+                move || {
+                    id.set(callback.borrow().as_ref().and_then(|c: &Closure<_>| {
+                        window()?
+                            .request_animation_frame(c.as_ref().unchecked_ref())
+                            .ok()
+                    }));
+                }
+            };
+            let mut times = VecDeque::with_capacity(SAMPLES);
+            let mut last = None;
+            let mut frames = 0;
+            let request_next = request.clone();
+            *callback.borrow_mut() = Some(Closure::new(move |time: f64| {
+                if let Some(last) = last.replace(time) {
+                    if times.len() == SAMPLES {
+                        times.pop_front();
+                    }
+                    times.push_back(time - last);
+                }
+                frames += 1;
+                if frames % UPDATE_INTERVAL == 0 {
+                    samples.set(Samples(times.iter().copied().collect()));
+                    alive.set(confetti.particle_count());
+                }
+                request_next();
+            }));
+            request();
+            move || {
+                if let Some((id, window)) = id.get().zip(window()) {
+                    let _ = window.cancel_animation_frame(id);
+                }
+                // Break the cycle between the closure and `request`.
+                callback.borrow_mut().take();
+            }
+        });
+    }
+
+    let on_particles = {
+        let particles = particles.clone();
+        Callback::from(move |event: InputEvent| {
+            let input = event.target_dyn_into::<HtmlInputElement>().unwrap();
+            if let Ok(value) = usize::from_str(&input.value()) {
+                particles.set(value);
+            }
+        })
+    };
+    let on_backend = {
+        let choice = choice.clone();
+        Callback::from(move |event: Event| {
+            let select = event.target_dyn_into::<HtmlSelectElement>().unwrap();
+            if let Some(&value) = Choice::ALL.get(select.selected_index() as usize) {
+                choice.set(value);
+            }
+        })
+    };
+
+    let burst = (*particles as f32 * PERIOD / LIFESPAN).ceil() as usize;
+    html! {
+        <>
+            <table>
+                <tr>
+                    <td>{"particles:"}</td>
+                    <td>
+                        <input
+                            type="range"
+                            min="1000"
+                            max="50000"
+                            step="1000"
+                            value={particles.to_string()}
+                            oninput={on_particles}
+                        />
+                    </td>
+                    <td>{*particles}</td>
+                </tr>
+                <tr>
+                    <td>{"backend:"}</td>
+                    <td>
+                        <select onchange={on_backend}>
+                            {for Choice::ALL.iter().map(|&c| html! {
+                                <option selected={c == *choice}>{c.name()}</option>
+                            })}
+                        </select>
+                    </td>
+                </tr>
+            </table>
+            <p>
+                {format!(
+                    "{} alive, frame time avg {:.1} ms, p95 {:.1} ms, max {:.1} ms",
+                    *alive,
+                    samples.average(),
+                    samples.percentile(0.95),
+                    samples.percentile(1.0),
+                )}
+            </p>
+            <svg
+                viewBox={format!("0 0 {} {GRAPH_MS}", SAMPLES - 1)}
+                preserveAspectRatio="none"
+                width="480"
+                height="120"
+                style="display: block; background-color: #2c3e50;"
+            >
+                // Budgets for 60 and 30 frames per second.
+                {for [1000.0 / 60.0, 1000.0 / 30.0].iter().map(|ms| {
+                    let y = (GRAPH_MS - ms).to_string();
+                    html! {
+                        <line
+                            x1="0"
+                            x2={SAMPLES.to_string()}
+                            y1={y.clone()}
+                            y2={y}
+                            stroke="gray"
+                            vector-effect="non-scaling-stroke"
+                        />
+                    }
+                })}
+                <polyline
+                    points={samples.points()}
+                    fill="none"
+                    stroke="#2ecc71"
+                    vector-effect="non-scaling-stroke"
+                />
+            </svg>
+            <Confetti
+                handle={confetti}
+                backend={choice.backend(*particles)}
+                width={1024}
+                height={768}
+                lifespan={LIFESPAN}
+                max_particles={*particles}
+                seed={Some(0)}
+                disable_for_reduced_motion={false}
+                style="display: block; margin-top: 1rem; max-width: 100%;"
+            >
+                <Cannon
+                    x={0.5}
+                    y={0.5}
+                    spread={std::f32::consts::TAU}
+                    velocity={0.7}
+                    mode={Mode::repeating_burst(burst, PERIOD)}
+                />
+            </Confetti>
+        </>
+    }
+}
+
+fn main() {
+    yew::Renderer::<App>::new().render();
+}
@@ -1,27 +1,110 @@
-use std::fmt::Write;
+use js_sys::wasm_bindgen::{JsCast, JsValue};
 use std::ops::Deref;
+use std::rc::Rc;
 use std::str::FromStr;
-use web_sys::HtmlInputElement;
+use web_sys::{window, Element, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
 use yew::{
-    function_component, html, html_nested, props, use_state_eq, Callback, Html, InputEvent,
-    MouseEvent, TargetCast, UseStateHandle,
+    function_component, html, props, use_effect_with, use_memo, use_mut_ref, use_state_eq,
+    AttrValue, Callback, Event, Html, InputEvent, MouseEvent, TargetCast, UseStateHandle,
 };
-use yew_confetti::{Cannon, CannonProps, Confetti, ConfettiProps, Mode, ModeImpl};
+use yew_confetti::{
+    use_confetti, CannonConfig, CannonProps, Confetti, ConfettiConfig, ConfettiOverlay,
+    ConfettiProps, ConfettiStats, Mode, ModeKind, Pattern, Shape,
+};
+
+/// Frames between updates of the stats readout, so re-rendering doesn't skew them.
+const STATS_INTERVAL: u32 = 15;
+
+/// The configuration in the URL fragment, if any, so links reproduce it.
+fn shared_config() -> Option<ConfettiConfig> {
+    let hash = window()?.location().hash().ok()?;
+    let json = js_sys::decode_uri_component(hash.strip_prefix('#')?).ok()?;
+    ConfettiConfig::from_json(&String::from(json)).ok()
+}
+
+/// Replace the URL fragment with `config`, without adding to the history.
+fn share_config(config: &ConfettiConfig) {
+    let Ok(json) = config.to_json() else {
+        return;
+    };
+    let url = format!("#{}", js_sys::encode_uri_component(&json));
+    if let Some(history) = window().and_then(|w| w.history().ok()) {
+        let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&url));
+    }
+}
+
+/// `json`, indented for reading and version control.
+fn pretty(json: &str) -> Option<String> {
+    let value = js_sys::JSON::parse(json).ok()?;
+    js_sys::JSON::stringify_with_replacer_and_space(&value, &JsValue::NULL, &JsValue::from(2))
+        .ok()?
+        .as_string()
+}
+
+/// Props with the fields of `config` that the playground edits.
+fn config_props(config: &ConfettiConfig) -> ConfettiProps {
+    props!(ConfettiProps {
+        width: config.width,
+        height: config.height,
+        decay: config.decay,
+        drift: config.drift,
+        gravity: config.gravity,
+        lifespan: config.lifespan,
+        scalar: config.scalar,
+    })
+}
 
 #[function_component(App)]
 fn app() -> Html {
-    let key = use_state_eq(|| 0i32);
+    let confetti = use_confetti();
     let on_reset = {
-        let key = key.clone();
+        let confetti = confetti.clone();
         Callback::from(move |_: MouseEvent| {
-            key.set(key.wrapping_add(1));
+            confetti.clear();
+            confetti.restart();
+        })
+    };
+
+    let on_fire = {
+        let confetti = confetti.clone();
+        Callback::from(move |_: MouseEvent| confetti.fire())
+    };
+
+    let stats = use_state_eq(ConfettiStats::default);
+    let stats_frames = use_mut_ref(|| 0u32);
+    let on_stats = {
+        let stats = stats.clone();
+        Callback::from(move |new_stats: ConfettiStats| {
+            let mut frames = stats_frames.borrow_mut();
+            *frames += 1;
+            // Show the final, empty frame too.
+            if *frames >= STATS_INTERVAL || new_stats.particles == 0 {
+                *frames = 0;
+                stats.set(new_stats);
+            }
         })
     };
 
     let show_defaults = use_state_eq(|| false);
-    let props = use_state_eq(|| props!(ConfettiProps {}));
+    let click_to_fire = use_state_eq(|| false);
+    let fullscreen = use_state_eq(|| false);
+    let on_fullscreen = {
+        let fullscreen = fullscreen.clone();
+        Callback::from(move |_: MouseEvent| fullscreen.set(!*fullscreen))
+    };
+    let shared = use_memo((), |_| shared_config());
+    let props = use_state_eq(|| {
+        (*shared)
+            .as_ref()
+            .map_or_else(|| props!(ConfettiProps {}), config_props)
+    });
 
-    let cannons_props = use_state_eq(|| vec![props!(CannonProps {})]);
+    // Configs, unlike props, own their colors.
+    let cannons_props = use_state_eq(|| {
+        (*shared)
+            .as_ref()
+            .map_or_else(|| vec![CannonConfig::default()], |c| c.cannons.clone())
+    });
 
     fn checkbox_factory<P: Clone + 'static>(
         name: &str,
@@ -90,66 +173,348 @@ fn app() -> Html {
         }
     }
 
+    fn colors_factory(
+        name: &str,
+        i: usize,
+        cannons_props: UseStateHandle<Vec<CannonConfig>>,
+    ) -> Html {
+        fn edit<E: 'static>(
+            cannons_props: &UseStateHandle<Vec<CannonConfig>>,
+            i: usize,
+            f: impl Fn(&mut Vec<AttrValue>, E) + 'static,
+        ) -> Callback<E> {
+            let cannons_props = cannons_props.clone();
+            Callback::from(move |event| {
+                let mut new_props = cannons_props.deref().clone();
+                f(&mut new_props[i].colors, event);
+                cannons_props.set(new_props);
+            })
+        }
+
+        let chips = cannons_props[i]
+            .colors
+            .iter()
+            .enumerate()
+            .map(|(j, color)| {
+                let oninput = edit(&cannons_props, i, move |colors, event: InputEvent| {
+                    let input = event.target_dyn_into::<HtmlInputElement>().unwrap();
+                    colors[j] = input.value().into();
+                });
+                let left = edit(&cannons_props, i, move |colors, _: MouseEvent| {
+                    colors.swap(j - 1, j);
+                });
+                let remove = edit(&cannons_props, i, move |colors, _: MouseEvent| {
+                    colors.remove(j);
+                });
+                html! {
+                    <span style="display: inline-flex; align-items: center;">
+                        <input type="color" value={color.clone()} {oninput}/>
+                        if j > 0 {
+                            <button style="color: black;" onclick={left}>{"<"}</button>
+                        }
+                        <button style="color: black;" onclick={remove}>{"x"}</button>
+                    </span>
+                }
+            });
+        let add = edit(&cannons_props, i, |colors, _: MouseEvent| {
+            colors.push(AttrValue::Static("#ffffff"));
+        });
+        html! {
+            <tr>
+                <td>{name}{":"}</td>
+                <td colspan="2">
+                    {for chips}
+                    <button style="color: black;" onclick={add}>{"+"}</button>
+                </td>
+            </tr>
+        }
+    }
+
+    /// A slider for a field of cannon `i`'s mode, if its kind has one.
+    fn mode_slider(
+        name: String,
+        min: f32,
+        max: f32,
+        i: usize,
+        cannons_props: UseStateHandle<Vec<CannonConfig>>,
+        load: fn(ModeKind) -> Option<f32>,
+        store: fn(&mut Mode, f32),
+    ) -> Html {
+        if load(cannons_props[i].mode.kind()).is_none() {
+            return Html::default();
+        }
+        slider_factory(
+            &name,
+            min,
+            max,
+            cannons_props,
+            move |props| load(props[i].mode.kind()).unwrap_or(0.0),
+            move |props, value| store(&mut props[i].mode, value),
+        )
+    }
+
+    /// The kind of cannon `i`'s mode, and its fields.
+    fn mode_factory(i: usize, cannons_props: UseStateHandle<Vec<CannonConfig>>) -> Html {
+        let kind = cannons_props[i].mode.kind();
+        let selected = match kind {
+            ModeKind::Burst { .. } => 0,
+            ModeKind::Continuous { .. } => 1,
+            ModeKind::Repeating { .. } => 2,
+            ModeKind::Manual { .. } => 3,
+            ModeKind::Hover { .. } => 4,
+            ModeKind::Drag { .. } => 5,
+            ModeKind::Tap { .. } => 6,
+        };
+        let onchange = {
+            let cannons_props = cannons_props.clone();
+            Callback::from(move |event: Event| {
+                let select = event.target_dyn_into::<HtmlSelectElement>().unwrap();
+                let mut new_props = cannons_props.deref().clone();
+                new_props[i].mode = match select.selected_index() {
+                    1 => Mode::continuous(100),
+                    2 => Mode::repeating_burst(50, 0.5),
+                    3 => Mode::manual(250),
+                    4 => Mode::hover(50, 0.5),
+                    5 => Mode::drag(60),
+                    6 => Mode::tap(50),
+                    _ => Mode::burst(250),
+                };
+                cannons_props.set(new_props);
+            })
+        };
+        let kinds = [
+            "burst",
+            "continuous",
+            "repeating",
+            "manual",
+            "hover",
+            "drag",
+            "tap",
+        ];
+        html! {<>
+            <tr>
+                <td>{format!("mode{i}:")}</td>
+                <td>
+                    <select style="color: black;" {onchange}>
+                        {for kinds.iter().enumerate().map(|(k, name)| html! {
+                            <option selected={k == selected}>{name}</option>
+                        })}
+                    </select>
+                </td>
+                <td></td>
+            </tr>
+            {mode_slider(format!("count{i}"), 0.0, 400.0, i, cannons_props.clone(), |kind| match kind {
+                ModeKind::Burst { count, .. }
+                | ModeKind::Repeating { count, .. }
+                | ModeKind::Manual { count }
+                | ModeKind::Hover { count, .. }
+                | ModeKind::Tap { count } => Some(count as f32),
+                ModeKind::Continuous { .. } | ModeKind::Drag { .. } => None,
+            }, |mode, count| mode.set_count(count as usize))}
+            {mode_slider(format!("rate{i}"), 0.0, 400.0, i, cannons_props.clone(), |kind| match kind {
+                ModeKind::Continuous { rate, .. } | ModeKind::Drag { rate } => Some(rate as f32),
+                _ => None,
+            }, |mode, rate| mode.set_rate(rate as usize))}
+            {mode_slider(format!("delay{i}"), 0.0, 2.0, i, cannons_props.clone(), |kind| match kind {
+                ModeKind::Burst { delay, .. }
+                | ModeKind::Continuous { delay, .. }
+                | ModeKind::Repeating { delay, .. } => Some(delay),
+                ModeKind::Manual { .. }
+                | ModeKind::Hover { .. }
+                | ModeKind::Drag { .. }
+                | ModeKind::Tap { .. } => None,
+            }, |mode, delay| mode.set_delay(delay))}
+            if let ModeKind::Continuous { duration, .. } = kind {
+                {checkbox_factory(&format!("finite{i}"), cannons_props.clone(), move |props| {
+                    matches!(props[i].mode.kind(), ModeKind::Continuous { duration: Some(_), .. })
+                }, move |props, finite| {
+                    props[i].mode.set_duration(finite.then_some(1.0));
+                })}
+                if duration.is_some() {
+                    {mode_slider(format!("duration{i}"), 0.1, 5.0, i, cannons_props.clone(), |kind| match kind {
+                        ModeKind::Continuous { duration, .. } => duration,
+                        _ => None,
+                    }, |mode, duration| mode.set_duration(Some(duration)))}
+                }
+            }
+            {mode_slider(format!("period{i}"), 0.05, 2.0, i, cannons_props.clone(), |kind| match kind {
+                ModeKind::Repeating { period, .. } => Some(period),
+                _ => None,
+            }, |mode, period| mode.set_period(period))}
+            {mode_slider(format!("cooldown{i}"), 0.0, 2.0, i, cannons_props.clone(), |kind| match kind {
+                ModeKind::Hover { cooldown, .. } => Some(cooldown),
+                _ => None,
+            }, |mode, cooldown| mode.set_cooldown(cooldown))}
+        </>}
+    }
+
+    fn pattern_factory(i: usize, cannons_props: UseStateHandle<Vec<CannonConfig>>) -> Html {
+        let patterns = [
+            Pattern::Cone,
+            Pattern::Ring,
+            Pattern::Fan,
+            Pattern::DoubleSided,
+        ];
+        let onchange = {
+            let cannons_props = cannons_props.clone();
+            Callback::from(move |event: Event| {
+                let select = event.target_dyn_into::<HtmlSelectElement>().unwrap();
+                if let Some(&pattern) = patterns.get(select.selected_index() as usize) {
+                    let mut new_props = cannons_props.deref().clone();
+                    new_props[i].pattern = pattern;
+                    cannons_props.set(new_props);
+                }
+            })
+        };
+        html! {
+            <tr>
+                <td>{format!("pattern{i}:")}</td>
+                <td>
+                    <select style="color: black;" {onchange}>
+                        {for patterns.iter().map(|&pattern| html! {
+                            <option selected={pattern == cannons_props[i].pattern}>
+                                {format!("{pattern:?}")}
+                            </option>
+                        })}
+                    </select>
+                </td>
+                <td></td>
+            </tr>
+        }
+    }
+
+    /// Weights of each shape, i.e. how many times it's repeated.
+    fn shapes_factory(
+        name: &str,
+        i: usize,
+        cannons_props: UseStateHandle<Vec<CannonConfig>>,
+    ) -> Html {
+        let inputs = [Shape::Circle, Shape::Square].into_iter().map(|shape| {
+            let weight = cannons_props[i]
+                .shapes
+                .iter()
+                .filter(|&&s| s == shape)
+                .count();
+            let cannons_props = cannons_props.clone();
+            let oninput = Callback::from(move |event: InputEvent| {
+                let input = event.target_dyn_into::<HtmlInputElement>().unwrap();
+                let Ok(weight) = usize::from_str(&input.value()) else {
+                    return;
+                };
+                let mut new_props = cannons_props.deref().clone();
+                let shapes = &mut new_props[i].shapes;
+                shapes.retain(|&s| s != shape);
+                shapes.extend(std::iter::repeat_n(shape, weight));
+                shapes.sort_by_key(|&s| s as u8);
+                cannons_props.set(new_props);
+            });
+            html! {
+                <label style="margin-right: 0.5rem;">
+                    {format!("{shape:?} ")}
+                    <input
+                        type="number"
+                        min="0"
+                        max="9"
+                        value={weight.to_string()}
+                        style="width: 3rem;"
+                        {oninput}
+                    />
+                </label>
+            }
+        });
+        html! {
+            <tr>
+                <td>{name}{":"}</td>
+                <td colspan="2">{for inputs}</td>
+            </tr>
+        }
+    }
+
     let style = format!(
         "background-color: black; width: {}px; height: {}px;",
         props.width, props.height
     );
 
-    let default_props = props!(ConfettiProps {});
-    let mut code = String::new();
-    write!(&mut code, "html! {{\n").unwrap();
-    write!(&mut code, "    <Confetti\n").unwrap();
-    macro_rules! prop {
-        ($code: ident, $props: ident, $defaults: ident, $prop: ident, $ident: literal, $show_defaults: ident) => {
-            if *$show_defaults || $props.$prop != $defaults.$prop {
-                write!(
-                    &mut $code,
-                    "{}        {}={{{}}}\n",
-                    $ident,
-                    stringify!($prop),
-                    $props.$prop
-                )
-                .unwrap();
+    let config = Rc::new(ConfettiConfig {
+        cannons: cannons_props.deref().clone(),
+        ..ConfettiConfig::from(props.deref())
+    });
+    use_effect_with(Rc::clone(&config), |config| share_config(config));
+
+    let json = use_state_eq(String::new);
+    let json_error = use_state_eq(|| None::<String>);
+    let on_json = {
+        let json = json.clone();
+        Callback::from(move |event: InputEvent| {
+            let textarea = event.target_dyn_into::<HtmlTextAreaElement>().unwrap();
+            json.set(textarea.value());
+        })
+    };
+    let on_export = {
+        let config = Rc::clone(&config);
+        let json = json.clone();
+        let json_error = json_error.clone();
+        Callback::from(move |_: MouseEvent| match config.to_json() {
+            Ok(compact) => {
+                json.set(pretty(&compact).unwrap_or(compact));
+                json_error.set(None);
             }
-        };
-    }
-    prop!(code, props, default_props, width, "", show_defaults);
-    prop!(code, props, default_props, height, "", show_defaults);
-    //prop!(code, props, default_props, count, "", show_defaults);
-    prop!(code, props, default_props, decay, "", show_defaults);
-    prop!(code, props, default_props, drift, "", show_defaults);
-    prop!(code, props, default_props, gravity, "", show_defaults);
-    prop!(code, props, default_props, lifespan, "", show_defaults);
-    prop!(code, props, default_props, scalar, "", show_defaults);
-    write!(&mut code, "        style={{{style:?}}}\n").unwrap();
-    write!(&mut code, "    >\n").unwrap();
-    for props in cannons_props.iter() {
-        let default_props = props!(CannonProps {});
-        write!(&mut code, "        <Cannon\n").unwrap();
-        prop!(code, props, default_props, x, "    ", show_defaults);
-        prop!(code, props, default_props, y, "    ", show_defaults);
-        prop!(code, props, default_props, angle, "    ", show_defaults);
-        prop!(code, props, default_props, spread, "    ", show_defaults);
-        prop!(code, props, default_props, velocity, "    ", show_defaults);
-        if *show_defaults || props.mode != default_props.mode {
-            write!(
-                &mut code,
-                "{}    {}={{{}}}\n",
-                "        ",
-                stringify!(mode),
-                match props.mode.impl_ref() {
-                    ModeImpl::Burst { count, delay: 0 } => format!("Mode::burst({count})"),
-                    ModeImpl::Burst { count, delay } =>
-                        format!("Mode::delayed_burst({count}, {:.3})", *delay as f32 * 0.001),
-                    ModeImpl::Continuous { rate, .. } => format!("Mode::continuous({rate})"),
+            Err(error) => json_error.set(Some(error.to_string())),
+        })
+    };
+    let on_import = {
+        let json = json.clone();
+        let json_error = json_error.clone();
+        let props = props.clone();
+        let cannons_props = cannons_props.clone();
+        Callback::from(
+            move |_: MouseEvent| match ConfettiConfig::from_json(&json) {
+                Ok(config) => {
+                    props.set(config_props(&config));
+                    cannons_props.set(config.cannons);
+                    json_error.set(None);
                 }
-            )
-            .unwrap();
-        }
-        write!(&mut code, "        />\n").unwrap();
+                Err(error) => json_error.set(Some(error.to_string())),
+            },
+        )
+    };
+    let on_preview_click = {
+        let confetti = confetti.clone();
+        let click_to_fire = *click_to_fire;
+        let cannon = cannons_props.first().cloned().unwrap_or_default();
+        Callback::from(move |event: MouseEvent| {
+            if !click_to_fire {
+                return;
+            }
+            let Some(preview) = event
+                .current_target()
+                .and_then(|target| target.dyn_into::<Element>().ok())
+            else {
+                return;
+            };
+            // Client pixels to normalized coordinates, where y points up.
+            let rect = preview.get_bounding_client_rect();
+            let x = (event.client_x() as f64 - rect.left()) / rect.width();
+            let y = 1.0 - (event.client_y() as f64 - rect.top()) / rect.height();
+            confetti.fire_at(
+                x as f32,
+                y as f32,
+                props!(CannonProps {
+                    angle: cannon.angle,
+                    spread: cannon.spread,
+                    velocity: cannon.velocity,
+                    mode: Mode::burst(cannon.mode.burst_count()),
+                }),
+            );
+        })
+    };
+
+    let code = ConfettiProps {
+        style: Some(style.clone().into()),
+        config: Some(Rc::clone(&config)),
+        ..props.deref().clone()
     }
-    write!(&mut code, "    </Confetti>\n").unwrap();
-    write!(&mut code, "}}\n").unwrap();
+    .to_html_snippet(&[], *show_defaults);
 
     html! {<>
         <h2 style="margin-top: 0;">{"yew_confetti"}</h2>
@@ -160,19 +525,37 @@ fn app() -> Html {
                 <a style="color: white;" href="https://docs.rs/yew_confetti/latest/yew_confetti">{"docs.rs"}</a>
             </div>
             <div style="display: flex; flex-direction: row; gap: 0.5rem;">
-                <Confetti
-                    key={*key}
-                    {style}
-                    ..props.deref().clone()
-                >
-                    {for cannons_props.deref().clone().into_iter().map(|props| html_nested!{
-                        <Cannon ..props/>
-                    })}
-                </Confetti>
+                if *fullscreen {
+                    // The overlay takes the handle, so Reset and Fire control it.
+                    <ConfettiOverlay handle={confetti} config={Some(config)}/>
+                    <button
+                        onclick={on_fullscreen.clone()}
+                        style="position: fixed; top: 0.5rem; right: 0.5rem; z-index: 1001; color: black;"
+                    >{"Exit full screen"}</button>
+                } else {
+                    <div
+                        onclick={on_preview_click}
+                        style={click_to_fire.then_some("cursor: crosshair;")}
+                    >
+                        <Confetti
+                            handle={confetti}
+                            style={format!("display: block; {style}")}
+                            config={Some(config)}
+                            {on_stats}
+                            ..props.deref().clone()
+                        />
+                    </div>
+                }
                 <pre style="min-width: 30rem;">
                     {code}
                 </pre>
             </div>
+            <div>
+                {format!(
+                    "{} particles, {:.0} fps, {:.2} ms per frame",
+                    stats.particles, stats.fps, stats.frame_time
+                )}
+            </div>
             <table style="border-spacing: 0.25rem; table-layout: fixed; border-collapse: separate; width: 40vw;">
                 {slider_factory("width", 64.0, 512.0, props.clone(), |props| props.width as f32, |props, width| {
                     props.width = width as u32;
@@ -184,7 +567,7 @@ fn app() -> Html {
                     let x = x as usize;
                     props.truncate(x);
                     while props.len() < x {
-                        props.push(props!(CannonProps{}));
+                        props.push(CannonConfig::default());
                     }
                 })}
                 {cannons_props.iter().enumerate().map(|(i, _)| html!{<>
@@ -203,49 +586,10 @@ fn app() -> Html {
                     {slider_factory(&format!("velocity{i}"), 0.1, 3.0, cannons_props.clone(), move |props| props[i].velocity, move |props, velocity| {
                         props[i].velocity = velocity;
                     })}
-                    {checkbox_factory(&format!("continuous{i}"), cannons_props.clone(), move |props| props[i].mode.is_continuous(), move |props, continuous| {
-                        props[i].mode = if continuous {
-                            Mode::continuous(100)
-                        } else {
-                            Mode::burst(250)
-                        };
-                    })}
-                    if cannons_props[i].mode.is_continuous() {
-                        {slider_factory(&format!("rate{i}"), 0.0, 400.0, cannons_props.clone(), move |props| -> f32 {
-                            if let ModeImpl::Continuous{rate, ..} = props[i].mode.impl_ref() {
-                                *rate as f32
-                            } else {
-                                0.0
-                            }
-                        }, move |props, value| {
-                            if let ModeImpl::Continuous{rate, ..} = props[i].mode.impl_mut() {
-                                *rate = value as u16;
-                            }
-                        })}
-                    } else {
-                        {slider_factory(&format!("count{i}"), 0.0, 400.0, cannons_props.clone(), move |props| -> f32 {
-                            if let ModeImpl::Burst{count, ..} = props[i].mode.impl_ref() {
-                                *count as f32
-                            } else {
-                                0.0
-                            }
-                        }, move |props, value| {
-                            if let ModeImpl::Burst{count, ..} = props[i].mode.impl_mut() {
-                                *count = value as usize;
-                            }
-                        })}
-                        {slider_factory(&format!("delay{i}"), 0.0, 2.0, cannons_props.clone(), move |props| -> f32 {
-                            if let ModeImpl::Burst{delay, ..} = props[i].mode.impl_ref() {
-                                *delay as f32 * 0.001
-                            } else {
-                                0.0
-                            }
-                        }, move |props, value| {
-                            if let ModeImpl::Burst{delay, ..} = props[i].mode.impl_mut() {
-                                *delay = (value * 1000.0) as u64;
-                            }
-                        })}
-                    }
+                    {pattern_factory(i, cannons_props.clone())}
+                    {shapes_factory(&format!("shapes{i}"), i, cannons_props.clone())}
+                    {colors_factory(&format!("colors{i}"), i, cannons_props.clone())}
+                    {mode_factory(i, cannons_props.clone())}
                 </>}).collect::<Html>()}
                 {slider_factory("decay", 0.01, 1.0, props.clone(), |props| props.decay, |props, decay| {
                     props.decay = decay;
@@ -262,16 +606,52 @@ fn app() -> Html {
                 {slider_factory("scalar", 0.1, 10.0, props.clone(), |props| props.scalar, |props, scalar| {
                     props.scalar = scalar;
                 })}
+                {checkbox_factory("debug", props.clone(), |props| props.debug, |props, debug| {
+                    props.debug = debug;
+                })}
+                {checkbox_factory("click_to_fire", click_to_fire.clone(), |props| *props, |props, click_to_fire| {
+                    *props = click_to_fire;
+                })}
                 {checkbox_factory("show_defaults", show_defaults.clone(), |props| *props, |props, continuous| {
                     *props = continuous;
                 })}
                 <tr>
-                    <td colspan="3"><button
-                        onclick={on_reset}
-                        style="color: black;"
-                    >{"Reset"}</button></td>
+                    <td colspan="3">
+                        <button
+                            onclick={on_reset}
+                            style="color: black;"
+                        >{"Reset"}</button>
+                        // Manual cannons only fire this way.
+                        <button
+                            onclick={on_fire}
+                            style="color: black; margin-left: 0.5rem;"
+                        >{"Fire"}</button>
+                        <button
+                            onclick={on_fullscreen}
+                            style="color: black; margin-left: 0.5rem;"
+                        >{"Full screen"}</button>
+                    </td>
                 </tr>
             </table>
+            <div style="display: flex; flex-direction: column; gap: 0.25rem;">
+                <textarea
+                    rows="8"
+                    placeholder="Configuration JSON"
+                    value={(*json).clone()}
+                    oninput={on_json}
+                    style="color: black; font-family: monospace;"
+                />
+                <div>
+                    <button onclick={on_export} style="color: black;">{"Export"}</button>
+                    <button
+                        onclick={on_import}
+                        style="color: black; margin-left: 0.5rem;"
+                    >{"Import"}</button>
+                    if let Some(error) = &*json_error {
+                        <span style="margin-left: 0.5rem; color: #ff5e7e;">{error}</span>
+                    }
+                </div>
+            </div>
         </div>
     </>}
 }
@@ -0,0 +1,159 @@
+//! Sounds played when cannons burst, see [`CannonProps::sound`](crate::CannonProps::sound).
+
+use js_sys::wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use js_sys::{ArrayBuffer, Reflect};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{
+    window, AddEventListenerOptions, AudioBuffer, AudioContext, AudioContextState,
+    OfflineAudioContext, Response,
+};
+use yew::AttrValue;
+
+/// Events that let a page start audio, per autoplay policies.
+const GESTURES: [&str; 3] = ["pointerdown", "keydown", "touchend"];
+
+/// A sound for [`CannonProps::sound`](crate::CannonProps::sound).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Sound {
+    /// Fetched and decoded when the cannon is added, and shared by cannons with the same URL.
+    Url(AttrValue),
+    /// Already decoded.
+    Buffer(AudioBuffer),
+}
+
+impl From<&'static str> for Sound {
+    fn from(url: &'static str) -> Self {
+        Self::Url(AttrValue::Static(url))
+    }
+}
+
+impl From<AudioBuffer> for Sound {
+    fn from(buffer: AudioBuffer) -> Self {
+        Self::Buffer(buffer)
+    }
+}
+
+#[derive(Default)]
+struct Audio {
+    /// Created after a user gesture, since browsers don't let a page start audio before one.
+    context: Option<AudioContext>,
+    /// Whether waiting for a user gesture.
+    listening: bool,
+    /// Decoded sounds by URL. `None` while loading, or if loading failed.
+    buffers: HashMap<AttrValue, Option<AudioBuffer>>,
+}
+
+thread_local! {
+    static AUDIO: RefCell<Audio> = RefCell::default();
+}
+
+/// Get ready to play `sound`.
+pub(crate) fn prepare(sound: &Sound) {
+    AUDIO.with_borrow_mut(|audio| {
+        audio.listen();
+        if let Sound::Url(url) = sound {
+            if !audio.buffers.contains_key(url) {
+                audio.buffers.insert(url.clone(), None);
+                spawn_local(load(url.clone()));
+            }
+        }
+    });
+}
+
+/// Play `sound` now, if the user has interacted with the page and it has loaded.
+pub(crate) fn play(sound: &Sound) {
+    // E.g. a sound fired with a handle, which wasn't prepared.
+    prepare(sound);
+    AUDIO.with_borrow(|audio| {
+        let Some(context) = &audio.context else {
+            return;
+        };
+        let buffer = match sound {
+            Sound::Url(url) => audio.buffers.get(url).and_then(Option::as_ref),
+            Sound::Buffer(buffer) => Some(buffer),
+        };
+        let Some(buffer) = buffer else {
+            return;
+        };
+        if context.state() == AudioContextState::Suspended {
+            // E.g. after an interruption on iOS.
+            let _ = context.resume();
+        }
+        if let Ok(source) = context.create_buffer_source() {
+            source.set_buffer(Some(buffer));
+            let _ = source.connect_with_audio_node(&context.destination());
+            let _ = source.start();
+        }
+    });
+}
+
+impl Audio {
+    /// Create the context after the next user gesture, or now if there has been one.
+    fn listen(&mut self) {
+        if self.context.is_some() || self.listening {
+            return;
+        }
+        let Some(window) = window() else {
+            return;
+        };
+        let active = Reflect::get(&window.navigator(), &"userActivation".into())
+            .and_then(|activation| Reflect::get(&activation, &"hasBeenActive".into()))
+            .is_ok_and(|active| active.is_truthy());
+        if active {
+            self.context = AudioContext::new().ok();
+            return;
+        }
+        self.listening = true;
+        let options = AddEventListenerOptions::new();
+        options.set_once(true);
+        options.set_passive(true);
+        for event in GESTURES {
+            let _ = window.add_event_listener_with_callback_and_add_event_listener_options(
+                event,
+                Closure::once_into_js(unlock).unchecked_ref(),
+                &options,
+            );
+        }
+    }
+}
+
+/// Create the context, in response to a user gesture.
+fn unlock() {
+    AUDIO.with_borrow_mut(|audio| {
+        if audio.context.is_none() {
+            audio.context = AudioContext::new().ok();
+        }
+    });
+}
+
+/// Fetch and decode the sound at `url`.
+async fn load(url: AttrValue) {
+    match fetch(&url).await {
+        Ok(buffer) => AUDIO.with_borrow_mut(|audio| {
+            audio.buffers.insert(url, Some(buffer));
+        }),
+        Err(error) => web_sys::console::warn_2(
+            &format!("yew_confetti: failed to load sound {url:?}").into(),
+            &error,
+        ),
+    }
+}
+
+async fn fetch(url: &str) -> Result<AudioBuffer, JsValue> {
+    let window = window().ok_or("no window")?;
+    let response: Response = JsFuture::from(window.fetch_with_str(url))
+        .await?
+        .dyn_into()?;
+    if !response.ok() {
+        return Err(format!("HTTP {}", response.status()).into());
+    }
+    let data: ArrayBuffer = JsFuture::from(response.array_buffer()?).await?.dyn_into()?;
+    // Decoding doesn't need a user gesture, unlike playing, and buffers work in any context.
+    let decoder =
+        OfflineAudioContext::new_with_number_of_channels_and_length_and_sample_rate(1, 1, 44100.0)?;
+    JsFuture::from(decoder.decode_audio_data(&data)?)
+        .await?
+        .dyn_into()
+}
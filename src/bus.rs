@@ -0,0 +1,78 @@
+//! Global dispatcher, so code outside of components, such as API response or websocket
+//! handlers, can fire confetti on every mounted [`ConfettiProvider`](crate::ConfettiProvider).
+//!
+//! Up to [`MAX_QUEUED`] requests made while no provider is mounted are delivered to the next
+//! one that mounts, dropping the oldest.
+//! The bus is per-thread, so requests from web workers must be forwarded to the main thread.
+
+use crate::{CannonProps, FireRequest};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// Most requests kept while no provider is mounted, so a page without one doesn't leak them.
+pub const MAX_QUEUED: usize = 8;
+
+type Subscriber = Rc<dyn Fn(FireRequest)>;
+
+#[derive(Default)]
+struct Bus {
+    next_id: u64,
+    subscribers: Vec<(u64, Subscriber)>,
+    queued: VecDeque<FireRequest>,
+}
+
+thread_local! {
+    static BUS: RefCell<Bus> = RefCell::default();
+}
+
+fn dispatch(request: FireRequest) {
+    let subscribers = BUS.with_borrow_mut(|bus| {
+        if bus.subscribers.is_empty() {
+            if bus.queued.len() == MAX_QUEUED {
+                bus.queued.pop_front();
+            }
+            bus.queued.push_back(request.clone());
+        }
+        bus.subscribers
+            .iter()
+            .map(|(_, subscriber)| Rc::clone(subscriber))
+            .collect::<Vec<_>>()
+    });
+    for subscriber in subscribers {
+        subscriber(request.clone());
+    }
+}
+
+/// Fire the [`ConfettiProviderProps::cannon`](crate::ConfettiProviderProps::cannon) of every
+/// mounted [`ConfettiProvider`](crate::ConfettiProvider).
+pub fn fire() {
+    dispatch(FireRequest::All);
+}
+
+/// Fire a custom cannon on every mounted [`ConfettiProvider`](crate::ConfettiProvider).
+pub fn fire_with(cannon: CannonProps) {
+    dispatch(FireRequest::With(Box::new(cannon)));
+}
+
+/// Unsubscribes when dropped.
+pub(crate) struct Subscription(u64);
+
+pub(crate) fn subscribe(subscriber: impl Fn(FireRequest) + 'static) -> Subscription {
+    let (id, queued) = BUS.with_borrow_mut(|bus| {
+        let id = bus.next_id;
+        bus.next_id += 1;
+        (id, std::mem::take(&mut bus.queued))
+    });
+    for request in queued {
+        subscriber(request);
+    }
+    BUS.with_borrow_mut(|bus| bus.subscribers.push((id, Rc::new(subscriber))));
+    Subscription(id)
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        BUS.with_borrow_mut(|bus| bus.subscribers.retain(|(id, _)| *id != self.0));
+    }
+}
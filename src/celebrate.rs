@@ -0,0 +1,80 @@
+use crate::{Cannon, CannonProps, ConfettiOverlay, Mode};
+use js_sys::wasm_bindgen::JsValue;
+use web_sys::{window, Element};
+use yew::{
+    function_component, html, props, use_effect_with, use_mut_ref, use_node_ref, use_state_eq,
+    Callback, Children, Html, Properties,
+};
+
+/// Success-state wrapper options.
+#[derive(Clone, PartialEq, Properties)]
+pub struct CelebrateProps {
+    /// Whether to celebrate, e.g. once a form is submitted. Only the first time counts.
+    pub when: bool,
+    /// Cannon fired from the center of the children, ignoring its `x` and `y`. Its mode should
+    /// finish, like a burst, or the overlay is never removed.
+    #[prop_or_else(burst)]
+    pub cannon: CannonProps,
+    /// Stacking order of the overlay canvas.
+    #[prop_or(1000)]
+    pub z_index: i32,
+    /// Wrapped in an inline block, which the burst is centered on.
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// A one-shot burst.
+pub(crate) fn burst() -> CannonProps {
+    props!(CannonProps {
+        mode: Mode::burst(100),
+    })
+}
+
+/// Bursts from its children the first time [`CelebrateProps::when`] is true, on a full-window
+/// overlay that only exists until the particles expire.
+#[function_component(Celebrate)]
+pub fn celebrate(props: &CelebrateProps) -> Html {
+    let node = use_node_ref();
+    let celebrated = use_mut_ref(|| false);
+    // Where the burst comes from, in normalized window coordinates, while celebrating.
+    let origin = use_state_eq(|| None::<(f32, f32)>);
+
+    {
+        let node = node.clone();
+        let origin = origin.clone();
+        use_effect_with(props.when, move |&when| {
+            if !when || celebrated.replace(true) {
+                return;
+            }
+            let Some((element, window)) = node.cast::<Element>().zip(window()) else {
+                return;
+            };
+            let size = |value: Result<_, _>| value.ok().and_then(|v: JsValue| v.as_f64());
+            let (Some(width), Some(height)) =
+                (size(window.inner_width()), size(window.inner_height()))
+            else {
+                return;
+            };
+            let rect = element.get_bounding_client_rect();
+            let x = (rect.left() + rect.width() * 0.5) / width;
+            let y = 1.0 - (rect.top() + rect.height() * 0.5) / height;
+            origin.set(Some((x as f32, y as f32)));
+        });
+    }
+
+    let on_complete = {
+        let origin = origin.clone();
+        Callback::from(move |_| origin.set(None))
+    };
+
+    html! {
+        <div ref={node} style="display: inline-block;">
+            {props.children.clone()}
+            if let Some((x, y)) = *origin {
+                <ConfettiOverlay z_index={props.z_index} {on_complete}>
+                    <Cannon {x} {y} ..props.cannon.clone()/>
+                </ConfettiOverlay>
+            }
+        </div>
+    }
+}
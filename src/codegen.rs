@@ -0,0 +1,388 @@
+//! Rust source for props, e.g. for configurators that let users copy what they tuned.
+
+use crate::{CannonConfig, CannonProps, ConfettiProps, Easing, Keyframe, Mode, ModeKind, Shape};
+use std::fmt::Write;
+use yew::props;
+
+/// Collects `name={value}` lines for props that differ from their defaults.
+struct Props {
+    show_defaults: bool,
+    lines: Vec<String>,
+}
+
+impl Props {
+    fn push<T: PartialEq>(
+        &mut self,
+        name: &str,
+        value: &T,
+        default: &T,
+        expr: impl Fn(&T) -> String,
+    ) {
+        if self.show_defaults || value != default {
+            self.lines.push(format!("{name}={{{}}}", expr(value)));
+        }
+    }
+
+    /// Write an element called `tag`, with `children` (lines) if any.
+    fn write(self, code: &mut String, indent: &str, tag: &str, children: &[String]) {
+        let close = if children.is_empty() { "/>" } else { ">" };
+        if self.lines.is_empty() {
+            let _ = writeln!(code, "{indent}<{tag}{close}");
+        } else {
+            let _ = writeln!(code, "{indent}<{tag}");
+            for line in self.lines {
+                let _ = writeln!(code, "{indent}    {line}");
+            }
+            let _ = writeln!(code, "{indent}{close}");
+        }
+        if !children.is_empty() {
+            for child in children {
+                code.push_str(child);
+            }
+            let _ = writeln!(code, "{indent}</{tag}>");
+        }
+    }
+}
+
+fn debug<T: std::fmt::Debug>(value: &T) -> String {
+    format!("{value:?}")
+}
+
+/// The [`Mode`] constructor call that creates `mode`.
+pub(crate) fn mode_expr(mode: &Mode) -> String {
+    match mode.kind() {
+        ModeKind::Burst { count, delay: 0.0 } => format!("Mode::burst({count})"),
+        ModeKind::Burst { count, delay } => format!("Mode::delayed_burst({count}, {delay:?})"),
+        ModeKind::Continuous {
+            rate,
+            delay,
+            duration,
+        } => match (delay == 0.0, duration) {
+            (true, None) => format!("Mode::continuous({rate})"),
+            (false, None) => format!("Mode::delayed_continuous({rate}, {delay:?})"),
+            (true, Some(duration)) => format!("Mode::finite_continuous({rate}, {duration:?})"),
+            (false, Some(duration)) => {
+                format!("Mode::delayed_finite_continuous({rate}, {delay:?}, {duration:?})")
+            }
+        },
+        ModeKind::Repeating {
+            count,
+            delay: 0.0,
+            period,
+        } => format!("Mode::repeating_burst({count}, {period:?})"),
+        ModeKind::Repeating {
+            count,
+            delay,
+            period,
+        } => format!("Mode::delayed_repeating_burst({count}, {delay:?}, {period:?})"),
+        ModeKind::Manual { count } => format!("Mode::manual({count})"),
+        ModeKind::Hover { count, cooldown } => format!("Mode::hover({count}, {cooldown:?})"),
+        ModeKind::Drag { rate } => format!("Mode::drag({rate})"),
+        ModeKind::Tap { count } => format!("Mode::tap({count})"),
+    }
+}
+
+/// A Rust expression that evaluates to `keyframe`.
+fn keyframe_expr(keyframe: &Keyframe) -> String {
+    let Keyframe {
+        track,
+        time,
+        value,
+        easing,
+    } = keyframe;
+    let mut expr = format!("Keyframe::new(Track::{track:?}, {time:?}, {value:?})");
+    if *easing != Easing::default() {
+        write!(expr, ".easing(Easing::{easing:?})").unwrap();
+    }
+    expr
+}
+
+impl ConfettiProps {
+    /// An `html!` invocation of a [`Confetti`](crate::Confetti) with these props and `cannons`,
+    /// omitting props equal to their defaults unless `show_defaults`. Callbacks, handles, and
+    /// other props that aren't plain data are left out. [`ConfettiProps::config`] is inlined,
+    /// with its cannons after `cannons`.
+    pub fn to_html_snippet(&self, cannons: &[CannonProps], show_defaults: bool) -> String {
+        let cannons = cannons
+            .iter()
+            .map(CannonConfig::from)
+            .chain(self.config_cannons().iter().cloned())
+            .collect::<Vec<_>>();
+        self.configured().snippet(&cannons, show_defaults)
+    }
+
+    fn snippet(&self, cannons: &[CannonConfig], show_defaults: bool) -> String {
+        let defaults = props!(ConfettiProps {});
+        let mut props = Props {
+            show_defaults,
+            lines: Vec::new(),
+        };
+        props.push("width", &self.width, &defaults.width, debug);
+        props.push("height", &self.height, &defaults.height, debug);
+        props.push("auto_size", &self.auto_size, &defaults.auto_size, debug);
+        props.push("decay", &self.decay, &defaults.decay, debug);
+        props.push("gravity", &self.gravity, &defaults.gravity, debug);
+        props.push("drift", &self.drift, &defaults.drift, debug);
+        props.push("lifespan", &self.lifespan, &defaults.lifespan, debug);
+        props.push("physics", &self.physics, &defaults.physics, debug);
+        props.push("units", &self.units, &defaults.units, |u| {
+            format!("Units::{u:?}")
+        });
+        props.push(
+            "aspect_correct",
+            &self.aspect_correct,
+            &defaults.aspect_correct,
+            debug,
+        );
+        props.push("transform", &self.transform, &defaults.transform, debug);
+        props.push(
+            "disable_for_reduced_motion",
+            &self.disable_for_reduced_motion,
+            &defaults.disable_for_reduced_motion,
+            debug,
+        );
+        props.push(
+            "reduced_motion",
+            &self.reduced_motion,
+            &defaults.reduced_motion,
+            |r| format!("ReducedMotion::{r:?}"),
+        );
+        props.push("scalar", &self.scalar, &defaults.scalar, debug);
+        props.push(
+            "density_reference",
+            &self.density_reference,
+            &defaults.density_reference,
+            debug,
+        );
+        props.push("seed", &self.seed, &defaults.seed, debug);
+        props.push(
+            "max_particles",
+            &self.max_particles,
+            &defaults.max_particles,
+            debug,
+        );
+        props.push("eviction", &self.eviction, &defaults.eviction, |e| {
+            format!("Eviction::{e:?}")
+        });
+        props.push("fps_cap", &self.fps_cap, &defaults.fps_cap, debug);
+        props.push(
+            "emit_on_visible",
+            &self.emit_on_visible,
+            &defaults.emit_on_visible,
+            debug,
+        );
+        props.push(
+            "pause_offscreen",
+            &self.pause_offscreen,
+            &defaults.pause_offscreen,
+            debug,
+        );
+        props.push(
+            "hidden_policy",
+            &self.hidden_policy,
+            &defaults.hidden_policy,
+            |p| format!("HiddenPolicy::{p:?}"),
+        );
+        props.push("dismiss_on", &self.dismiss_on, &defaults.dismiss_on, |d| {
+            format!("DismissOn::{d:?}")
+        });
+        props.push(
+            "power_saving",
+            &self.power_saving,
+            &defaults.power_saving,
+            |p| format!("PowerSaving::{p:?}"),
+        );
+        props.push("active", &self.active, &defaults.active, debug);
+        props.push("paused", &self.paused, &defaults.paused, debug);
+        props.push("portal", &self.portal, &defaults.portal, debug);
+        props.push("debug", &self.debug, &defaults.debug, debug);
+        for (name, value) in [("id", &self.id), ("style", &self.style)] {
+            if let Some(value) = value {
+                props.lines.push(format!("{name}={{{:?}}}", value.as_str()));
+            }
+        }
+
+        let cannons = cannons
+            .iter()
+            .map(|cannon| cannon.to_html_snippet(show_defaults))
+            .collect::<Vec<_>>();
+        let mut code = String::from("html! {\n");
+        props.write(&mut code, "    ", "Confetti", &cannons);
+        code.push_str("}\n");
+        code
+    }
+}
+
+impl CannonConfig {
+    /// A `<Cannon/>` with these props, indented for [`ConfettiProps::to_html_snippet`].
+    fn to_html_snippet(&self, show_defaults: bool) -> String {
+        let defaults = CannonConfig::default();
+        let mut props = Props {
+            show_defaults,
+            lines: Vec::new(),
+        };
+        props.push("x", &self.x, &defaults.x, debug);
+        props.push("y", &self.y, &defaults.y, debug);
+        props.push("path", &self.path, &defaults.path, |path| {
+            format!("&{path:?} as &[_]")
+        });
+        props.push("angle", &self.angle, &defaults.angle, debug);
+        props.push("spread", &self.spread, &defaults.spread, debug);
+        props.push("pattern", &self.pattern, &defaults.pattern, |p| {
+            format!("Pattern::{p:?}")
+        });
+        props.push("velocity", &self.velocity, &defaults.velocity, debug);
+        props.push("shapes", &self.shapes, &defaults.shapes, |shapes| {
+            let shapes = shapes
+                .iter()
+                .map(|shape: &Shape| format!("Shape::{shape:?}"))
+                .collect::<Vec<_>>();
+            format!("&[{}] as &[_]", shapes.join(", "))
+        });
+        props.push("colors", &self.colors, &defaults.colors, |colors| {
+            let colors = colors.iter().map(|c| c.as_str()).collect::<Vec<_>>();
+            format!("&{colors:?} as &[_]")
+        });
+        props.push("mode", &self.mode, &defaults.mode, mode_expr);
+        props.push(
+            "keyframes",
+            &self.keyframes,
+            &defaults.keyframes,
+            |keyframes| {
+                let keyframes = keyframes.iter().map(keyframe_expr).collect::<Vec<_>>();
+                format!("&[{}] as &[_]", keyframes.join(", "))
+            },
+        );
+        props.push(
+            "max_particles",
+            &self.max_particles,
+            &defaults.max_particles,
+            debug,
+        );
+        if let Some(group) = &self.group {
+            props.lines.push(format!("group={{{:?}}}", group.as_str()));
+        }
+        let mut code = String::new();
+        props.write(&mut code, "        ", "Cannon", &[]);
+        code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mode_expr;
+    use crate::{CannonConfig, CannonProps, ConfettiConfig, ConfettiProps, Mode, Shape};
+    use std::rc::Rc;
+    use yew::props;
+
+    #[test]
+    fn modes() {
+        for (mode, expr) in [
+            (Mode::burst(10), "Mode::burst(10)"),
+            (
+                Mode::delayed_burst(10, 0.25),
+                "Mode::delayed_burst(10, 0.25)",
+            ),
+            (Mode::continuous(100), "Mode::continuous(100)"),
+            (
+                Mode::delayed_continuous(100, 1.5),
+                "Mode::delayed_continuous(100, 1.5)",
+            ),
+            (
+                Mode::finite_continuous(100, 2.0),
+                "Mode::finite_continuous(100, 2.0)",
+            ),
+            (
+                Mode::delayed_finite_continuous(100, 0.5, 1.1),
+                "Mode::delayed_finite_continuous(100, 0.5, 1.1)",
+            ),
+            (
+                Mode::repeating_burst(5, 0.3),
+                "Mode::repeating_burst(5, 0.3)",
+            ),
+            (
+                Mode::delayed_repeating_burst(5, 1.0, 0.3),
+                "Mode::delayed_repeating_burst(5, 1.0, 0.3)",
+            ),
+            (Mode::manual(200), "Mode::manual(200)"),
+            (Mode::hover(30, 0.5), "Mode::hover(30, 0.5)"),
+            (Mode::drag(60), "Mode::drag(60)"),
+            (Mode::tap(40), "Mode::tap(40)"),
+        ] {
+            assert_eq!(mode_expr(&mode), expr);
+        }
+    }
+
+    #[test]
+    fn defaults_are_omitted() {
+        let code = props!(ConfettiProps {}).to_html_snippet(&[props!(CannonProps {})], false);
+        assert_eq!(
+            code,
+            "html! {\n    <Confetti>\n        <Cannon/>\n    </Confetti>\n}\n"
+        );
+        let code = props!(ConfettiProps {}).to_html_snippet(&[], true);
+        assert!(code.contains("        decay={0.3}\n"));
+        assert!(code.contains("        gravity={1.0}\n"));
+    }
+
+    #[test]
+    fn changed_props() {
+        let props = props!(ConfettiProps {
+            width: 300,
+            style: "background: black;",
+        });
+        let cannon = CannonProps {
+            x: 0.25,
+            shapes: &[Shape::Square],
+            colors: &["red", "#00ff00"],
+            mode: Mode::burst(50),
+            ..props!(CannonProps {})
+        };
+        assert_eq!(
+            props.to_html_snippet(&[cannon], false),
+            r##"html! {
+    <Confetti
+        width={300}
+        style={"background: black;"}
+    >
+        <Cannon
+            x={0.25}
+            shapes={&[Shape::Square] as &[_]}
+            colors={&["red", "#00ff00"] as &[_]}
+            mode={Mode::burst(50)}
+        />
+    </Confetti>
+}
+"##
+        );
+    }
+
+    #[test]
+    fn config_is_inlined() {
+        let config = ConfettiConfig {
+            width: 300,
+            cannons: vec![CannonConfig {
+                colors: vec!["#123456".into()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let props = props!(ConfettiProps {
+            config: Some(Rc::new(config)),
+        });
+        assert_eq!(
+            props.to_html_snippet(&[props!(CannonProps {})], false),
+            r##"html! {
+    <Confetti
+        width={300}
+    >
+        <Cannon/>
+        <Cannon
+            colors={&["#123456"] as &[_]}
+        />
+    </Confetti>
+}
+"##
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use crate::render::Appearance;
+use crate::{
+    CannonGroup, DismissOn, Emitter, Eviction, HiddenPolicy, Keyframe, Mode, Pattern, Physics,
+    PowerSaving, ReducedMotion, Shape, Transform, Units,
+};
+#[cfg(feature = "yew")]
+use crate::{CannonProps, ConfettiProps};
+use implicit_clone::unsync::IString;
+
+/// Shapes of a cannon by default.
+pub(crate) const DEFAULT_SHAPES: &[Shape] = &[Shape::Circle, Shape::Square];
+
+/// Colors of a cannon by default.
+pub(crate) const DEFAULT_COLORS: &[&str] = &[
+    "#26ccff", "#a25afd", "#ff5e7e", "#88ff5a", "#fcff42", "#ffa62d", "#ff36ff",
+];
+
+/// Data-only equivalent of [`ConfettiProps`](crate::ConfettiProps), e.g. to store or send a celebration. With the
+/// `serde` feature, it is serializable, and missing fields take their defaults.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ConfettiConfig {
+    pub width: u32,
+    pub height: u32,
+    pub auto_size: bool,
+    pub decay: f32,
+    pub gravity: f32,
+    pub drift: f32,
+    pub lifespan: f32,
+    pub physics: Option<Physics>,
+    pub units: Units,
+    pub aspect_correct: bool,
+    pub transform: Option<Transform>,
+    pub disable_for_reduced_motion: bool,
+    pub reduced_motion: ReducedMotion,
+    pub scalar: f32,
+    pub density_reference: Option<(u32, u32)>,
+    pub seed: Option<u64>,
+    pub max_particles: usize,
+    pub eviction: Eviction,
+    pub fps_cap: Option<u16>,
+    pub emit_on_visible: Option<f32>,
+    pub pause_offscreen: bool,
+    pub hidden_policy: HiddenPolicy,
+    pub dismiss_on: DismissOn,
+    pub power_saving: PowerSaving,
+    pub groups: Vec<CannonGroup>,
+    /// Equivalent of [`ConfettiProps::children`](crate::ConfettiProps::children).
+    pub cannons: Vec<CannonConfig>,
+}
+
+/// The defaults of [`ConfettiProps`](crate::ConfettiProps).
+impl Default for ConfettiConfig {
+    fn default() -> Self {
+        Self {
+            width: 256,
+            height: 256,
+            auto_size: false,
+            decay: 0.3,
+            gravity: 1.0,
+            drift: 0.0,
+            lifespan: 2.5,
+            physics: None,
+            units: Units::default(),
+            aspect_correct: false,
+            transform: None,
+            disable_for_reduced_motion: true,
+            reduced_motion: ReducedMotion::default(),
+            scalar: 5.0,
+            density_reference: None,
+            seed: None,
+            max_particles: 10_000,
+            eviction: Eviction::default(),
+            fps_cap: None,
+            emit_on_visible: None,
+            pause_offscreen: true,
+            hidden_policy: HiddenPolicy::default(),
+            dismiss_on: DismissOn::default(),
+            power_saving: PowerSaving::default(),
+            groups: Vec::new(),
+            cannons: Vec::new(),
+        }
+    }
+}
+
+impl ConfettiConfig {
+    /// Parse a configuration, e.g. from a CMS, in the format of its `Serialize` implementation.
+    /// Missing fields take their defaults.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Serialize the config, in the format that [`ConfettiConfig::from_json`] parses, e.g. to
+    /// store it.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// The response to reduced motion, given whether the user `prefers` it.
+    pub(crate) fn reduced_motion_response(&self, prefers: bool) -> ReducedMotion {
+        if prefers && self.disable_for_reduced_motion {
+            self.reduced_motion
+        } else {
+            ReducedMotion::Ignore
+        }
+    }
+
+    /// [`ConfettiConfig::physics`], or the individual fields, clamped.
+    pub(crate) fn clamped_physics(&self) -> Physics {
+        let mut physics = self.physics.unwrap_or(Physics {
+            decay: self.decay,
+            gravity: self.gravity,
+            drift: self.drift,
+            lifespan: self.lifespan,
+        });
+        physics.clamp();
+        physics
+    }
+
+    /// What renderers need of the config.
+    pub(crate) fn appearance(&self) -> Appearance<'_> {
+        Appearance {
+            lifespan: self.clamped_physics().lifespan,
+            scalar: self.scalar,
+            transform: self.transform.map(|mut transform| {
+                transform.clamp();
+                transform
+            }),
+            mask: None,
+        }
+    }
+
+    /// Replace the fields of `props` with those of the config, except for cannons.
+    #[cfg(feature = "yew")]
+    pub(crate) fn apply(&self, props: &mut ConfettiProps) {
+        props.width = self.width;
+        props.height = self.height;
+        props.auto_size = self.auto_size;
+        props.decay = self.decay;
+        props.gravity = self.gravity;
+        props.drift = self.drift;
+        props.lifespan = self.lifespan;
+        props.physics = self.physics;
+        props.units = self.units;
+        props.aspect_correct = self.aspect_correct;
+        props.transform = self.transform;
+        props.disable_for_reduced_motion = self.disable_for_reduced_motion;
+        props.reduced_motion = self.reduced_motion;
+        props.scalar = self.scalar;
+        props.density_reference = self.density_reference;
+        props.seed = self.seed;
+        props.max_particles = self.max_particles;
+        props.eviction = self.eviction;
+        props.fps_cap = self.fps_cap;
+        props.emit_on_visible = self.emit_on_visible;
+        props.pause_offscreen = self.pause_offscreen;
+        props.hidden_policy = self.hidden_policy;
+        props.dismiss_on = self.dismiss_on;
+        props.power_saving = self.power_saving;
+        props.groups = self.groups.clone();
+    }
+}
+
+#[cfg(feature = "yew")]
+impl From<&ConfettiProps> for ConfettiConfig {
+    fn from(props: &ConfettiProps) -> Self {
+        Self {
+            width: props.width,
+            height: props.height,
+            auto_size: props.auto_size,
+            decay: props.decay,
+            gravity: props.gravity,
+            drift: props.drift,
+            lifespan: props.lifespan,
+            physics: props.physics,
+            units: props.units,
+            aspect_correct: props.aspect_correct,
+            transform: props.transform,
+            disable_for_reduced_motion: props.disable_for_reduced_motion,
+            reduced_motion: props.reduced_motion,
+            scalar: props.scalar,
+            density_reference: props.density_reference,
+            seed: props.seed,
+            max_particles: props.max_particles,
+            eviction: props.eviction,
+            fps_cap: props.fps_cap,
+            emit_on_visible: props.emit_on_visible,
+            pause_offscreen: props.pause_offscreen,
+            hidden_policy: props.hidden_policy,
+            dismiss_on: props.dismiss_on,
+            power_saving: props.power_saving,
+            groups: props.groups.clone(),
+            cannons: props
+                .children
+                .iter()
+                .map(|cannon| CannonConfig::from(&*cannon.props))
+                .collect(),
+        }
+    }
+}
+
+/// Data-only equivalent of [`CannonProps`](crate::CannonProps), with owned shapes and colors.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct CannonConfig {
+    pub x: f32,
+    pub y: f32,
+    pub path: Vec<(f32, f32)>,
+    pub angle: f32,
+    pub spread: f32,
+    pub pattern: Pattern,
+    pub velocity: f32,
+    pub shapes: Vec<Shape>,
+    pub colors: Vec<IString>,
+    pub mode: Mode,
+    pub keyframes: Vec<Keyframe>,
+    pub max_particles: Option<usize>,
+    pub group: Option<IString>,
+}
+
+impl CannonConfig {
+    /// The cannon, ignoring [`CannonConfig::group`].
+    pub(crate) fn emitter(&self) -> Emitter {
+        Emitter {
+            x: self.x,
+            y: self.y,
+            path: self.path.iter().copied().collect(),
+            angle: self.angle,
+            spread: self.spread,
+            pattern: self.pattern,
+            velocity: self.velocity,
+            shapes: self.shapes.iter().copied().collect(),
+            colors: self.colors.iter().cloned().collect(),
+            mode: self.mode,
+            keyframes: self.keyframes.iter().copied().collect(),
+            max_particles: self.max_particles,
+        }
+    }
+}
+
+/// The defaults of [`CannonProps`](crate::CannonProps).
+impl Default for CannonConfig {
+    fn default() -> Self {
+        Self {
+            x: 0.5,
+            y: 0.5,
+            path: Vec::new(),
+            angle: 90f32.to_radians(),
+            spread: 45f32.to_radians(),
+            pattern: Pattern::default(),
+            velocity: 2.0,
+            shapes: DEFAULT_SHAPES.to_vec(),
+            colors: DEFAULT_COLORS.iter().map(|&c| IString::Static(c)).collect(),
+            mode: Mode::default(),
+            keyframes: Vec::new(),
+            max_particles: None,
+            group: None,
+        }
+    }
+}
+
+#[cfg(feature = "yew")]
+impl From<&CannonProps> for CannonConfig {
+    fn from(props: &CannonProps) -> Self {
+        Self {
+            x: props.x,
+            y: props.y,
+            path: props.path.to_vec(),
+            angle: props.angle,
+            spread: props.spread,
+            pattern: props.pattern,
+            velocity: props.velocity,
+            shapes: props.shapes.to_vec(),
+            colors: props.colors.iter().map(|&c| IString::Static(c)).collect(),
+            mode: props.mode,
+            keyframes: props.keyframes.to_vec(),
+            max_particles: props.max_particles,
+            group: props.group.clone(),
+        }
+    }
+}
+
+/// Serialized as a [`CannonConfig`], without [`CannonProps::on_burst`]. Deserialize a
+/// [`CannonConfig`] instead, since the props borrow their shapes and colors statically.
+#[cfg(all(feature = "serde", feature = "yew"))]
+impl serde::Serialize for CannonProps {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CannonConfig::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(all(test, any(feature = "serde", feature = "yew")))]
+mod tests {
+    use super::{CannonConfig, ConfettiConfig};
+
+    #[test]
+    #[cfg(feature = "yew")]
+    fn defaults_match_props() {
+        use crate::{CannonProps, ConfettiProps};
+        use yew::props;
+
+        assert_eq!(
+            ConfettiConfig::default(),
+            ConfettiConfig::from(&props!(ConfettiProps {}))
+        );
+        assert_eq!(
+            CannonConfig::default(),
+            CannonConfig::from(&props!(CannonProps {}))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn json_round_trip() {
+        use crate::Mode;
+
+        let config = ConfettiConfig {
+            width: 300,
+            cannons: vec![CannonConfig {
+                colors: vec!["#123456".into()],
+                mode: Mode::delayed_finite_continuous(100, 0.5, 1.5),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let json = config.to_json().unwrap();
+        assert_eq!(ConfettiConfig::from_json(&json).unwrap(), config);
+    }
+}
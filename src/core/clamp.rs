@@ -0,0 +1,188 @@
+//! The valid domain of physics and emitters. Values from props, configs, and deserialization
+//! are clamped into it when ingested, so the simulation never sees them. In debug builds,
+//! `validate` warns about the values before they're clamped.
+//!
+//! | Value | Domain | Otherwise |
+//! |-------|--------|-----------|
+//! | decay | `(0, 1]` | nearest bound, 1 if NaN |
+//! | lifespan | at least [`MIN_LIFESPAN`] | [`MIN_LIFESPAN`] |
+//! | gravity, drift | finite | 0 |
+//! | x, y, angle, velocity | finite | default |
+//! | path points | finite | removed |
+//! | keyframe times and values | finite | removed |
+//! | spread | `0..=TAU` | nearest bound |
+//! | continuous and drag rate | at most [`MAX_RATE`] | [`MAX_RATE`] |
+//! | repeating period | at least a millisecond | a millisecond |
+//! | transform scale | finite, at least [`MIN_SCALE`] in magnitude | 1, or [`MIN_SCALE`] |
+//! | transform rotation and translation | finite | 0 |
+
+use super::{Emitter, Mode, ModeImpl, Physics, Transform};
+use std::f32::consts::TAU;
+
+/// Shortest lifespan, in seconds.
+pub(crate) const MIN_LIFESPAN: f32 = 0.001;
+/// Most particles per second of a continuous mode.
+pub(crate) const MAX_RATE: u16 = 1000;
+
+/// Smallest magnitude of a transform's scale, so it can be inverted.
+pub(crate) const MIN_SCALE: f32 = 0.001;
+
+/// `value` if finite, otherwise `default`.
+fn finite(value: f32, default: f32) -> f32 {
+    if value.is_finite() {
+        value
+    } else {
+        default
+    }
+}
+
+impl Physics {
+    /// Clamp into the valid domain.
+    pub(crate) fn clamp(&mut self) {
+        // `f32::clamp` keeps NaN.
+        self.decay = finite(self.decay, 1.0).clamp(f32::MIN_POSITIVE, 1.0);
+        self.lifespan = finite(self.lifespan, MIN_LIFESPAN).max(MIN_LIFESPAN);
+        self.gravity = finite(self.gravity, 0.0);
+        self.drift = finite(self.drift, 0.0);
+    }
+}
+
+impl Transform {
+    /// Clamp into the valid domain.
+    pub(crate) fn clamp(&mut self) {
+        self.scale = finite(self.scale, 1.0);
+        if self.scale.abs() < MIN_SCALE {
+            self.scale = MIN_SCALE.copysign(self.scale);
+        }
+        self.rotate = finite(self.rotate, 0.0);
+        self.translate = (finite(self.translate.0, 0.0), finite(self.translate.1, 0.0));
+    }
+}
+
+impl Emitter {
+    /// Clamp into the valid domain.
+    pub(crate) fn clamp(&mut self) {
+        let default = Self::default();
+        self.x = finite(self.x, default.x);
+        self.y = finite(self.y, default.y);
+        if self
+            .path
+            .iter()
+            .any(|(x, y)| !x.is_finite() || !y.is_finite())
+        {
+            self.path = self
+                .path
+                .iter()
+                .filter(|(x, y)| x.is_finite() && y.is_finite())
+                .copied()
+                .collect();
+        }
+        self.angle = finite(self.angle, default.angle);
+        self.spread = finite(self.spread, default.spread).clamp(0.0, TAU);
+        self.velocity = finite(self.velocity, default.velocity);
+        self.mode.clamp();
+        if self
+            .keyframes
+            .iter()
+            .any(|k| !k.time.is_finite() || !k.value.is_finite())
+        {
+            self.keyframes = self
+                .keyframes
+                .iter()
+                .filter(|k| k.time.is_finite() && k.value.is_finite())
+                .copied()
+                .collect();
+        }
+    }
+}
+
+impl Mode {
+    /// Clamp into the valid domain. Constructors already do.
+    pub(crate) fn clamp(&mut self) {
+        match &mut self.0 {
+            ModeImpl::Continuous { rate, .. } | ModeImpl::Drag { rate } => {
+                *rate = (*rate).min(MAX_RATE)
+            }
+            ModeImpl::Repeating { period, .. } => *period = (*period).max(1),
+            ModeImpl::Burst { .. }
+            | ModeImpl::Manual { .. }
+            | ModeImpl::Hover { .. }
+            | ModeImpl::Tap { .. } => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_RATE, MIN_LIFESPAN, MIN_SCALE};
+    use crate::core::{Emitter, Mode, ModeImpl, Physics, Transform};
+    use std::f32::consts::TAU;
+
+    #[test]
+    fn physics() {
+        let mut physics = Physics {
+            decay: 1.5,
+            gravity: f32::NAN,
+            drift: f32::INFINITY,
+            lifespan: -1.0,
+        };
+        physics.clamp();
+        assert_eq!(
+            physics,
+            Physics {
+                decay: 1.0,
+                gravity: 0.0,
+                drift: 0.0,
+                lifespan: MIN_LIFESPAN,
+            }
+        );
+        physics.decay = 0.0;
+        physics.clamp();
+        assert!(physics.decay > 0.0);
+
+        let mut valid = Physics::default();
+        valid.clamp();
+        assert_eq!(valid, Physics::default());
+    }
+
+    #[test]
+    fn emitters() {
+        let mut emitter = Emitter {
+            x: f32::NAN,
+            spread: -1.0,
+            mode: Mode(ModeImpl::Continuous {
+                rate: 5000,
+                start: 0,
+                end: u64::MAX,
+            }),
+            ..Default::default()
+        };
+        emitter.clamp();
+        assert_eq!(emitter.x, Emitter::default().x);
+        assert_eq!(emitter.spread, 0.0);
+        assert_eq!(emitter.mode, Mode::continuous(MAX_RATE as usize));
+        emitter.spread = 10.0;
+        emitter.mode = Mode(ModeImpl::Repeating {
+            count: 1,
+            delay: 0,
+            period: 0,
+        });
+        emitter.clamp();
+        assert_eq!(emitter.spread, TAU);
+        assert_eq!(emitter.mode, Mode::repeating_burst(1, 0.001));
+
+        let mut valid = Emitter::default();
+        valid.clamp();
+        assert_eq!(valid, Emitter::default());
+    }
+
+    #[test]
+    fn transforms() {
+        let mut transform = Transform::new().scale(0.0).rotate(f32::NAN);
+        transform.clamp();
+        assert_eq!(transform, Transform::new().scale(MIN_SCALE));
+        transform.scale = f32::INFINITY;
+        transform.clamp();
+        assert_eq!(transform, Transform::new());
+    }
+}
@@ -0,0 +1,393 @@
+use super::{Emitter, Eviction, Fetti, Mode, ParticleInit, Particles, Physics, Randomness, Rng};
+use implicit_clone::unsync::IString;
+
+/// Particles and emitter schedules, advanced in whole milliseconds.
+pub(crate) struct Engine {
+    pub(crate) particles: Particles,
+    /// Randomness of particles not emitted by a scheduled emitter.
+    rng: Rng,
+    /// See [`Engine::reseed`].
+    seed: u64,
+    randomness: Randomness,
+    /// Simulated time, in milliseconds since start or restart.
+    pub(crate) time: u64,
+    clocks: Vec<Clock>,
+    /// Index in `clocks` of each emitter.
+    emitter_clocks: Vec<usize>,
+    /// Whether emitters may emit on their schedules.
+    pub(crate) scheduling: bool,
+    /// Factor applied to emitted counts, e.g. to scale density.
+    pub(crate) emission_scale: f32,
+    /// Factor applied to launch velocities, and its square to gravity and drift, e.g. for
+    /// slower particles along the same trajectories.
+    pub(crate) speed_scale: f32,
+    /// Normalized distance per unit of motion, horizontally and vertically, see
+    /// [`Units::scale`](super::Units::scale).
+    pub(crate) unit_scale: (f32, f32),
+    pub(crate) max_particles: usize,
+    pub(crate) eviction: Eviction,
+    /// Bursts emitted on schedule by the last step, as emitter index and count.
+    pub(crate) bursts: Vec<(usize, usize)>,
+    /// Counts scheduled by each emitter in a substep, kept to reuse the allocation.
+    scheduled: Vec<usize>,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self {
+            particles: Particles::default(),
+            rng: Rng::new(0),
+            seed: 0,
+            randomness: Randomness::default(),
+            time: 0,
+            clocks: Vec::new(),
+            emitter_clocks: Vec::new(),
+            scheduling: true,
+            emission_scale: 1.0,
+            speed_scale: 1.0,
+            unit_scale: (1.0, 1.0),
+            max_particles: usize::MAX,
+            eviction: Eviction::default(),
+            bursts: Vec::new(),
+            scheduled: Vec::new(),
+        }
+    }
+}
+
+/// When a schedule started, so that emitters added (or changed) after the start start their
+/// schedule when they appear.
+struct Clock {
+    key: ClockKey,
+    mode: Mode,
+    epoch: u64,
+    /// Randomness of emitters with this clock, so other emitters don't change their particles.
+    rng: Rng,
+}
+
+/// Identifies an emitter's schedule across changes to the emitters.
+#[derive(Clone, PartialEq)]
+pub(crate) enum ClockKey {
+    /// Unkeyed emitters are tracked by index.
+    Index(usize),
+    /// Keyed emitters keep their schedule when others are added or removed before them.
+    Key(IString),
+    /// Emitters in a group share a clock.
+    Group(IString),
+}
+
+/// Live particles and cannon schedules, to continue an animation elsewhere, see
+/// [`ConfettiHandle::snapshot`](crate::ConfettiHandle::snapshot).
+#[derive(Clone)]
+pub struct ConfettiSnapshot {
+    particles: Particles,
+    time: u64,
+    /// Key, mode, and epoch of each clock. Randomness isn't captured.
+    clocks: Vec<(ClockKey, Mode, u64)>,
+}
+
+impl ConfettiSnapshot {
+    /// Number of particles alive when the snapshot was taken.
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+}
+
+impl ClockKey {
+    /// Stable hash (FNV-1a), so each clock's [`Rng`] is derived from the seed.
+    fn hash(&self) -> u64 {
+        let (tag, bytes) = match self {
+            Self::Index(i) => (0, &(*i as u64).to_le_bytes()[..]),
+            Self::Key(key) => (1, key.as_bytes()),
+            Self::Group(name) => (2, name.as_bytes()),
+        };
+        std::iter::once(tag)
+            .chain(bytes.iter().copied())
+            .fold(0xcbf29ce484222325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            })
+    }
+}
+
+impl Engine {
+    /// Restart all randomness from `seed`, so the same seed, emitters, and steps produce the
+    /// same particles.
+    pub(crate) fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = Rng::with(&self.randomness, seed);
+        for clock in &mut self.clocks {
+            clock.rng = Rng::with(&self.randomness, seed ^ clock.key.hash());
+        }
+    }
+
+    /// Replace the source of randomness, and restart it from the current seed.
+    pub(crate) fn set_randomness(&mut self, randomness: Randomness) {
+        self.randomness = randomness;
+        self.reseed(self.seed);
+    }
+
+    pub(crate) fn randomness(&self) -> &Randomness {
+        &self.randomness
+    }
+
+    pub(crate) fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Start the schedules of new or changed emitters now, and keep the rest.
+    pub(crate) fn sync_clocks(&mut self, emitters: impl IntoIterator<Item = (ClockKey, Mode)>) {
+        let mut old = std::mem::take(&mut self.clocks);
+        self.emitter_clocks.clear();
+        for (key, mode) in emitters {
+            let index = if let Some(index) = self.clocks.iter().position(|c| c.key == key) {
+                index
+            } else {
+                let clock = old
+                    .iter()
+                    .position(|c| c.key == key && c.mode == mode)
+                    .map(|i| old.swap_remove(i))
+                    .unwrap_or_else(|| Clock {
+                        rng: Rng::with(&self.randomness, self.seed ^ key.hash()),
+                        key,
+                        mode,
+                        epoch: self.time,
+                    });
+                self.clocks.push(clock);
+                self.clocks.len() - 1
+            };
+            self.emitter_clocks.push(index);
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> ConfettiSnapshot {
+        ConfettiSnapshot {
+            particles: self.particles.clone(),
+            time: self.time,
+            clocks: self
+                .clocks
+                .iter()
+                .map(|clock| (clock.key.clone(), clock.mode, clock.epoch))
+                .collect(),
+        }
+    }
+
+    /// Replace particles and schedules with those of `snapshot`, restarting randomness from the
+    /// seed. Call [`Engine::sync_clocks`] afterwards to match the schedules to the emitters.
+    pub(crate) fn restore(&mut self, snapshot: ConfettiSnapshot) {
+        self.particles = snapshot.particles;
+        // Triggers belong to the instance that fired them.
+        self.particles.untrack();
+        self.time = snapshot.time;
+        self.clocks = snapshot
+            .clocks
+            .into_iter()
+            .map(|(key, mode, epoch)| Clock {
+                rng: Rng::with(&self.randomness, self.seed ^ key.hash()),
+                key,
+                mode,
+                epoch,
+            })
+            .collect();
+        self.emitter_clocks.clear();
+    }
+
+    /// Replay schedules, and randomness, from the beginning.
+    pub(crate) fn restart(&mut self) {
+        for clock in &mut self.clocks {
+            clock.epoch = 0;
+        }
+        self.time = 0;
+        self.reseed(self.seed);
+    }
+
+    /// When the schedule of the emitter at `index` started.
+    fn epoch(&self, index: usize) -> u64 {
+        self.emitter_clocks
+            .get(index)
+            .map(|&clock| self.clocks[clock].epoch)
+            .unwrap_or(0)
+    }
+
+    /// Whether no more particles will be emitted on schedule.
+    pub(crate) fn is_done(&self, emitters: &[Emitter]) -> bool {
+        emitters
+            .iter()
+            .enumerate()
+            .all(|(i, emitter)| emitter.mode.0.is_done(self.time - self.epoch(i)))
+    }
+
+    /// Emit `count` particles, scaled by [`Engine::emission_scale`], from `emitter`, using the
+    /// randomness of the emitter at `index` if any. Returns how many were emitted.
+    pub(crate) fn emit(
+        &mut self,
+        index: Option<usize>,
+        emitter: &Emitter,
+        count: usize,
+        physics: &Physics,
+        trigger: u32,
+    ) -> usize {
+        if !emitter.can_emit() {
+            return 0;
+        }
+        let mut count = self.scale(count);
+        let cannon = index.map_or(0, |i| i as u32 + 1);
+        if let Some(max) = emitter.max_particles.filter(|_| cannon != 0 && count > 0) {
+            count = count.min(max.saturating_sub(self.particles.cannon_count(cannon)));
+        }
+        let animated;
+        let emitter = if emitter.keyframes.is_empty() {
+            emitter
+        } else {
+            let elapsed = index.map_or(0, |i| self.time - self.epoch(i));
+            animated = emitter.at(elapsed as f32 * 0.001);
+            &animated
+        };
+        let rng = match index.and_then(|i| self.emitter_clocks.get(i)) {
+            Some(&clock) => &mut self.clocks[clock].rng,
+            None => &mut self.rng,
+        };
+        self.particles.reserve(count);
+        for _ in 0..count {
+            let mut init = ParticleInit::from_emitter(emitter, rng);
+            init.velocity *= self.speed_scale;
+            let mut fetti = Fetti::new(init, physics.lifespan, trigger, rng);
+            fetti.cannon = cannon;
+            self.particles.push(fetti);
+        }
+        count
+    }
+
+    /// Recolor particles after the emitters change from `old` to `new`, with `keys`, before
+    /// [`Engine::sync_clocks`]. Emitters are paired by key, so each only recolors its own
+    /// particles.
+    pub(crate) fn recolor(&mut self, old: &[Emitter], new: &[Emitter], keys: &[ClockKey]) {
+        for (i, old) in old.iter().enumerate() {
+            let Some(&clock) = self.emitter_clocks.get(i) else {
+                continue;
+            };
+            let key = &self.clocks[clock].key;
+            // Emitters in a group share a key, so pair them in order.
+            let nth = self.emitter_clocks[..i]
+                .iter()
+                .filter(|&&c| c == clock)
+                .count();
+            let new = keys.iter().zip(new).filter(|(k, _)| *k == key).nth(nth);
+            if let Some((_, new)) = new.filter(|(_, new)| old.colors != new.colors) {
+                self.particles.recolor(i, &old.colors, &new.colors);
+            }
+        }
+    }
+
+    /// Emit particles with explicit initial states.
+    pub(crate) fn spawn(&mut self, particles: Vec<ParticleInit>, physics: &Physics, trigger: u32) {
+        self.particles.reserve(particles.len());
+        for init in particles {
+            self.particles
+                .push(Fetti::new(init, physics.lifespan, trigger, &mut self.rng));
+        }
+    }
+
+    /// Scale `count` by [`Engine::emission_scale`], rounding randomly so small counts scale
+    /// on average.
+    fn scale(&mut self, count: usize) -> usize {
+        if count == 0 || self.emission_scale == 1.0 {
+            return count;
+        }
+        (count as f32 * self.emission_scale + self.rng.unit()) as usize
+    }
+
+    /// Advance by `delta` milliseconds, emitting on schedule and updating particles. Returns
+    /// how many particles were emitted, and records bursts in [`Engine::bursts`].
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "confetti_step",
+            level = "trace",
+            skip(self, emitters, physics)
+        )
+    )]
+    pub(crate) fn step(
+        &mut self,
+        mut delta: u64,
+        emitters: &[Emitter],
+        physics: &Physics,
+    ) -> usize {
+        self.bursts.clear();
+        let acceleration_scale = self.speed_scale * self.speed_scale;
+        let physics = &Physics {
+            gravity: physics.gravity * acceleration_scale,
+            drift: physics.drift * acceleration_scale,
+            ..*physics
+        };
+        // TODO: use lifespan instead of constant?
+        if delta > 500 {
+            // Skip some time.
+            self.time += delta - 500;
+            delta = 500;
+        }
+        let substeps = (delta / 100).max(1);
+        let mut emitted = 0;
+        for substep in 0..substeps {
+            // Spread the remainder, so no time is lost.
+            let delta_time = delta * (substep + 1) / substeps - delta * substep / substeps;
+            // Inclusive.
+            let start_time = self.time;
+            // Exclusive.
+            let end_time = start_time + delta_time;
+            if self.scheduling {
+                let mut scheduled = std::mem::take(&mut self.scheduled);
+                scheduled.clear();
+                scheduled.extend(emitters.iter().enumerate().map(|(i, emitter)| {
+                    let epoch = self.epoch(i);
+                    emitter
+                        .mode
+                        .0
+                        .scheduled(start_time - epoch..end_time - epoch)
+                }));
+                if self.eviction == Eviction::DropNew {
+                    share(
+                        &mut scheduled,
+                        self.max_particles.saturating_sub(self.particles.len()),
+                    );
+                }
+                for (i, (emitter, &count)) in emitters.iter().zip(&scheduled).enumerate() {
+                    let count = self.emit(Some(i), emitter, count, physics, 0);
+                    emitted += count;
+                    if count > 0 && !emitter.mode.is_continuous() {
+                        self.bursts.push((i, count));
+                    }
+                }
+                self.scheduled = scheduled;
+            }
+            self.time = end_time;
+
+            if let Some(excess) = self.particles.len().checked_sub(self.max_particles) {
+                match self.eviction {
+                    Eviction::DropNew => self.particles.truncate(self.max_particles),
+                    // Particles are in order of emission.
+                    Eviction::KillOldest => self.particles.remove_oldest(excess),
+                }
+            }
+            self.particles
+                .update(delta_time as f32 * 0.001, physics, self.unit_scale);
+        }
+        emitted
+    }
+}
+
+/// Reduce `counts` in proportion to fit in `capacity`, if they don't already, so that cannons
+/// share what's left under [`Engine::max_particles`] instead of the first taking it all.
+fn share(counts: &mut [usize], capacity: usize) {
+    let total = counts.iter().sum::<usize>();
+    if total <= capacity {
+        return;
+    }
+    // Round cumulative shares, so they add up to `capacity` exactly.
+    let mut cumulative = 0;
+    let mut allocated = 0;
+    for count in counts {
+        cumulative += *count;
+        let end = (cumulative as u128 * capacity as u128 / total as u128) as usize;
+        *count = end - allocated;
+        allocated = end;
+    }
+}
@@ -0,0 +1,146 @@
+use super::Emitter;
+use implicit_clone::ImplicitClone;
+
+/// A cannon value animated by [`Keyframe`]s.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Track {
+    X,
+    Y,
+    Angle,
+    Velocity,
+}
+
+/// How a [`Track`] changes between keyframes.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Easing {
+    #[default]
+    Linear,
+    /// Hold the value until the next keyframe.
+    Step,
+    /// Start slow.
+    EaseIn,
+    /// End slow.
+    EaseOut,
+    /// Start and end slow.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Progress towards the next keyframe, given the fraction `t` of the time between them.
+    fn apply(self, t: f32) -> f32 {
+        match self {
+            Self::Linear => t,
+            Self::Step => 0.0,
+            Self::EaseIn => t * t,
+            Self::EaseOut => t * (2.0 - t),
+            Self::EaseInOut if t < 0.5 => 2.0 * t * t,
+            Self::EaseInOut => 1.0 - 2.0 * (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+/// The value of a [`Track`] at a time since the cannon's schedule started, evaluated whenever
+/// it emits. Before the first keyframe of a track, it has that keyframe's value, and after the
+/// last, the last's.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keyframe {
+    pub track: Track,
+    /// Seconds since the cannon's schedule started. Keyframes of a track must be in order.
+    pub time: f32,
+    /// In the units of the cannon value, e.g. radians for [`Track::Angle`].
+    pub value: f32,
+    /// How the value changes towards the next keyframe of the track.
+    pub easing: Easing,
+}
+
+impl ImplicitClone for Keyframe {}
+
+impl Keyframe {
+    /// A keyframe with linear easing.
+    pub const fn new(track: Track, time: f32, value: f32) -> Self {
+        Self {
+            track,
+            time,
+            value,
+            easing: Easing::Linear,
+        }
+    }
+
+    /// Change how the value changes towards the next keyframe.
+    pub const fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+/// The value of `track` at `time`, if it has keyframes.
+fn value(keyframes: &[Keyframe], track: Track, time: f32) -> Option<f32> {
+    let mut previous = None::<&Keyframe>;
+    for keyframe in keyframes.iter().filter(|k| k.track == track) {
+        if keyframe.time > time {
+            let Some(previous) = previous else {
+                return Some(keyframe.value);
+            };
+            let t = (time - previous.time) / (keyframe.time - previous.time);
+            return Some(
+                previous.value + (keyframe.value - previous.value) * previous.easing.apply(t),
+            );
+        }
+        previous = Some(keyframe);
+    }
+    previous.map(|keyframe| keyframe.value)
+}
+
+impl Emitter {
+    /// The emitter `time` seconds into its schedule, per [`Emitter::keyframes`].
+    pub(crate) fn at(&self, time: f32) -> Self {
+        let keyframes = self.keyframes.as_slice();
+        let value = |track, default| value(keyframes, track, time).unwrap_or(default);
+        Self {
+            x: value(Track::X, self.x),
+            y: value(Track::Y, self.y),
+            angle: value(Track::Angle, self.angle),
+            velocity: value(Track::Velocity, self.velocity),
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{value, Easing, Keyframe, Track};
+
+    #[test]
+    fn values() {
+        let keyframes = [
+            Keyframe::new(Track::X, 1.0, 0.0),
+            Keyframe::new(Track::Y, 0.0, 5.0),
+            Keyframe::new(Track::X, 3.0, 1.0).easing(Easing::Step),
+            Keyframe::new(Track::X, 4.0, 0.0),
+        ];
+        let x = |time| value(&keyframes, Track::X, time).unwrap();
+        assert_eq!(x(0.0), 0.0);
+        assert_eq!(x(2.0), 0.5);
+        assert_eq!(x(3.5), 1.0);
+        assert_eq!(x(10.0), 0.0);
+        assert_eq!(value(&keyframes, Track::Y, 2.0), Some(5.0));
+        assert_eq!(value(&keyframes, Track::Angle, 2.0), None);
+    }
+
+    #[test]
+    fn easings_span_keyframes() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.0), 1.0, "{easing:?}");
+            assert!((easing.apply(0.5) - 0.5).abs() <= 0.25, "{easing:?}");
+        }
+    }
+}
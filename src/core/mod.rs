@@ -0,0 +1,551 @@
+//! Particle state, emission scheduling, and physics, without browser or Yew APIs, so they can
+//! be tested natively and driven by other frontends.
+
+mod clamp;
+mod engine;
+mod keyframe;
+mod mode;
+mod particles;
+mod rng;
+mod simulation;
+#[cfg(test)]
+mod tests;
+
+pub use engine::ConfettiSnapshot;
+pub(crate) use engine::{ClockKey, Engine};
+pub use keyframe::{Easing, Keyframe, Track};
+pub use mode::{Mode, ModeImpl, ModeKind};
+pub(crate) use particles::Particles;
+pub(crate) use rng::Rng;
+pub use rng::{RandomSource, Randomness};
+pub use simulation::{Particle, Simulation};
+
+use implicit_clone::unsync::{IArray, IString};
+use implicit_clone::ImplicitClone;
+use std::ops::Range;
+
+/// Particle shape.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Shape {
+    Circle,
+    Square,
+}
+
+impl ImplicitClone for Shape {}
+
+/// How launch angles and velocities are distributed around an emitter's angle.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pattern {
+    /// Within `spread` of the angle, mostly near it.
+    #[default]
+    Cone,
+    /// Every direction, at nearly the same velocity, ignoring the angle and `spread`.
+    Ring,
+    /// Evenly within `spread` of the angle, at nearly the same velocity.
+    Fan,
+    /// Like [`Pattern::Cone`], but half the particles go the opposite way.
+    DoubleSided,
+}
+
+/// What happens when emitting more than
+/// [`ConfettiProps::max_particles`](crate::ConfettiProps::max_particles).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Eviction {
+    /// Don't emit new particles until old ones expire. Cannons emitting at once share what
+    /// room is left in proportion to how many particles they would emit.
+    #[default]
+    DropNew,
+    /// Remove the oldest particles to make room.
+    KillOldest,
+}
+
+/// What velocities, [`Physics::gravity`], and [`Physics::drift`] are measured in, see
+/// [`ConfettiProps::units`](crate::ConfettiProps::units).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Units {
+    /// Canvas widths (horizontally) and heights (vertically) per second, or per second
+    /// squared, so motion scales with the canvas.
+    #[default]
+    Normalized,
+    /// CSS pixels per second, or per second squared, so motion looks the same at any canvas
+    /// size. E.g. a velocity of 500 and gravity of 250 look like the defaults on a 250 pixel
+    /// canvas.
+    Pixels,
+}
+
+impl Units {
+    /// Normalized distance per unit, horizontally and vertically. If `aspect_correct`,
+    /// normalized units are canvas heights on both axes.
+    pub(crate) fn scale(self, viewport: Viewport, aspect_correct: bool) -> (f32, f32) {
+        match self {
+            Self::Normalized if aspect_correct => (viewport.height / viewport.width.max(1.0), 1.0),
+            Self::Normalized => (1.0, 1.0),
+            Self::Pixels => (
+                viewport.scale / viewport.width.max(1.0),
+                viewport.scale / viewport.height.max(1.0),
+            ),
+        }
+    }
+}
+
+/// How particles move, for a [`Simulation`]. Defaults match
+/// [`ConfettiProps`](crate::ConfettiProps).
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Physics {
+    /// Velocity decay per second (0.5 means lose 50% of velocity per second).
+    pub decay: f32,
+    /// Downward acceleration.
+    pub gravity: f32,
+    /// Rightward acceleration.
+    pub drift: f32,
+    /// Number of seconds each particle lasts.
+    pub lifespan: f32,
+}
+
+impl Default for Physics {
+    fn default() -> Self {
+        Self {
+            decay: 0.3,
+            gravity: 1.0,
+            drift: 0.0,
+            lifespan: 2.5,
+        }
+    }
+}
+
+impl Physics {
+    /// Paper confetti outdoors: quick to lose speed to air resistance, then falling steadily.
+    pub const fn realistic() -> Self {
+        Self {
+            decay: 0.2,
+            gravity: 1.5,
+            drift: 0.0,
+            lifespan: 3.0,
+        }
+    }
+
+    /// Slow, drifting particles that hang in the air, e.g. for a calm background.
+    pub const fn floaty() -> Self {
+        Self {
+            decay: 0.4,
+            gravity: 0.3,
+            drift: 0.05,
+            lifespan: 5.0,
+        }
+    }
+
+    /// Particles that keep their speed and drop fast, for a short, punchy burst.
+    pub const fn heavy() -> Self {
+        Self {
+            decay: 0.6,
+            gravity: 3.0,
+            drift: 0.0,
+            lifespan: 1.5,
+        }
+    }
+}
+
+/// Where and how particles are emitted, for a [`Simulation`]. Fields and defaults match
+/// [`CannonProps`](crate::CannonProps).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Emitter {
+    /// Horizontal position. 0.0 means left edge, 1.0 means right edge.
+    pub x: f32,
+    /// Vertical position. 0.0 means bottom edge, 1.0 means top edge.
+    pub y: f32,
+    /// Normalized points to emit along instead of at `x` and `y`, evenly by arc length.
+    pub path: IArray<(f32, f32)>,
+    /// Launch angle (0 = right, PI/2 = up, etc.).
+    pub angle: f32,
+    /// Random variation in launch angle (PI/2 = PI/4 on each side).
+    pub spread: f32,
+    /// How `angle`, `spread`, and `velocity` combine.
+    pub pattern: Pattern,
+    /// Initial velocity.
+    pub velocity: f32,
+    /// Shape probability distribution. Repeated shapes are more likely.
+    pub shapes: IArray<Shape>,
+    /// CSS color probability distribution. Repeated colors are more likely.
+    pub colors: IArray<IString>,
+    /// How to emit particles.
+    pub mode: Mode,
+    /// Animate `x`, `y`, `angle`, and `velocity` over the schedule.
+    pub keyframes: IArray<Keyframe>,
+    /// Most live particles emitted on schedule or by index. Emission beyond it is skipped.
+    pub max_particles: Option<usize>,
+}
+
+impl Default for Emitter {
+    fn default() -> Self {
+        Self {
+            x: 0.5,
+            y: 0.5,
+            path: IArray::Static(&[]),
+            angle: 90f32.to_radians(),
+            spread: 45f32.to_radians(),
+            pattern: Pattern::Cone,
+            velocity: 2.0,
+            shapes: IArray::Static(&[Shape::Circle, Shape::Square]),
+            colors: IArray::Static(&[
+                IString::Static("#26ccff"),
+                IString::Static("#a25afd"),
+                IString::Static("#ff5e7e"),
+                IString::Static("#88ff5a"),
+                IString::Static("#fcff42"),
+                IString::Static("#ffa62d"),
+                IString::Static("#ff36ff"),
+            ]),
+            mode: Mode::default(),
+            keyframes: IArray::Static(&[]),
+            max_particles: None,
+        }
+    }
+}
+
+impl Emitter {
+    /// Whether it has colors and shapes to give particles.
+    pub(crate) fn can_emit(&self) -> bool {
+        !self.colors.is_empty() && !self.shapes.is_empty()
+    }
+
+    /// A random point of [`Emitter::path`], or `x` and `y` if it's empty.
+    fn origin(&self, rng: &mut Rng) -> (f32, f32) {
+        let path = self.path.as_slice();
+        let Some(&first) = path.first() else {
+            return (self.x, self.y);
+        };
+        let length = |[(x1, y1), (x2, y2)]: [(f32, f32); 2]| (x2 - x1).hypot(y2 - y1);
+        let segments = || path.windows(2).map(|pair| [pair[0], pair[1]]);
+        let mut remaining = rng.max(segments().map(length).sum());
+        for segment in segments() {
+            let segment_length = length(segment);
+            if remaining <= segment_length && segment_length > 0.0 {
+                let [(x1, y1), (x2, y2)] = segment;
+                let t = remaining / segment_length;
+                return (x1 + (x2 - x1) * t, y1 + (y2 - y1) * t);
+            }
+            remaining -= segment_length;
+        }
+        // A single point, or rounding error.
+        path.last().copied().unwrap_or(first)
+    }
+}
+
+/// A particle, see [`Particles`] for how they're stored.
+#[derive(Clone)]
+pub(crate) struct Fetti {
+    pub(crate) x: f32,
+    pub(crate) y: f32,
+    /// Direction of travel.
+    pub(crate) dir_x: f32,
+    pub(crate) dir_y: f32,
+    pub(crate) velocity: f32,
+    pub(crate) wobble: f32,
+    pub(crate) wobble_speed: f32,
+    pub(crate) tilt_angle: f32,
+    pub(crate) life_remaining: f32,
+    pub(crate) color: IString,
+    pub(crate) shape: Shape,
+    /// Which [`ConfettiHandle::fire_then`](crate::ConfettiHandle::fire_then) emitted the
+    /// particle, or zero.
+    pub(crate) trigger: u32,
+    /// One more than the index of the scheduled emitter that emitted the particle, or zero.
+    pub(crate) cannon: u32,
+}
+
+/// Initial state of a particle, for [`ConfettiHandle::spawn`](crate::ConfettiHandle::spawn).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParticleInit {
+    /// Horizontal position. 0.0 means left edge, 1.0 means right edge.
+    pub x: f32,
+    /// Vertical position. 0.0 means bottom edge, 1.0 means top edge.
+    pub y: f32,
+    /// Direction of travel (0 = right, PI/2 = up, etc.).
+    pub angle: f32,
+    /// Initial velocity.
+    pub velocity: f32,
+    /// CSS color.
+    pub color: IString,
+    pub shape: Shape,
+}
+
+impl Default for ParticleInit {
+    fn default() -> Self {
+        Self {
+            x: 0.5,
+            y: 0.5,
+            angle: 90f32.to_radians(),
+            velocity: 0.0,
+            color: IString::Static("#ffffff"),
+            shape: Shape::Square,
+        }
+    }
+}
+
+impl ParticleInit {
+    /// Randomize a particle emitted by `emitter`.
+    pub(crate) fn from_emitter(emitter: &Emitter, rng: &mut Rng) -> Self {
+        let (x, y) = emitter.origin(rng);
+        let theta = rng.max(std::f32::consts::TAU);
+        let (sin, cos) = theta.sin_cos();
+        let unit = rng.unit();
+        let mag = unit.sqrt();
+        let cone = emitter.angle + cos * emitter.spread * 0.5 * mag;
+        let (angle, velocity) = match emitter.pattern {
+            Pattern::Cone => (cone, 0.9 + 0.1 * sin * mag),
+            Pattern::Ring => (theta, 0.95 + 0.05 * unit),
+            Pattern::Fan => (
+                emitter.angle + (unit - 0.5) * emitter.spread,
+                0.95 + 0.05 * cos,
+            ),
+            Pattern::DoubleSided if sin < 0.0 => {
+                (cone + std::f32::consts::PI, 0.9 - 0.1 * sin * mag)
+            }
+            Pattern::DoubleSided => (cone, 0.9 + 0.1 * sin * mag),
+        };
+        Self {
+            x,
+            y,
+            angle,
+            velocity: emitter.velocity * velocity,
+            color: emitter.colors[rng.max(emitter.colors.len() as f32) as usize].clone(),
+            shape: emitter.shapes[rng.max(emitter.shapes.len() as f32) as usize],
+        }
+    }
+}
+
+impl Fetti {
+    pub(crate) fn new(init: ParticleInit, lifespan: f32, trigger: u32, rng: &mut Rng) -> Self {
+        let (dir_y, dir_x) = init.angle.sin_cos();
+        Self {
+            x: init.x,
+            y: init.y,
+            dir_x,
+            dir_y,
+            velocity: init.velocity,
+            wobble: rng.unit(),
+            wobble_speed: rng.range(0.01, 0.015),
+            tilt_angle: rng.max(std::f32::consts::TAU),
+            life_remaining: lifespan,
+            color: init.color,
+            shape: init.shape,
+            trigger,
+            cannon: 0,
+        }
+    }
+
+    /// Opacity, fading out over the particle's `lifespan`.
+    pub(crate) fn alpha(&self, lifespan: f32) -> f32 {
+        self.life_remaining / lifespan
+    }
+
+    /// Outline in canvas pixels. `scalar` is the particle size, and `tilt` the length of its
+    /// tilt, which renderers jitter between 2.0 and 3.0 so particles twinkle.
+    pub(crate) fn geometry(&self, scalar: f32, viewport: Viewport, tilt: f32) -> Geometry {
+        let center_x = map_ranges(self.x, 0.0..1.0, 0.0..viewport.width);
+        let center_y = map_ranges(self.y, 0.0..1.0, viewport.height..0.0);
+
+        let scalar = scalar * viewport.scale;
+        let wobble_x = center_x + self.wobble.cos() * scalar;
+        let wobble_y = center_y + self.wobble.sin() * scalar;
+        let tilt_sin = self.tilt_angle.sin();
+        let tilt_cos = self.tilt_angle.cos();
+
+        let random = tilt * viewport.scale;
+        let x1 = center_x + tilt_cos * random;
+        let y1 = center_y + tilt_sin * random;
+        let x2 = wobble_x + tilt_cos * random;
+        let y2 = wobble_y + tilt_sin * random;
+
+        match self.shape {
+            Shape::Circle => Geometry::Ellipse {
+                center: [center_x, center_y],
+                radii: [(x2 - x1).abs() * 0.5, (y2 - y1).abs() * 0.5],
+                rotation: self.wobble,
+            },
+            Shape::Square => Geometry::Quad([
+                [center_x.floor(), center_y.floor()],
+                [wobble_x.floor(), y1],
+                [x2.floor(), y2.floor()],
+                [x1.floor(), wobble_y.floor()],
+            ]),
+        }
+    }
+}
+
+/// Drawing area of a renderer.
+#[derive(Copy, Clone)]
+pub(crate) struct Viewport {
+    pub(crate) width: f32,
+    pub(crate) height: f32,
+    /// Canvas pixels per CSS pixel.
+    pub(crate) scale: f32,
+}
+
+/// Scale, rotation, and translation of all confetti, in that order, about the center of the
+/// canvas, see [`ConfettiProps::transform`](crate::ConfettiProps::transform).
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform {
+    /// Factor, e.g. 2.0 to zoom in.
+    pub scale: f32,
+    /// Clockwise, in radians.
+    pub rotate: f32,
+    /// Rightward and downward, in CSS pixels.
+    pub translate: (f32, f32),
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transform {
+    /// No change.
+    pub const fn new() -> Self {
+        Self {
+            scale: 1.0,
+            rotate: 0.0,
+            translate: (0.0, 0.0),
+        }
+    }
+
+    /// Change the scale factor.
+    pub const fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Change the clockwise rotation, in radians.
+    pub const fn rotate(mut self, rotate: f32) -> Self {
+        self.rotate = rotate;
+        self
+    }
+
+    /// Change the translation, in CSS pixels.
+    pub const fn translate(mut self, x: f32, y: f32) -> Self {
+        self.translate = (x, y);
+        self
+    }
+
+    /// Map a point in canvas pixels.
+    fn apply(&self, [x, y]: [f32; 2], viewport: Viewport) -> [f32; 2] {
+        let (sin, cos) = self.rotate.sin_cos();
+        let (dx, dy) = (
+            (x - viewport.width * 0.5) * self.scale,
+            (y - viewport.height * 0.5) * self.scale,
+        );
+        [
+            viewport.width * 0.5 + cos * dx - sin * dy + self.translate.0 * viewport.scale,
+            viewport.height * 0.5 + sin * dx + cos * dy + self.translate.1 * viewport.scale,
+        ]
+    }
+
+    /// Map a normalized position back to where it was before the transform, e.g. to find what
+    /// a pointer points at.
+    pub(crate) fn invert(&self, (x, y): (f32, f32), viewport: Viewport) -> (f32, f32) {
+        let (sin, cos) = self.rotate.sin_cos();
+        let (dx, dy) = (
+            map_ranges(x, 0.0..1.0, 0.0..viewport.width)
+                - viewport.width * 0.5
+                - self.translate.0 * viewport.scale,
+            map_ranges(y, 0.0..1.0, viewport.height..0.0)
+                - viewport.height * 0.5
+                - self.translate.1 * viewport.scale,
+        );
+        let scale = 1.0 / self.scale;
+        let (x, y) = (
+            viewport.width * 0.5 + (cos * dx + sin * dy) * scale,
+            viewport.height * 0.5 + (cos * dy - sin * dx) * scale,
+        );
+        (
+            map_ranges(x, 0.0..viewport.width, 0.0..1.0),
+            map_ranges(y, viewport.height..0.0, 0.0..1.0),
+        )
+    }
+}
+
+/// Outline of a [`Fetti`] in canvas pixels.
+pub(crate) enum Geometry {
+    Ellipse {
+        center: [f32; 2],
+        radii: [f32; 2],
+        rotation: f32,
+    },
+    /// Corners, in order around the perimeter.
+    Quad([[f32; 2]; 4]),
+}
+
+impl Geometry {
+    /// Corners of the bounding quad, in order around the perimeter.
+    pub(crate) fn corners(&self) -> [[f32; 2]; 4] {
+        match *self {
+            Self::Ellipse {
+                center: [x, y],
+                radii: [rx, ry],
+                rotation,
+            } => {
+                let (sin, cos) = rotation.sin_cos();
+                let (ax, ay) = (cos * rx, sin * rx);
+                let (bx, by) = (-sin * ry, cos * ry);
+                [
+                    [x - ax - bx, y - ay - by],
+                    [x + ax - bx, y + ay - by],
+                    [x + ax + bx, y + ay + by],
+                    [x - ax + bx, y - ay + by],
+                ]
+            }
+            Self::Quad(corners) => corners,
+        }
+    }
+
+    /// Map through `transform`.
+    pub(crate) fn transform(self, transform: &Transform, viewport: Viewport) -> Self {
+        match self {
+            Self::Ellipse {
+                center,
+                radii: [rx, ry],
+                rotation,
+            } => Self::Ellipse {
+                center: transform.apply(center, viewport),
+                radii: [rx * transform.scale, ry * transform.scale],
+                rotation: rotation + transform.rotate,
+            },
+            Self::Quad(corners) => Self::Quad(corners.map(|p| transform.apply(p, viewport))),
+        }
+    }
+
+    /// Affine transform `[a, b, c, d, e, f]` from the unit square onto the bounding quad.
+    pub(crate) fn matrix(&self) -> [f32; 6] {
+        let [p0, p1, _, p3] = self.corners();
+        [
+            p1[0] - p0[0],
+            p1[1] - p0[1],
+            p3[0] - p0[0],
+            p3[1] - p0[1],
+            p0[0],
+            p0[1],
+        ]
+    }
+}
+
+#[inline]
+fn map_ranges(number: f32, old: Range<f32>, new: Range<f32>) -> f32 {
+    let old_range = old.end - old.start;
+    let new_range = new.end - new.start;
+    let mul: f32 = new_range / old_range;
+    let add: f32 = -old.start * mul + new.start;
+
+    if cfg!(target_feature = "fma") {
+        number.mul_add(mul, add)
+    } else {
+        number * mul + add
+    }
+}
@@ -0,0 +1,489 @@
+use super::clamp::MAX_RATE;
+use std::ops::Range;
+
+/// How to emit particles. Times are precise to the nearest millisecond, and serialized in
+/// milliseconds.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Mode(pub(crate) ModeImpl);
+
+impl Default for Mode {
+    fn default() -> Self {
+        Self::continuous(100)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+#[doc(hidden)]
+pub enum ModeImpl {
+    /// Emit all particles at a certain time.
+    Burst {
+        /// How many particles to emit.
+        count: usize,
+        /// Time, in milliseconds since first render.
+        delay: u64,
+    },
+    /// Constant stream of particles.
+    Continuous {
+        /// How many particles are emitted per second. Max is 1000.
+        rate: u16,
+        /// When the particles start being emitted, in milliseconds since first render.
+        start: u64,
+        /// When the particles stop being emitted, in milliseconds since first render.
+        end: u64,
+    },
+    /// Emit particles periodically.
+    Repeating {
+        /// How many particles to emit each time.
+        count: usize,
+        /// Time of the first burst, in milliseconds since first render.
+        delay: u64,
+        /// Time between bursts, in milliseconds.
+        period: u64,
+    },
+    /// Emit particles only when fired imperatively.
+    Manual {
+        /// How many particles to emit.
+        count: usize,
+    },
+    /// Emit particles when the pointer enters the hover target.
+    Hover {
+        /// How many particles to emit.
+        count: usize,
+        /// Minimum time between bursts, in milliseconds.
+        cooldown: u64,
+    },
+    /// Emit particles along the pointer's path while it drags across the canvas.
+    Drag {
+        /// How many particles are emitted per second of dragging. Max is 1000.
+        rate: u16,
+    },
+    /// Emit particles wherever a pointer presses the canvas.
+    Tap {
+        /// How many particles to emit per press.
+        count: usize,
+    },
+}
+
+/// What a [`Mode`] does, with times in seconds, e.g. to show it in a configurator. See
+/// [`Mode::kind`], and the constructors of [`Mode`] with the same names.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ModeKind {
+    /// See [`Mode::delayed_burst`].
+    Burst { count: usize, delay: f32 },
+    /// See [`Mode::delayed_finite_continuous`]. `None` means forever.
+    Continuous {
+        rate: usize,
+        delay: f32,
+        duration: Option<f32>,
+    },
+    /// See [`Mode::delayed_repeating_burst`].
+    Repeating {
+        count: usize,
+        delay: f32,
+        period: f32,
+    },
+    /// See [`Mode::manual`].
+    Manual { count: usize },
+    /// See [`Mode::hover`].
+    Hover { count: usize, cooldown: f32 },
+    /// See [`Mode::drag`].
+    Drag { rate: usize },
+    /// See [`Mode::tap`].
+    Tap { count: usize },
+}
+
+impl From<ModeKind> for Mode {
+    fn from(kind: ModeKind) -> Self {
+        match kind {
+            ModeKind::Burst { count, delay } => Self::delayed_burst(count, delay),
+            ModeKind::Continuous {
+                rate,
+                delay,
+                duration: None,
+            } => Self::delayed_continuous(rate, delay),
+            ModeKind::Continuous {
+                rate,
+                delay,
+                duration: Some(duration),
+            } => Self::delayed_finite_continuous(rate, delay, duration),
+            ModeKind::Repeating {
+                count,
+                delay,
+                period,
+            } => Self::delayed_repeating_burst(count, delay, period),
+            ModeKind::Manual { count } => Self::manual(count),
+            ModeKind::Hover { count, cooldown } => Self::hover(count, cooldown),
+            ModeKind::Drag { rate } => Self::drag(rate),
+            ModeKind::Tap { count } => Self::tap(count),
+        }
+    }
+}
+
+/// Permutation of the milliseconds in a second, such that emitting on the first `rate`
+/// milliseconds (in this order) spaces out `rate` particles as evenly as possible.
+/*
+fn main() {
+    let mut sus: [u16; 1000] = std::array::from_fn(|i| i as u16);
+    fn key(n: u16) -> u16 {
+        (1..100).filter(|k| n % ((1000 + k - 1) / k) == 0).min().unwrap_or(u16::MAX)
+    }
+    sus.sort_by_key(|n| key(*n));
+    let mut hash = 0u16;
+    for i in 0..1000 {
+        hash = hash.wrapping_mul(37).wrapping_add(i);
+        let i0 = (hash % 1000) as usize;
+        hash = hash.wrapping_mul(79).wrapping_add(i);
+        let i1 = (hash % 1000) as usize;
+        if key(sus[i0]) == key(sus[i1]) {
+            sus.swap(i0 as usize, i1 as usize);
+        }
+    }
+    // println!("{sus:?}");
+    let ret: [u16; 1000] = std::array::from_fn(|i| sus.iter().position(|k| *k == i as u16).unwrap() as u16);
+    println!("{ret:?}");
+}
+*/
+#[rustfmt::skip]
+static ORDER: [u16; 1000] = [0, 603, 549, 550, 551, 813, 553, 758, 751, 556, 557, 534, 524, 513, 504, 497, 491, 474, 469, 452, 447, 439, 433, 418, 415, 406, 397, 383, 381, 352, 346, 315, 306, 288, 268, 255, 248, 991, 228, 210, 201, 552, 196, 773, 180, 498, 159, 561, 147, 562, 139, 475, 398, 121, 384, 535, 117, 453, 353, 101, 347, 563, 316, 91, 307, 514, 289, 77, 269, 419, 256, 564, 65, 565, 968, 407, 229, 54, 211, 567, 202, 385, 690, 569, 45, 476, 695, 368, 181, 571, 348, 35, 160, 317, 881, 454, 148, 894, 505, 290, 31, 574, 270, 575, 399, 257, 122, 932, 249, 577, 434, 866, 23, 649, 230, 420, 355, 212, 102, 477, 203, 536, 580, 581, 318, 19, 92, 582, 308, 996, 400, 579, 182, 455, 78, 386, 271, 585, 161, 586, 258, 587, 588, 13, 66, 356, 589, 440, 570, 591, 140, 861, 231, 478, 55, 319, 213, 790, 627, 123, 204, 421, 387, 656, 596, 291, 744, 10, 46, 515, 272, 456, 808, 599, 357, 259, 183, 103, 705, 583, 250, 757, 36, 904, 162, 604, 320, 479, 605, 93, 232, 606, 149, 607, 804, 214, 382, 767, 292, 610, 6, 79, 611, 358, 273, 612, 613, 422, 401, 457, 197, 614, 124, 615, 953, 617, 67, 321, 618, 619, 184, 481, 960, 840, 24, 408, 622, 623, 233, 595, 163, 56, 359, 718, 215, 584, 107, 896, 274, 628, 150, 629, 435, 392, 630, 260, 631, 458, 322, 708, 4, 633, 47, 423, 634, 480, 309, 635, 636, 816, 402, 367, 638, 639, 185, 125, 234, 712, 88, 641, 349, 642, 275, 37, 643, 409, 164, 644, 645, 323, 118, 646, 797, 672, 985, 459, 14, 829, 68, 482, 361, 872, 652, 974, 198, 105, 654, 293, 869, 424, 32, 755, 657, 568, 235, 659, 276, 651, 57, 661, 324, 662, 216, 779, 664, 94, 715, 887, 126, 362, 205, 736, 165, 460, 251, 410, 668, 669, 725, 671, 294, 650, 673, 600, 2, 81, 25, 675, 403, 676, 277, 325, 236, 677, 624, 425, 679, 924, 363, 681, 141, 217, 186, 682, 106, 683, 684, 441, 685, 849, 69, 461, 558, 295, 38, 990, 689, 728, 166, 721, 692, 127, 326, 832, 278, 20, 694, 364, 95, 720, 237, 863, 697, 698, 151, 58, 699, 862, 701, 702, 218, 426, 119, 703, 954, 660, 187, 984, 707, 442, 7, 696, 82, 327, 709, 389, 365, 537, 279, 710, 711, 839, 560, 104, 167, 714, 310, 772, 238, 716, 48, 939, 992, 693, 128, 411, 590, 999, 722, 15, 723, 724, 70, 845, 328, 366, 726, 427, 727, 936, 188, 96, 280, 548, 525, 730, 731, 982, 26, 733, 142, 538, 949, 735, 640, 39, 239, 737, 738, 390, 168, 739, 59, 740, 376, 329, 741, 742, 219, 83, 743, 780, 108, 539, 745, 412, 281, 129, 746, 747, 152, 516, 748, 428, 189, 749, 391, 750, 880, 625, 261, 827, 526, 354, 240, 296, 330, 754, 688, 756, 1, 11, 885, 598, 49, 759, 169, 220, 602, 761, 282, 762, 311, 388, 763, 764, 527, 540, 506, 765, 206, 620, 369, 976, 691, 262, 912, 331, 153, 429, 130, 109, 241, 517, 770, 573, 84, 846, 962, 60, 252, 774, 753, 566, 283, 777, 40, 778, 678, 554, 143, 370, 170, 781, 782, 499, 783, 826, 332, 518, 27, 297, 785, 786, 528, 787, 788, 97, 831, 937, 242, 791, 16, 864, 507, 413, 71, 793, 284, 794, 371, 795, 796, 131, 784, 221, 798, 621, 50, 333, 110, 952, 492, 801, 298, 263, 802, 830, 171, 680, 8, 805, 508, 85, 806, 541, 807, 593, 243, 372, 809, 519, 253, 632, 811, 500, 61, 812, 594, 814, 334, 393, 815, 686, 154, 21, 817, 299, 888, 483, 98, 819, 820, 821, 822, 823, 132, 41, 373, 578, 207, 825, 898, 944, 172, 501, 244, 828, 72, 111, 144, 335, 647, 958, 766, 666, 493, 833, 509, 834, 190, 835, 836, 222, 799, 264, 470, 374, 3, 838, 86, 542, 28, 559, 837, 394, 404, 964, 842, 843, 208, 844, 336, 717, 245, 928, 510, 847, 496, 133, 173, 929, 957, 62, 850, 851, 375, 484, 789, 853, 33, 597, 223, 462, 191, 502, 855, 994, 112, 857, 616, 859, 971, 337, 199, 17, 592, 841, 626, 608, 73, 865, 246, 824, 867, 360, 300, 868, 42, 395, 776, 485, 529, 775, 871, 265, 174, 87, 472, 810, 448, 224, 134, 873, 338, 920, 875, 576, 192, 877, 5, 878, 495, 879, 377, 555, 51, 909, 882, 301, 209, 883, 884, 856, 886, 486, 891, 113, 155, 670, 63, 889, 890, 752, 471, 339, 892, 443, 893, 463, 225, 543, 175, 378, 29, 874, 895, 818, 897, 732, 899, 900, 74, 520, 901, 135, 902, 903, 200, 487, 9, 959, 905, 544, 80, 266, 340, 906, 907, 908, 350, 983, 379, 910, 436, 911, 156, 464, 769, 43, 449, 913, 914, 915, 916, 302, 114, 771, 176, 918, 919, 870, 312, 488, 921, 12, 193, 341, 922, 923, 52, 380, 601, 925, 926, 521, 473, 64, 136, 927, 145, 430, 530, 860, 511, 465, 760, 719, 18, 931, 450, 444, 876, 933, 75, 665, 935, 489, 342, 545, 351, 89, 713, 700, 177, 22, 531, 938, 800, 940, 194, 941, 99, 942, 285, 115, 943, 663, 416, 945, 946, 303, 947, 466, 948, 667, 30, 226, 950, 343, 34, 137, 437, 445, 951, 854, 858, 706, 955, 956, 44, 637, 157, 546, 609, 503, 729, 648, 286, 961, 178, 930, 963, 522, 53, 414, 917, 965, 313, 966, 344, 467, 967, 655, 969, 490, 76, 970, 90, 653, 451, 972, 973, 431, 116, 100, 438, 852, 532, 523, 146, 975, 120, 658, 138, 803, 978, 304, 979, 980, 158, 345, 405, 981, 934, 768, 179, 687, 195, 468, 792, 986, 254, 987, 988, 227, 494, 989, 704, 547, 267, 572, 734, 993, 417, 674, 287, 446, 247, 432, 305, 995, 314, 848, 512, 997, 533, 998, 977, 396];
+
+impl ModeImpl {
+    /// How many particles to emit on schedule in the `time` range (milliseconds).
+    pub(crate) fn scheduled(&self, time: Range<u64>) -> usize {
+        match *self {
+            Self::Burst { count, delay } => {
+                if time.contains(&delay) {
+                    count
+                } else {
+                    0
+                }
+            }
+            Self::Continuous { rate, start, end } => {
+                let effective_start_time = time.start.max(start);
+                let effective_end_time = time.end.min(end);
+                if rate > 0 && effective_end_time > effective_start_time {
+                    (effective_start_time..effective_end_time)
+                        .filter(|effective_time| rate > ORDER[(effective_time % 1000) as usize])
+                        .count()
+                } else {
+                    0
+                }
+            }
+            Self::Repeating {
+                count,
+                delay,
+                period,
+            } => {
+                // Number of bursts strictly before `time`.
+                let bursts_before = |time: u64| {
+                    delay
+                        .checked_add(1)
+                        .and_then(|first| time.checked_sub(first))
+                        .map(|since| since / period.max(1) + 1)
+                        .unwrap_or(0) as usize
+                };
+                count.saturating_mul(bursts_before(time.end) - bursts_before(time.start))
+            }
+            Self::Manual { .. } | Self::Hover { .. } | Self::Drag { .. } | Self::Tap { .. } => 0,
+        }
+    }
+
+    /// Whether no more particles will be emitted on schedule after `time`.
+    pub(crate) fn is_done(&self, time: u64) -> bool {
+        match *self {
+            Self::Burst { delay, .. } => time > delay,
+            Self::Continuous { end, .. } => time > end,
+            Self::Repeating { .. } => false,
+            Self::Manual { .. } | Self::Hover { .. } | Self::Drag { .. } | Self::Tap { .. } => true,
+        }
+    }
+
+    fn burst_count(&self) -> usize {
+        match *self {
+            Self::Burst { count, .. }
+            | Self::Repeating { count, .. }
+            | Self::Manual { count }
+            | Self::Hover { count, .. }
+            | Self::Tap { count } => count,
+            Self::Continuous { rate, .. } | Self::Drag { rate } => rate as usize,
+        }
+    }
+}
+
+/// Milliseconds in `seconds`, or zero if negative.
+fn round_time(seconds: f32) -> u64 {
+    (seconds * 1000.0).round() as u64
+}
+
+fn seconds(milliseconds: u64) -> f32 {
+    milliseconds as f32 / 1000.0
+}
+
+impl Mode {
+    /// Emit `count` particles upon first render.
+    pub fn burst(count: usize) -> Self {
+        Self(ModeImpl::Burst { count, delay: 0 })
+    }
+
+    /// Emit `count` particles after `delay` seconds after first render.
+    pub fn delayed_burst(count: usize, delay: f32) -> Self {
+        Self(ModeImpl::Burst {
+            count,
+            delay: round_time(delay),
+        })
+    }
+
+    pub fn is_burst(&self) -> bool {
+        matches!(self.0, ModeImpl::Burst { .. })
+    }
+
+    /// Constantly emit `rate` particles per second.
+    ///
+    /// Clamps `rate` to at most 1000.
+    pub fn continuous(rate: usize) -> Self {
+        Self(ModeImpl::Continuous {
+            rate: rate.min(MAX_RATE as usize) as u16,
+            start: 0,
+            end: u64::MAX,
+        })
+    }
+
+    /// Constantly emit `rate` particles per second, starting `delay` seconds after first render.
+    ///
+    /// Clamps `rate` to at most 1000 and a negative `delay` to zero.
+    pub fn delayed_continuous(rate: usize, delay: f32) -> Self {
+        Self(ModeImpl::Continuous {
+            rate: rate.min(MAX_RATE as usize) as u16,
+            start: round_time(delay),
+            end: u64::MAX,
+        })
+    }
+
+    /// Constantly emit `rate` particles per second, for the first `duration` seconds after first
+    /// render.
+    ///
+    /// Clamps `rate` to at most 1000 and a negative `duration` to zero.
+    pub fn finite_continuous(rate: usize, duration: f32) -> Self {
+        Self(ModeImpl::Continuous {
+            rate: rate.min(MAX_RATE as usize) as u16,
+            start: 0,
+            end: round_time(duration),
+        })
+    }
+
+    /// Constantly emit `rate` particles per second, starting `delay` seconds after first render
+    /// and for `duration` seconds thereafter.
+    ///
+    /// Clamps `rate` to at most 1000, and a negative `delay` or `duration` to zero.
+    pub fn delayed_finite_continuous(rate: usize, delay: f32, duration: f32) -> Self {
+        Self(ModeImpl::Continuous {
+            rate: rate.min(MAX_RATE as usize) as u16,
+            start: round_time(delay),
+            end: round_time(delay + duration),
+        })
+    }
+
+    pub fn is_continuous(&self) -> bool {
+        matches!(self.0, ModeImpl::Continuous { .. })
+    }
+
+    /// Emit `count` particles every `period` seconds, starting upon first render.
+    ///
+    /// Clamps `period` to at least a millisecond.
+    pub fn repeating_burst(count: usize, period: f32) -> Self {
+        Self::delayed_repeating_burst(count, 0.0, period)
+    }
+
+    /// Emit `count` particles every `period` seconds, starting `delay` seconds after first
+    /// render.
+    ///
+    /// Clamps a negative `delay` to zero and `period` to at least a millisecond.
+    pub fn delayed_repeating_burst(count: usize, delay: f32, period: f32) -> Self {
+        Self(ModeImpl::Repeating {
+            count,
+            delay: round_time(delay),
+            period: round_time(period).max(1),
+        })
+    }
+
+    pub fn is_repeating(&self) -> bool {
+        matches!(self.0, ModeImpl::Repeating { .. })
+    }
+
+    /// Never emit particles on a schedule, only emit `count` particles when fired by a
+    /// [`ConfettiHandle`](crate::ConfettiHandle).
+    pub fn manual(count: usize) -> Self {
+        Self(ModeImpl::Manual { count })
+    }
+
+    pub fn is_manual(&self) -> bool {
+        matches!(self.0, ModeImpl::Manual { .. })
+    }
+
+    /// Emit `count` particles whenever the pointer enters
+    /// [`ConfettiProps::hover_target`](crate::ConfettiProps::hover_target), at most once every
+    /// `cooldown` seconds.
+    ///
+    /// Clamps a negative `cooldown` to zero.
+    pub fn hover(count: usize, cooldown: f32) -> Self {
+        Self(ModeImpl::Hover {
+            count,
+            cooldown: round_time(cooldown),
+        })
+    }
+
+    pub fn is_hover(&self) -> bool {
+        matches!(self.0, ModeImpl::Hover { .. })
+    }
+
+    /// Emit `rate` particles per second along the pointer's path while it drags across the
+    /// canvas, with a mouse, pen, or finger. Particles head the way the pointer moves, faster
+    /// the faster it moves. Never emits on a schedule.
+    ///
+    /// Clamps `rate` to 1000.
+    pub fn drag(rate: usize) -> Self {
+        Self(ModeImpl::Drag {
+            rate: rate.min(MAX_RATE as usize) as u16,
+        })
+    }
+
+    pub fn is_drag(&self) -> bool {
+        matches!(self.0, ModeImpl::Drag { .. })
+    }
+
+    /// Emit `count` particles from wherever a mouse, pen, or finger presses the canvas. Each
+    /// finger bursts once per press, so several can burst at once on touchscreens. Never emits
+    /// on a schedule.
+    pub fn tap(count: usize) -> Self {
+        Self(ModeImpl::Tap { count })
+    }
+
+    pub fn is_tap(&self) -> bool {
+        matches!(self.0, ModeImpl::Tap { .. })
+    }
+
+    /// How many particles are emitted when fired imperatively, e.g. by
+    /// [`ConfettiHandle::fire`](crate::ConfettiHandle::fire). This is the `count` of bursts, or
+    /// one second's worth of continuous emission.
+    pub fn burst_count(&self) -> usize {
+        self.0.burst_count()
+    }
+
+    /// What the mode does, with times in seconds.
+    pub fn kind(&self) -> ModeKind {
+        match self.0 {
+            ModeImpl::Burst { count, delay } => ModeKind::Burst {
+                count,
+                delay: seconds(delay),
+            },
+            ModeImpl::Continuous { rate, start, end } => ModeKind::Continuous {
+                rate: rate as usize,
+                delay: seconds(start),
+                duration: (end != u64::MAX).then(|| seconds(end.saturating_sub(start))),
+            },
+            ModeImpl::Repeating {
+                count,
+                delay,
+                period,
+            } => ModeKind::Repeating {
+                count,
+                delay: seconds(delay),
+                period: seconds(period),
+            },
+            ModeImpl::Manual { count } => ModeKind::Manual { count },
+            ModeImpl::Hover { count, cooldown } => ModeKind::Hover {
+                count,
+                cooldown: seconds(cooldown),
+            },
+            ModeImpl::Drag { rate } => ModeKind::Drag {
+                rate: rate as usize,
+            },
+            ModeImpl::Tap { count } => ModeKind::Tap { count },
+        }
+    }
+
+    /// Set how many particles each burst emits. Does nothing for continuous and drag modes.
+    pub fn set_count(&mut self, new_count: usize) {
+        match &mut self.0 {
+            ModeImpl::Burst { count, .. }
+            | ModeImpl::Repeating { count, .. }
+            | ModeImpl::Manual { count }
+            | ModeImpl::Hover { count, .. }
+            | ModeImpl::Tap { count } => *count = new_count,
+            ModeImpl::Continuous { .. } | ModeImpl::Drag { .. } => {}
+        }
+    }
+
+    /// Set how many particles a continuous or drag mode emits per second, at most 1000. Does
+    /// nothing for other modes.
+    pub fn set_rate(&mut self, new_rate: usize) {
+        if let ModeImpl::Continuous { rate, .. } | ModeImpl::Drag { rate } = &mut self.0 {
+            *rate = new_rate.min(MAX_RATE as usize) as u16;
+        }
+    }
+
+    /// Set the delay, in seconds since first render, keeping the duration of a finite
+    /// continuous mode. Does nothing for manual, hover, drag, and tap modes.
+    pub fn set_delay(&mut self, new_delay: f32) {
+        let new_delay = round_time(new_delay);
+        match &mut self.0 {
+            ModeImpl::Burst { delay, .. } | ModeImpl::Repeating { delay, .. } => *delay = new_delay,
+            ModeImpl::Continuous { start, end, .. } => {
+                if *end != u64::MAX {
+                    *end = new_delay.saturating_add(end.saturating_sub(*start));
+                }
+                *start = new_delay;
+            }
+            ModeImpl::Manual { .. }
+            | ModeImpl::Hover { .. }
+            | ModeImpl::Drag { .. }
+            | ModeImpl::Tap { .. } => {}
+        }
+    }
+
+    /// Set how many seconds a continuous mode emits for, or `None` for forever. Does nothing for
+    /// other modes.
+    pub fn set_duration(&mut self, duration: Option<f32>) {
+        if let ModeImpl::Continuous { start, end, .. } = &mut self.0 {
+            *end = duration.map_or(u64::MAX, |duration| {
+                start.saturating_add(round_time(duration))
+            });
+        }
+    }
+
+    /// Set the seconds between repeating bursts, at least one millisecond. Does nothing for
+    /// other modes.
+    pub fn set_period(&mut self, new_period: f32) {
+        if let ModeImpl::Repeating { period, .. } = &mut self.0 {
+            *period = round_time(new_period).max(1);
+        }
+    }
+
+    /// Set the minimum seconds between hover bursts. Does nothing for other modes.
+    pub fn set_cooldown(&mut self, new_cooldown: f32) {
+        if let ModeImpl::Hover { cooldown, .. } = &mut self.0 {
+            *cooldown = round_time(new_cooldown);
+        }
+    }
+
+    #[doc(hidden)]
+    #[deprecated(note = "use `Mode::kind`")]
+    pub fn impl_ref(&self) -> &ModeImpl {
+        &self.0
+    }
+
+    #[doc(hidden)]
+    #[deprecated(note = "use the setters of `Mode`")]
+    pub fn impl_mut(&mut self) -> &mut ModeImpl {
+        &mut self.0
+    }
+}
@@ -0,0 +1,295 @@
+use super::{Fetti, Physics, Shape};
+use implicit_clone::unsync::IString;
+
+/// Apply `$f` to every column of `$particles`.
+macro_rules! for_each_column {
+    ($particles:expr, |$column:ident| $f:expr) => {{
+        let particles = $particles;
+        {
+            let $column = &mut particles.x;
+            $f;
+        }
+        {
+            let $column = &mut particles.y;
+            $f;
+        }
+        {
+            let $column = &mut particles.dir_x;
+            $f;
+        }
+        {
+            let $column = &mut particles.dir_y;
+            $f;
+        }
+        {
+            let $column = &mut particles.velocity;
+            $f;
+        }
+        {
+            let $column = &mut particles.wobble;
+            $f;
+        }
+        {
+            let $column = &mut particles.wobble_speed;
+            $f;
+        }
+        {
+            let $column = &mut particles.tilt_angle;
+            $f;
+        }
+        {
+            let $column = &mut particles.life_remaining;
+            $f;
+        }
+        {
+            let $column = &mut particles.color;
+            $f;
+        }
+        {
+            let $column = &mut particles.shape;
+            $f;
+        }
+        {
+            let $column = &mut particles.trigger;
+            $f;
+        }
+        {
+            let $column = &mut particles.cannon;
+            $f;
+        }
+    }};
+}
+
+/// Live particles, in order of emission, as a struct of arrays so they can be updated with
+/// SIMD. Expired particles are removed in place, so the capacity is reused and steady emission
+/// doesn't allocate.
+#[derive(Clone, Default)]
+pub(crate) struct Particles {
+    x: Vec<f32>,
+    y: Vec<f32>,
+    dir_x: Vec<f32>,
+    dir_y: Vec<f32>,
+    velocity: Vec<f32>,
+    wobble: Vec<f32>,
+    wobble_speed: Vec<f32>,
+    tilt_angle: Vec<f32>,
+    life_remaining: Vec<f32>,
+    color: Vec<IString>,
+    shape: Vec<Shape>,
+    trigger: Vec<u32>,
+    cannon: Vec<u32>,
+}
+
+impl Particles {
+    pub(crate) fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    pub(crate) fn get(&self, i: usize) -> Fetti {
+        Fetti {
+            x: self.x[i],
+            y: self.y[i],
+            dir_x: self.dir_x[i],
+            dir_y: self.dir_y[i],
+            velocity: self.velocity[i],
+            wobble: self.wobble[i],
+            wobble_speed: self.wobble_speed[i],
+            tilt_angle: self.tilt_angle[i],
+            life_remaining: self.life_remaining[i],
+            color: self.color[i].clone(),
+            shape: self.shape[i],
+            trigger: self.trigger[i],
+            cannon: self.cannon[i],
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Fetti> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+
+    pub(crate) fn color(&self, i: usize) -> &IString {
+        &self.color[i]
+    }
+
+    /// Opacity, fading out over the particle's `lifespan`.
+    pub(crate) fn alpha(&self, i: usize, lifespan: f32) -> f32 {
+        self.life_remaining[i] / lifespan
+    }
+
+    /// Whether any particle was emitted by `trigger`.
+    pub(crate) fn has_trigger(&self, trigger: u32) -> bool {
+        self.trigger.contains(&trigger)
+    }
+
+    /// How many particles `cannon` emitted, see [`Fetti::cannon`].
+    pub(crate) fn cannon_count(&self, cannon: u32) -> usize {
+        self.cannon.iter().filter(|&&c| c == cannon).count()
+    }
+
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        for_each_column!(self, |column| column.reserve(additional));
+    }
+
+    pub(crate) fn push(&mut self, fetti: Fetti) {
+        self.x.push(fetti.x);
+        self.y.push(fetti.y);
+        self.dir_x.push(fetti.dir_x);
+        self.dir_y.push(fetti.dir_y);
+        self.velocity.push(fetti.velocity);
+        self.wobble.push(fetti.wobble);
+        self.wobble_speed.push(fetti.wobble_speed);
+        self.tilt_angle.push(fetti.tilt_angle);
+        self.life_remaining.push(fetti.life_remaining);
+        self.color.push(fetti.color);
+        self.shape.push(fetti.shape);
+        self.trigger.push(fetti.trigger);
+        self.cannon.push(fetti.cannon);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        for_each_column!(self, |column| column.clear());
+    }
+
+    /// Give particles of the emitter at `index` with a color of `old` the color at the same
+    /// index of `new`, e.g. to switch themes mid-animation.
+    pub(crate) fn recolor(&mut self, index: usize, old: &[IString], new: &[IString]) {
+        if new.is_empty() {
+            return;
+        }
+        let cannon = index as u32 + 1;
+        for (color, _) in self
+            .color
+            .iter_mut()
+            .zip(&self.cannon)
+            .filter(|(_, &c)| c == cannon)
+        {
+            if let Some(i) = old.iter().position(|c| c == color) {
+                *color = new[i % new.len()].clone();
+            }
+        }
+    }
+
+    /// Forget which trigger emitted each particle.
+    pub(crate) fn untrack(&mut self) {
+        self.trigger.fill(0);
+    }
+
+    /// Remove the newest particles, keeping `len`.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        for_each_column!(self, |column| column.truncate(len));
+    }
+
+    /// Remove the `count` oldest particles.
+    pub(crate) fn remove_oldest(&mut self, count: usize) {
+        for_each_column!(self, |column| drop(column.drain(..count)));
+    }
+
+    /// Advance by `delta` seconds, and remove expired particles. Motion is scaled by `scale`,
+    /// horizontally and vertically.
+    pub(crate) fn update(&mut self, delta: f32, physics: &Physics, scale: (f32, f32)) {
+        let decay = physics.decay.powf(delta);
+        let (delta_x, delta_y) = (delta * scale.0, delta * scale.1);
+        #[cfg(all(
+            feature = "simd128",
+            target_arch = "wasm32",
+            target_feature = "simd128"
+        ))]
+        let start = self.update_simd(delta, (delta_x, delta_y), decay, physics);
+        #[cfg(not(all(
+            feature = "simd128",
+            target_arch = "wasm32",
+            target_feature = "simd128"
+        )))]
+        let start = 0;
+        for i in start..self.len() {
+            self.x[i] += (self.dir_x[i] * self.velocity[i] + physics.drift) * delta_x;
+            self.y[i] += (self.dir_y[i] * self.velocity[i] - physics.gravity) * delta_y;
+            self.velocity[i] *= decay;
+            self.wobble[i] += self.wobble_speed[i] * delta;
+            self.tilt_angle[i] += 0.1 * delta;
+            self.life_remaining[i] -= delta;
+        }
+
+        // Compact in place, preserving order.
+        let mut alive = 0;
+        for i in 0..self.len() {
+            if self.life_remaining[i] > 0.0 {
+                if alive != i {
+                    for_each_column!(&mut *self, |column| column.swap(alive, i));
+                }
+                alive += 1;
+            }
+        }
+        self.truncate(alive);
+    }
+
+    /// Like [`Particles::update`], without removing expired particles, for groups of 4
+    /// particles. Returns how many particles were updated.
+    #[cfg(all(
+        feature = "simd128",
+        target_arch = "wasm32",
+        target_feature = "simd128"
+    ))]
+    fn update_simd(
+        &mut self,
+        delta: f32,
+        (delta_x, delta_y): (f32, f32),
+        decay: f32,
+        physics: &Physics,
+    ) -> usize {
+        use core::arch::wasm32::*;
+
+        let len = self.len() / 4 * 4;
+        let delta_4 = f32x4_splat(delta);
+        let delta_x_4 = f32x4_splat(delta_x);
+        let delta_y_4 = f32x4_splat(delta_y);
+        let decay_4 = f32x4_splat(decay);
+        let drift_4 = f32x4_splat(physics.drift);
+        let gravity_4 = f32x4_splat(physics.gravity);
+        let tilt_4 = f32x4_splat(0.1 * delta);
+
+        for i in (0..len).step_by(4) {
+            // SAFETY: All columns have `self.len()` elements and `i + 4 <= self.len()`.
+            // `v128_load` and `v128_store` don't require alignment.
+            unsafe {
+                let load = |column: &[f32]| v128_load(column.as_ptr().add(i) as *const v128);
+                let store = |column: &mut [f32], value: v128| {
+                    v128_store(column.as_mut_ptr().add(i) as *mut v128, value)
+                };
+
+                let velocity = load(&self.velocity);
+                let x = f32x4_add(
+                    load(&self.x),
+                    f32x4_mul(
+                        f32x4_add(f32x4_mul(load(&self.dir_x), velocity), drift_4),
+                        delta_x_4,
+                    ),
+                );
+                let y = f32x4_add(
+                    load(&self.y),
+                    f32x4_mul(
+                        f32x4_sub(f32x4_mul(load(&self.dir_y), velocity), gravity_4),
+                        delta_y_4,
+                    ),
+                );
+                let wobble = f32x4_add(
+                    load(&self.wobble),
+                    f32x4_mul(load(&self.wobble_speed), delta_4),
+                );
+                let tilt_angle = f32x4_add(load(&self.tilt_angle), tilt_4);
+                let life_remaining = f32x4_sub(load(&self.life_remaining), delta_4);
+                store(&mut self.x, x);
+                store(&mut self.y, y);
+                store(&mut self.velocity, f32x4_mul(velocity, decay_4));
+                store(&mut self.wobble, wobble);
+                store(&mut self.tilt_angle, tilt_angle);
+                store(&mut self.life_remaining, life_remaining);
+            }
+        }
+        len
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::{self, Debug, Formatter};
+use std::rc::Rc;
+
+/// Source of uniformly random bits, for [`Randomness::new`].
+pub trait RandomSource {
+    fn next_u64(&mut self) -> u64;
+}
+
+/// Small, fast, non-cryptographic random number generator (xorshift64*), the default
+/// [`RandomSource`].
+#[derive(Clone, Debug)]
+struct Xorshift(u64);
+
+impl Xorshift {
+    fn new(seed: u64) -> Self {
+        // Scramble the seed (splitmix64), so similar seeds diverge and zero is valid.
+        let mut z = seed.wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        Self((z ^ (z >> 31)) | 1)
+    }
+}
+
+impl RandomSource for Xorshift {
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+}
+
+#[cfg(feature = "rand")]
+impl RandomSource for rand::rngs::SmallRng {
+    fn next_u64(&mut self) -> u64 {
+        rand::RngCore::next_u64(self)
+    }
+}
+
+/// Creates the [`RandomSource`] of each cannon from a seed, see
+/// [`ConfettiProps::randomness`](crate::ConfettiProps::randomness). Compares by identity.
+#[derive(Clone)]
+pub struct Randomness(Rc<dyn Fn(u64) -> Box<dyn RandomSource>>);
+
+impl Randomness {
+    /// Use sources created by `source`, which should return the same sequence for the same
+    /// seed if particles are to be deterministic.
+    pub fn new<R: RandomSource + 'static>(source: impl Fn(u64) -> R + 'static) -> Self {
+        Self(Rc::new(move |seed| Box::new(source(seed))))
+    }
+
+    /// Use [`rand::rngs::SmallRng`].
+    #[cfg(feature = "rand")]
+    pub fn small_rng() -> Self {
+        Self::new(<rand::rngs::SmallRng as rand::SeedableRng>::seed_from_u64)
+    }
+}
+
+thread_local! {
+    /// Shared, so default props compare equal and re-renders don't restart randomness.
+    static DEFAULT: Randomness = Randomness::new(Xorshift::new);
+}
+
+/// The built-in xorshift generator.
+impl Default for Randomness {
+    fn default() -> Self {
+        DEFAULT.with(Randomness::clone)
+    }
+}
+
+impl PartialEq for Randomness {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Debug for Randomness {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Randomness").finish_non_exhaustive()
+    }
+}
+
+/// Random floats from a [`RandomSource`].
+pub(crate) struct Rng(Box<dyn RandomSource>);
+
+impl Rng {
+    /// The built-in generator, seeded with `seed`.
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(Box::new(Xorshift::new(seed)))
+    }
+
+    pub(crate) fn with(randomness: &Randomness, seed: u64) -> Self {
+        Self((randomness.0)(seed))
+    }
+
+    /// Uniform in `0.0..1.0`.
+    pub(crate) fn unit(&mut self) -> f32 {
+        (self.0.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform in `0.0..max`.
+    pub(crate) fn max(&mut self, max: f32) -> f32 {
+        self.unit() * max
+    }
+
+    /// Uniform in `min..max`.
+    pub(crate) fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + self.max(max - min)
+    }
+}
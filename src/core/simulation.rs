@@ -0,0 +1,150 @@
+use super::{Emitter, Engine, Fetti, ParticleInit, Physics, Randomness, Shape, Viewport};
+use implicit_clone::unsync::IString;
+
+/// Confetti physics and emitter schedules, without rendering or Yew, for custom render loops
+/// or other frameworks. Positions are normalized, with 0.0 at the left or bottom edge and 1.0
+/// at the right or top edge.
+///
+/// ```
+/// use yew_confetti::{Emitter, Mode, Physics, Simulation};
+///
+/// let mut simulation = Simulation::new(Physics::default(), 42);
+/// simulation.add_emitter(Emitter {
+///     mode: Mode::burst(100),
+///     ..Default::default()
+/// });
+/// simulation.step(1.0 / 60.0);
+/// assert_eq!(simulation.particles().count(), 100);
+/// ```
+pub struct Simulation {
+    engine: Engine,
+    emitters: Vec<Emitter>,
+    physics: Physics,
+    /// Time not yet simulated, in milliseconds, since steps are whole milliseconds.
+    remainder: f32,
+}
+
+impl Simulation {
+    /// Create an empty simulation, with randomness derived from `seed`. Invalid physics are
+    /// clamped.
+    pub fn new(mut physics: Physics, seed: u64) -> Self {
+        physics.clamp();
+        let mut engine = Engine::default();
+        engine.reseed(seed);
+        Self {
+            engine,
+            emitters: Vec::new(),
+            physics,
+            remainder: 0.0,
+        }
+    }
+
+    /// Replace the source of randomness, restarting it from the seed.
+    pub fn set_randomness(&mut self, randomness: Randomness) {
+        self.engine.set_randomness(randomness);
+    }
+
+    /// Add an emitter, whose schedule starts now, clamping invalid values. Returns its index,
+    /// for [`Simulation::fire`].
+    pub fn add_emitter(&mut self, mut emitter: Emitter) -> usize {
+        emitter.clamp();
+        self.emitters.push(emitter);
+        self.engine.sync_clocks(
+            self.emitters
+                .iter()
+                .enumerate()
+                .map(|(i, emitter)| (super::ClockKey::Index(i), emitter.mode)),
+        );
+        self.emitters.len() - 1
+    }
+
+    /// Emit a burst from the emitter at `index` now, regardless of its schedule. See
+    /// [`Mode::burst_count`](super::Mode::burst_count).
+    pub fn fire(&mut self, index: usize) {
+        if let Some(emitter) = self.emitters.get(index) {
+            let count = emitter.mode.burst_count();
+            self.engine
+                .emit(Some(index), emitter, count, &self.physics, 0);
+        }
+    }
+
+    /// Emit particles with explicit initial states now.
+    pub fn spawn(&mut self, particles: impl IntoIterator<Item = ParticleInit>) {
+        self.engine
+            .spawn(particles.into_iter().collect(), &self.physics, 0);
+    }
+
+    /// Advance by `dt` seconds, emitting on schedule and moving particles. Steps longer than
+    /// half a second are truncated, like when a browser tab is in the background.
+    pub fn step(&mut self, dt: f32) {
+        self.remainder += dt.max(0.0) * 1000.0;
+        let delta = self.remainder as u64;
+        self.remainder -= delta as f32;
+        self.engine.step(delta, &self.emitters, &self.physics);
+    }
+
+    /// Live particles, in order of emission.
+    pub fn particles(&self) -> impl Iterator<Item = Particle> + '_ {
+        let lifespan = self.physics.lifespan;
+        self.engine
+            .particles
+            .iter()
+            .map(move |fetti| Particle { fetti, lifespan })
+    }
+
+    /// Whether all particles have expired, and no more are scheduled.
+    pub fn is_idle(&self) -> bool {
+        self.engine.particles.is_empty() && self.engine.is_done(&self.emitters)
+    }
+
+    /// Simulated time, in seconds.
+    pub fn time(&self) -> f32 {
+        self.engine.time as f32 * 0.001
+    }
+}
+
+/// A particle of a [`Simulation`].
+#[derive(Clone)]
+pub struct Particle {
+    fetti: Fetti,
+    lifespan: f32,
+}
+
+impl Particle {
+    /// Horizontal position. 0.0 means left edge, 1.0 means right edge.
+    pub fn x(&self) -> f32 {
+        self.fetti.x
+    }
+
+    /// Vertical position. 0.0 means bottom edge, 1.0 means top edge.
+    pub fn y(&self) -> f32 {
+        self.fetti.y
+    }
+
+    /// Opacity, fading from 1.0 when emitted to 0.0 when it expires.
+    pub fn alpha(&self) -> f32 {
+        self.fetti.alpha(self.lifespan)
+    }
+
+    /// CSS color.
+    pub fn color(&self) -> &IString {
+        &self.fetti.color
+    }
+
+    pub fn shape(&self) -> Shape {
+        self.fetti.shape
+    }
+
+    /// Corners of the particle, in order around the perimeter, in pixels of a `width` by
+    /// `height` canvas with the origin at the top left. `scalar` is the particle size, like
+    /// [`ConfettiProps::scalar`](crate::ConfettiProps::scalar). A [`Shape::Circle`] is the
+    /// ellipse inscribed in them.
+    pub fn corners(&self, width: f32, height: f32, scalar: f32) -> [[f32; 2]; 4] {
+        let viewport = Viewport {
+            width,
+            height,
+            scale: 1.0,
+        };
+        self.fetti.geometry(scalar, viewport, 2.5).corners()
+    }
+}
@@ -0,0 +1,425 @@
+use super::{
+    ClockKey, Emitter, Engine, Mode, ParticleInit, Pattern, Physics, Rng, Transform, Units,
+    Viewport,
+};
+use implicit_clone::unsync::{IArray, IString};
+
+fn emitter(mode: Mode) -> Emitter {
+    Emitter {
+        mode,
+        ..Default::default()
+    }
+}
+
+fn engine(emitters: &[Emitter]) -> Engine {
+    let mut engine = Engine::default();
+    engine.reseed(42);
+    engine.sync_clocks(
+        emitters
+            .iter()
+            .enumerate()
+            .map(|(i, emitter)| (ClockKey::Index(i), emitter.mode)),
+    );
+    engine
+}
+
+/// Particles outlive the tests, so emitted particles can be counted.
+fn immortal() -> Physics {
+    Physics {
+        lifespan: 1000.0,
+        ..Default::default()
+    }
+}
+
+/// Irregular frame times, in milliseconds, short enough that no time is skipped.
+fn frames(seed: u64) -> impl Iterator<Item = u64> {
+    let mut rng = Rng::new(seed);
+    std::iter::repeat_with(move || 1 + rng.max(120.0) as u64)
+}
+
+/// Particles emitted in each millisecond.
+fn per_millisecond(emitters: &[Emitter], duration: u64) -> Vec<usize> {
+    let mut engine = engine(emitters);
+    (0..duration)
+        .map(|_| engine.step(1, emitters, &immortal()))
+        .collect()
+}
+
+#[test]
+fn continuous_emits_rate_in_every_second() {
+    for rate in [1, 7, 60, 100, 333, 999, 1000] {
+        let emitters = [emitter(Mode::continuous(rate))];
+        let counts = per_millisecond(&emitters, 3000);
+        for window in counts.windows(1000) {
+            let count = window.iter().sum::<usize>();
+            assert!(count.abs_diff(rate) <= 1, "{count} particles at {rate}/s");
+        }
+    }
+}
+
+#[test]
+fn emission_is_independent_of_frame_times() {
+    let emitters = [
+        emitter(Mode::continuous(100)),
+        emitter(Mode::delayed_finite_continuous(250, 0.3, 1.1)),
+        emitter(Mode::delayed_burst(40, 0.5)),
+        emitter(Mode::repeating_burst(3, 0.2)),
+    ];
+    let counts = per_millisecond(&emitters, 4000);
+    for seed in 0..20 {
+        let mut engine = engine(&emitters);
+        let mut emitted = 0;
+        for delta in frames(seed) {
+            emitted += engine.step(delta, &emitters, &immortal());
+            let time = engine.time as usize;
+            if time >= counts.len() {
+                break;
+            }
+            assert_eq!(emitted, counts[..time].iter().sum::<usize>(), "at {time}ms");
+        }
+    }
+}
+
+#[test]
+fn particles_never_outlive_lifespan() {
+    let emitters = [emitter(Mode::finite_continuous(200, 1.0))];
+    let physics = Physics {
+        lifespan: 0.5,
+        ..Default::default()
+    };
+    let mut engine = engine(&emitters);
+    for delta in frames(1) {
+        engine.step(delta, &emitters, &physics);
+        for i in 0..engine.particles.len() {
+            let alpha = engine.particles.alpha(i, physics.lifespan);
+            assert!(alpha > 0.0 && alpha <= 1.0, "alpha {alpha}");
+        }
+        if engine.time > 1500 {
+            assert!(engine.particles.is_empty());
+            assert!(engine.is_done(&emitters));
+        }
+        if engine.time > 3000 {
+            break;
+        }
+    }
+}
+
+#[test]
+fn bursts_emit_on_schedule() {
+    let emitters = [
+        emitter(Mode::delayed_burst(50, 0.25)),
+        emitter(Mode::repeating_burst(10, 0.1)),
+    ];
+    let mut engine = engine(&emitters);
+    let mut bursts = Vec::new();
+    while engine.time < 1000 {
+        engine.step(7.min(1000 - engine.time), &emitters, &immortal());
+        bursts.extend(engine.bursts.iter().copied());
+    }
+    assert_eq!(engine.particles.len(), 50 + 10 * 10);
+    assert_eq!(bursts.iter().filter(|(i, _)| *i == 0).count(), 1);
+    assert!(bursts.iter().all(|&(i, count)| count == [50, 10][i]));
+}
+
+#[test]
+fn long_frames_skip_time() {
+    let emitters = [emitter(Mode::continuous(100))];
+    let mut engine = engine(&emitters);
+    let emitted = engine.step(2000, &emitters, &immortal());
+    assert!(emitted.abs_diff(50) <= 1, "{emitted}");
+    assert_eq!(engine.time, 2000);
+}
+
+#[test]
+fn new_emitters_start_their_schedule() {
+    let mut emitters = vec![emitter(Mode::continuous(100))];
+    let mut engine = engine(&emitters);
+    engine.step(400, &emitters, &immortal());
+    emitters.push(emitter(Mode::delayed_burst(20, 0.2)));
+    engine.sync_clocks(
+        emitters
+            .iter()
+            .enumerate()
+            .map(|(i, emitter)| (ClockKey::Index(i), emitter.mode)),
+    );
+    engine.step(200, &emitters, &immortal());
+    assert!(engine.bursts.is_empty());
+    engine.step(1, &emitters, &immortal());
+    assert_eq!(engine.bursts, [(1, 20)]);
+}
+
+#[test]
+fn same_seed_same_particles() {
+    let positions = |emitters: &[Emitter]| {
+        let mut engine = engine(emitters);
+        for delta in frames(2).take(50) {
+            engine.step(delta, emitters, &immortal());
+        }
+        engine
+            .particles
+            .iter()
+            .filter(|fetti| fetti.color == "#26ccff")
+            .map(|fetti| (fetti.x, fetti.y))
+            .collect::<Vec<_>>()
+    };
+    let first = emitter(Mode::continuous(100));
+    let other = Emitter {
+        colors: IArray::Static(&[IString::Static("#000000")]),
+        ..emitter(Mode::continuous(300))
+    };
+    let alone = positions(std::slice::from_ref(&first));
+    assert!(!alone.is_empty());
+    assert_eq!(alone, positions(std::slice::from_ref(&first)));
+    // Other emitters don't change a cannon's particles.
+    assert_eq!(alone, positions(&[first, other]));
+}
+
+#[test]
+fn emission_scale_rounds_on_average() {
+    let emitters = [emitter(Mode::manual(10))];
+    let mut engine = engine(&emitters);
+    engine.emission_scale = 0.25;
+    let emitted = (0..1000)
+        .map(|_| engine.emit(Some(0), &emitters[0], 10, &immortal(), 0))
+        .sum::<usize>();
+    assert!(emitted.abs_diff(2500) < 150, "{emitted}");
+}
+
+#[test]
+fn speed_scale_slows_particles() {
+    let offsets = |speed_scale: f32| {
+        let emitters = [emitter(Mode::burst(10))];
+        let mut engine = engine(&emitters);
+        engine.speed_scale = speed_scale;
+        engine.step(300, &emitters, &immortal());
+        engine
+            .particles
+            .iter()
+            .map(|fetti| fetti.x - 0.5)
+            .collect::<Vec<_>>()
+    };
+    for (full, half) in offsets(1.0).into_iter().zip(offsets(0.5)) {
+        assert!((full * 0.5 - half).abs() < 1e-5, "{full} {half}");
+    }
+}
+
+#[test]
+fn empty_cannons_emit_nothing() {
+    let emitters = [Emitter {
+        colors: IArray::Static(&[]),
+        ..emitter(Mode::continuous(100))
+    }];
+    let mut engine = engine(&emitters);
+    assert_eq!(engine.step(1000, &emitters, &immortal()), 0);
+    assert_eq!(engine.emit(Some(0), &emitters[0], 10, &immortal(), 0), 0);
+}
+
+#[test]
+fn cannons_stay_within_their_budgets() {
+    let emitters = [
+        Emitter {
+            max_particles: Some(30),
+            ..emitter(Mode::continuous(100))
+        },
+        emitter(Mode::delayed_burst(50, 0.6)),
+    ];
+    let mut engine = engine(&emitters);
+    engine.max_particles = 100;
+    assert_eq!(engine.step(500, &emitters, &immortal()), 30);
+    assert_eq!(engine.emit(Some(0), &emitters[0], 10, &immortal(), 0), 0);
+    assert_eq!(engine.step(500, &emitters, &immortal()), 50);
+}
+
+#[test]
+fn cannons_share_the_budget() {
+    let emitters = [emitter(Mode::burst(100)), emitter(Mode::burst(50))];
+    let mut engine = engine(&emitters);
+    engine.max_particles = 30;
+    assert_eq!(engine.step(100, &emitters, &immortal()), 30);
+    assert_eq!(engine.particles.cannon_count(1), 20);
+    assert_eq!(engine.particles.cannon_count(2), 10);
+}
+
+#[test]
+fn patterns_shape_launches() {
+    let mut rng = Rng::new(7);
+    let mut launches = |pattern| {
+        let emitter = Emitter {
+            pattern,
+            ..Default::default()
+        };
+        (0..1000)
+            .map(|_| ParticleInit::from_emitter(&emitter, &mut rng))
+            .collect::<Vec<_>>()
+    };
+    let default = Emitter::default();
+    let fan = launches(Pattern::Fan);
+    assert!(fan
+        .iter()
+        .all(|p| (p.angle - default.angle).abs() <= default.spread * 0.5));
+    let ring = launches(Pattern::Ring);
+    assert!(ring.iter().any(|p| p.angle.sin() < -0.9));
+    assert!(ring.iter().all(|p| p.velocity >= default.velocity * 0.95));
+    let double = launches(Pattern::DoubleSided);
+    let down = double.iter().filter(|p| p.angle.sin() < 0.0).count();
+    assert!((400..600).contains(&down), "{down}");
+}
+
+#[test]
+fn paths_spread_particles_by_length() {
+    let emitter = Emitter {
+        path: IArray::Static(&[(0.0, 0.0), (0.1, 0.0), (0.1, 0.3)]),
+        ..emitter(Mode::burst(1000))
+    };
+    let mut engine = engine(&[]);
+    engine.emit(None, &emitter, 1000, &immortal(), 0);
+    let first = (0..engine.particles.len())
+        .filter(|&i| {
+            let fetti = engine.particles.get(i);
+            let (x, y) = (fetti.x, fetti.y);
+            assert!((0.0..=0.1).contains(&x) && (0.0..=0.3).contains(&y));
+            // Exactly on the first segment, or on the second.
+            assert!(y == 0.0 || x == 0.1, "({x}, {y}) is off the path");
+            y == 0.0
+        })
+        .count();
+    assert!((200..300).contains(&first), "{first}");
+}
+
+#[test]
+fn far_delays_dont_overflow() {
+    let mut mode = Mode::delayed_repeating_burst(usize::MAX, 1e20, 0.001);
+    assert_eq!(mode.0.scheduled(0..u64::MAX), 0);
+    mode.set_delay(0.0);
+    assert_eq!(mode.0.scheduled(0..u64::MAX), usize::MAX);
+    mode.set_delay(f32::INFINITY);
+    assert!(!mode.0.is_done(u64::MAX));
+}
+
+#[test]
+fn mode_kinds_round_trip() {
+    for mode in [
+        Mode::delayed_burst(10, 0.25),
+        Mode::continuous(100),
+        Mode::delayed_finite_continuous(250, 0.3, 1.1),
+        Mode::delayed_repeating_burst(3, 1.0, 0.2),
+        Mode::manual(7),
+        Mode::hover(30, 0.5),
+        Mode::drag(60),
+        Mode::tap(40),
+    ] {
+        assert_eq!(Mode::from(mode.kind()), mode);
+    }
+    let mut mode = Mode::delayed_finite_continuous(100, 1.0, 2.0);
+    mode.set_delay(0.5);
+    mode.set_rate(5000);
+    assert_eq!(mode, Mode::delayed_finite_continuous(1000, 0.5, 2.0));
+    mode.set_duration(None);
+    assert_eq!(mode, Mode::delayed_continuous(1000, 0.5));
+}
+
+#[test]
+fn restored_snapshots_continue() {
+    let emitters = [
+        emitter(Mode::repeating_burst(10, 0.1)),
+        emitter(Mode::delayed_burst(20, 0.5)),
+    ];
+    let mut original = engine(&emitters);
+    original.step(300, &emitters, &immortal());
+    let snapshot = original.snapshot();
+    assert_eq!(snapshot.particle_count(), original.particles.len());
+
+    let mut restored = engine(&emitters);
+    restored.restore(snapshot);
+    restored.sync_clocks(
+        emitters
+            .iter()
+            .enumerate()
+            .map(|(i, emitter)| (ClockKey::Index(i), emitter.mode)),
+    );
+    for engine in [&mut original, &mut restored] {
+        engine.step(300, &emitters, &immortal());
+    }
+    assert_eq!(restored.time, 600);
+    assert_eq!(restored.particles.len(), original.particles.len());
+    assert_eq!(restored.particles.len(), 10 * 6 + 20);
+}
+
+#[test]
+fn pixels_move_the_same_at_any_size() {
+    let moved = |width: f32, height: f32| {
+        let viewport = Viewport {
+            width,
+            height,
+            scale: 1.0,
+        };
+        let mut engine = engine(&[]);
+        engine.unit_scale = Units::Pixels.scale(viewport, true);
+        engine.spawn(
+            vec![ParticleInit {
+                x: 0.5,
+                y: 0.5,
+                angle: 1.0,
+                velocity: 100.0,
+                ..Default::default()
+            }],
+            &immortal(),
+            0,
+        );
+        engine.step(500, &[], &immortal());
+        let fetti = engine.particles.get(0);
+        ((fetti.x - 0.5) * width, (fetti.y - 0.5) * height)
+    };
+    let (small, large) = (moved(200.0, 100.0), moved(800.0, 800.0));
+    assert!(small.0 > 10.0 && small.1 > 10.0, "{small:?}");
+    assert!((small.0 - large.0).abs() < 0.01 && (small.1 - large.1).abs() < 0.01);
+}
+
+#[test]
+fn aspect_correction_keeps_angles() {
+    let physics = Physics {
+        gravity: 0.0,
+        ..immortal()
+    };
+    let viewport = Viewport {
+        width: 800.0,
+        height: 200.0,
+        scale: 1.0,
+    };
+    let slope = |aspect_correct| {
+        let mut engine = engine(&[]);
+        engine.unit_scale = Units::Normalized.scale(viewport, aspect_correct);
+        engine.spawn(
+            vec![ParticleInit {
+                x: 0.5,
+                y: 0.5,
+                angle: std::f32::consts::FRAC_PI_4,
+                velocity: 0.5,
+                ..Default::default()
+            }],
+            &physics,
+            0,
+        );
+        engine.step(100, &[], &physics);
+        let fetti = engine.particles.get(0);
+        (fetti.y - 0.5) * viewport.height / ((fetti.x - 0.5) * viewport.width)
+    };
+    assert!((slope(true) - 1.0).abs() < 1e-3, "{}", slope(true));
+    assert!(slope(false) < 0.5, "{}", slope(false));
+}
+
+#[test]
+fn transforms_invert() {
+    let viewport = Viewport {
+        width: 300.0,
+        height: 200.0,
+        scale: 2.0,
+    };
+    let transform = Transform::new()
+        .scale(1.5)
+        .rotate(0.4)
+        .translate(10.0, -20.0);
+    let [x, y] = transform.apply([90.0, 40.0], viewport);
+    let (x, y) = transform.invert((x / 300.0, 1.0 - y / 200.0), viewport);
+    assert!((x * 300.0 - 90.0).abs() < 1e-3 && ((1.0 - y) * 200.0 - 40.0).abs() < 1e-3);
+}
@@ -0,0 +1,111 @@
+//! Overlay for tuning cannons, see [`ConfettiProps::debug`](crate::ConfettiProps::debug).
+
+use crate::core::{Emitter, Viewport};
+use std::f64::consts::TAU;
+use web_sys::CanvasRenderingContext2d;
+
+/// Smoothed frames per second.
+#[derive(Default)]
+pub(crate) struct FrameRate {
+    last_frame: Option<f64>,
+    fps: f64,
+}
+
+impl FrameRate {
+    /// Count a frame rendered at `raw_time`, in milliseconds.
+    pub(crate) fn frame(&mut self, raw_time: f64) {
+        let delta = raw_time - self.last_frame.unwrap_or(raw_time);
+        self.last_frame = Some(raw_time);
+        if delta <= 0.0 {
+            return;
+        }
+        let fps = 1000.0 / delta;
+        // Start over after the animation slept, instead of averaging in the gap.
+        self.fps = if self.fps == 0.0 || delta > 500.0 {
+            fps
+        } else {
+            self.fps * 0.9 + fps * 0.1
+        };
+    }
+
+    pub(crate) fn fps(&self) -> f64 {
+        self.fps
+    }
+}
+
+/// Performance of a rendered frame, see [`ConfettiProps::on_stats`](crate::ConfettiProps::on_stats).
+/// The overlay shows these, besides the cannons.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ConfettiStats {
+    /// Particles alive, including those of scenes drawn on the canvas.
+    pub particles: usize,
+    /// Frames rendered per second, smoothed.
+    pub fps: f64,
+    /// Milliseconds spent simulating and drawing the frame.
+    pub frame_time: f64,
+}
+
+/// Line segments approximating the arc of an emission cone.
+const CONE_SEGMENTS: u32 = 16;
+
+/// Draw each cannon's origin, emission cone (angle ± half the spread), and velocity vector
+/// (distance per second, in units scaled by `unit_scale`), and `stats` in the corner. Expects
+/// the identity transform.
+pub(crate) fn draw(
+    context: &CanvasRenderingContext2d,
+    viewport: Viewport,
+    unit_scale: (f32, f32),
+    emitters: &[Emitter],
+    stats: &ConfettiStats,
+) {
+    let (width, height) = (viewport.width as f64, viewport.height as f64);
+    let scale = viewport.scale as f64;
+    context.set_global_alpha(1.0);
+    context.set_line_width(scale);
+    context.set_stroke_style_str("#ff00ff");
+    context.set_fill_style_str("#ff00ff");
+    for emitter in emitters {
+        let x = emitter.x as f64 * width;
+        let y = (1.0 - emitter.y as f64) * height;
+        let angle = emitter.angle as f64;
+        let half_spread = (emitter.spread as f64 * 0.5).min(TAU * 0.5);
+        // Where a particle launched at `angle` would be after a second without forces. Like
+        // positions, scaled velocities are relative to the canvas size, and y points up.
+        let velocity = emitter.velocity as f64;
+        let (scale_x, scale_y) = (unit_scale.0 as f64, unit_scale.1 as f64);
+        let offset = |angle: f64| {
+            let (sin, cos) = angle.sin_cos();
+            (
+                cos * velocity * scale_x * width,
+                -sin * velocity * scale_y * height,
+            )
+        };
+
+        context.begin_path();
+        let _ = context.arc(x, y, 3.0 * scale, 0.0, TAU);
+        context.fill();
+
+        context.begin_path();
+        context.move_to(x, y);
+        for i in 0..=CONE_SEGMENTS {
+            let t = i as f64 / CONE_SEGMENTS as f64;
+            let (dx, dy) = offset(angle - half_spread + 2.0 * half_spread * t);
+            context.line_to(x + dx, y + dy);
+        }
+        context.close_path();
+        context.set_global_alpha(0.2);
+        context.fill();
+        context.set_global_alpha(1.0);
+
+        let (dx, dy) = offset(angle);
+        context.begin_path();
+        context.move_to(x, y);
+        context.line_to(x + dx, y + dy);
+        context.stroke();
+    }
+
+    context.set_font(&format!("{}px monospace", (12.0 * scale).round()));
+    context.set_text_baseline("top");
+    let text = format!("{} particles, {:.0} fps", stats.particles, stats.fps);
+    let _ = context.fill_text(&text, 4.0 * scale, 4.0 * scale);
+}
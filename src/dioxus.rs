@@ -0,0 +1,250 @@
+//! [`Confetti`] and [`Cannon`] for Dioxus.
+
+use crate::{
+    CannonConfig, CanvasConfetti, ConfettiConfig, ConfettiError, Keyframe, Mode, Pattern, Shape,
+};
+use ::dioxus::prelude::*;
+use implicit_clone::unsync::IString;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::HtmlCanvasElement;
+
+/// Imperative control over a [`Confetti`], obtained from [`use_confetti`] and passed to
+/// `handle`, like the Yew [`ConfettiHandle`](crate::ConfettiHandle).
+#[derive(Clone, Default)]
+pub struct ConfettiHandle(Rc<RefCell<Option<CanvasConfetti>>>);
+
+impl PartialEq for ConfettiHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl ConfettiHandle {
+    /// Emit a burst from every cannon.
+    pub fn fire(&self) {
+        if let Some(confetti) = &*self.0.borrow() {
+            confetti.fire();
+        }
+    }
+
+    /// Emit a burst from the cannon at `index`, counting the cannons of the config first.
+    pub fn fire_cannon(&self, index: usize) {
+        if let Some(confetti) = &*self.0.borrow() {
+            confetti.fire_cannon(index);
+        }
+    }
+
+    /// Remove all particles and stop the schedules until the config or cannons change.
+    pub fn stop(&self) {
+        if let Some(confetti) = &*self.0.borrow() {
+            confetti.stop();
+        }
+    }
+}
+
+/// A [`ConfettiHandle`] to pass to a [`Confetti`].
+pub fn use_confetti() -> ConfettiHandle {
+    use_hook(ConfettiHandle::default)
+}
+
+/// The [`Cannon`]s of a [`Confetti`], in the order they were created.
+#[derive(Clone, Copy)]
+struct Cannons {
+    list: Signal<Vec<CannonEntry>>,
+    next_id: Signal<u64>,
+}
+
+struct CannonEntry {
+    id: u64,
+    config: CannonConfig,
+    on_burst: Option<EventHandler<usize>>,
+}
+
+/// Confetti animation for Dioxus, like the Yew [`Confetti`](crate::Confetti), with the
+/// cannons of `config` followed by [`Cannon`] children.
+#[component]
+pub fn Confetti(
+    /// Physics and drawing. Changing it keeps particles, and the schedules of unchanged
+    /// cannons.
+    config: ReadOnlySignal<Option<ConfettiConfig>>,
+    /// See [`use_confetti`].
+    handle: Option<ConfettiHandle>,
+    /// Inline style to apply to the canvas.
+    #[props(default)]
+    style: String,
+    /// See [`ConfettiProps::on_complete`](crate::ConfettiProps::on_complete).
+    on_complete: Option<EventHandler<()>>,
+    /// See [`ConfettiProps::on_error`](crate::ConfettiProps::on_error).
+    on_error: Option<EventHandler<ConfettiError>>,
+    /// [`Cannon`]s, and anything else to render after the canvas.
+    children: Element,
+) -> Element {
+    let own_handle = use_confetti();
+    let handle = handle.unwrap_or(own_handle);
+    let cannons = use_context_provider(|| Cannons {
+        list: Signal::new(Vec::new()),
+        next_id: Signal::new(0),
+    });
+    let mut canvas = use_signal(|| None::<HtmlCanvasElement>);
+    // By index, for `CanvasConfetti::set_on_burst`.
+    let on_bursts = use_hook(|| Rc::new(RefCell::new(Vec::<Option<EventHandler<usize>>>::new())));
+    let failed = use_hook(|| Rc::new(Cell::new(false)));
+    {
+        let handle = handle.clone();
+        use_effect(move || {
+            let mut config = config.read().clone().unwrap_or_default();
+            {
+                let list = cannons.list.read();
+                let mut callbacks = vec![None; config.cannons.len()];
+                callbacks.extend(list.iter().map(|entry| entry.on_burst));
+                *on_bursts.borrow_mut() = callbacks;
+                config
+                    .cannons
+                    .extend(list.iter().map(|entry| entry.config.clone()));
+            }
+            let Some(canvas) = canvas.read().clone() else {
+                return;
+            };
+            if failed.get() {
+                return;
+            }
+            let mut confetti = handle.0.borrow_mut();
+            if let Some(confetti) = &*confetti {
+                confetti.set_config(&config);
+                return;
+            }
+            match CanvasConfetti::new(&canvas, &config) {
+                Ok(new) => {
+                    let on_bursts = Rc::clone(&on_bursts);
+                    new.set_on_burst(move |i, count| {
+                        let on_burst = on_bursts.borrow().get(i).copied().flatten();
+                        if let Some(on_burst) = on_burst {
+                            on_burst.call(count);
+                        }
+                    });
+                    new.set_on_complete(move || {
+                        if let Some(on_complete) = on_complete {
+                            on_complete.call(());
+                        }
+                    });
+                    *confetti = Some(new);
+                }
+                Err(error) => {
+                    failed.set(true);
+                    drop(confetti);
+                    if let Some(on_error) = on_error {
+                        on_error.call(error);
+                    }
+                }
+            }
+        });
+    }
+    {
+        let handle = handle.clone();
+        use_drop(move || {
+            let confetti = handle.0.borrow_mut().take();
+            drop(confetti);
+        });
+    }
+    let (width, height) = config.with(|config| {
+        let config = config.clone().unwrap_or_default();
+        (config.width, config.height)
+    });
+    rsx! {
+        canvas {
+            width: "{width}",
+            height: "{height}",
+            style: "{style}",
+            "aria-hidden": "true",
+            onmounted: move |event| {
+                let element = event.data().downcast::<web_sys::Element>().cloned();
+                canvas.set(element.and_then(|element| element.dyn_into().ok()));
+            },
+        }
+        {children}
+    }
+}
+
+/// A cannon of the enclosing [`Confetti`], like the Yew [`Cannon`](crate::Cannon). Props
+/// default to those of [`CannonConfig`].
+#[component]
+pub fn Cannon(
+    /// See [`CannonProps::x`](crate::CannonProps::x).
+    x: Option<f32>,
+    /// See [`CannonProps::y`](crate::CannonProps::y).
+    y: Option<f32>,
+    /// See [`CannonProps::path`](crate::CannonProps::path).
+    #[props(default)]
+    path: &'static [(f32, f32)],
+    /// See [`CannonProps::angle`](crate::CannonProps::angle).
+    angle: Option<f32>,
+    /// See [`CannonProps::spread`](crate::CannonProps::spread).
+    spread: Option<f32>,
+    /// See [`CannonProps::pattern`](crate::CannonProps::pattern).
+    pattern: Option<Pattern>,
+    /// See [`CannonProps::velocity`](crate::CannonProps::velocity).
+    velocity: Option<f32>,
+    /// See [`CannonProps::shapes`](crate::CannonProps::shapes).
+    shapes: Option<&'static [Shape]>,
+    /// See [`CannonProps::colors`](crate::CannonProps::colors).
+    colors: Option<&'static [&'static str]>,
+    /// See [`CannonProps::mode`](crate::CannonProps::mode).
+    mode: Option<Mode>,
+    /// See [`CannonProps::keyframes`](crate::CannonProps::keyframes).
+    #[props(default)]
+    keyframes: &'static [Keyframe],
+    /// See [`CannonProps::max_particles`](crate::CannonProps::max_particles).
+    max_particles: Option<usize>,
+    /// See [`CannonProps::group`](crate::CannonProps::group).
+    group: Option<String>,
+    /// See [`CannonProps::on_burst`](crate::CannonProps::on_burst).
+    on_burst: Option<EventHandler<usize>>,
+) -> Element {
+    let mut cannons = use_context::<Cannons>();
+    let id = use_hook(|| {
+        let id = *cannons.next_id.peek();
+        cannons.next_id.set(id + 1);
+        id
+    });
+    let defaults = CannonConfig::default();
+    let config = CannonConfig {
+        x: x.unwrap_or(defaults.x),
+        y: y.unwrap_or(defaults.y),
+        path: path.to_vec(),
+        angle: angle.unwrap_or(defaults.angle),
+        spread: spread.unwrap_or(defaults.spread),
+        pattern: pattern.unwrap_or(defaults.pattern),
+        velocity: velocity.unwrap_or(defaults.velocity),
+        shapes: shapes.map_or(defaults.shapes, <[Shape]>::to_vec),
+        colors: colors.map_or(defaults.colors, |colors| {
+            colors.iter().map(|&c| IString::Static(c)).collect()
+        }),
+        mode: mode.unwrap_or(defaults.mode),
+        keyframes: keyframes.to_vec(),
+        max_particles,
+        group: group.map(IString::from),
+    };
+    use_effect(use_reactive(
+        (&config, &on_burst),
+        move |(config, on_burst)| {
+            let mut list = cannons.list.write();
+            match list.iter_mut().find(|entry| entry.id == id) {
+                Some(entry) => {
+                    entry.config = config;
+                    entry.on_burst = on_burst;
+                }
+                None => list.push(CannonEntry {
+                    id,
+                    config,
+                    on_burst,
+                }),
+            }
+        },
+    ));
+    use_drop(move || {
+        cannons.list.write().retain(|entry| entry.id != id);
+    });
+    rsx! {}
+}
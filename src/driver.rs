@@ -0,0 +1,255 @@
+//! Confetti on a `<canvas>` without Yew, which the adapters for other frameworks wrap.
+
+use crate::core::{ClockKey, Emitter, Engine, Viewport};
+use crate::render::{self, Backend, Renderer};
+use crate::scheduler::{self, Frame};
+use crate::{prefers_reduced_motion, random_seed, ConfettiConfig, ConfettiError, ReducedMotion};
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use web_sys::HtmlCanvasElement;
+
+/// Animates a [`ConfettiConfig`] on a `<canvas>`, until dropped. Like a
+/// [`Confetti`](crate::Confetti), cannons start their schedules when added or changed, and
+/// particles persist across changes.
+pub struct CanvasConfetti {
+    driver: Rc<RefCell<Driver>>,
+}
+
+struct Driver {
+    canvas: HtmlCanvasElement,
+    renderer: Box<dyn Renderer>,
+    engine: Engine,
+    /// The config, for physics and drawing.
+    config: ConfettiConfig,
+    emitters: Vec<Emitter>,
+    /// Called on animation frames.
+    frame: Option<Frame>,
+    animation_frame: Option<u64>,
+    last_raw_time: Option<f64>,
+    /// The response to reduced motion.
+    reduced_motion: ReducedMotion,
+    /// Called once idle, see [`CanvasConfetti::on_idle`].
+    on_idle: Vec<Box<dyn FnOnce()>>,
+    /// See [`CanvasConfetti::set_on_burst`].
+    on_burst: Option<Rc<dyn Fn(usize, usize)>>,
+    /// See [`CanvasConfetti::set_on_complete`].
+    on_complete: Option<Rc<dyn Fn()>>,
+}
+
+impl CanvasConfetti {
+    /// Start animating `config` on `canvas`, drawn at the canvas's size.
+    pub fn new(canvas: &HtmlCanvasElement, config: &ConfettiConfig) -> Result<Self, ConfettiError> {
+        let seed = config.seed.unwrap_or_else(random_seed);
+        let renderer = render::renderer(canvas, &Backend::Canvas, false, seed)
+            .ok_or(ConfettiError::Unsupported(Backend::Canvas))?;
+        let mut engine = Engine::default();
+        engine.reseed(seed);
+        let driver = Rc::new(RefCell::new(Driver {
+            canvas: canvas.clone(),
+            renderer,
+            engine,
+            config: ConfettiConfig::default(),
+            emitters: Vec::new(),
+            frame: None,
+            animation_frame: None,
+            last_raw_time: None,
+            reduced_motion: ReducedMotion::Ignore,
+            on_idle: Vec::new(),
+            on_burst: None,
+            on_complete: None,
+        }));
+        let weak = Rc::downgrade(&driver);
+        driver.borrow_mut().frame = Some(Rc::new(RefCell::new(move |raw_time| {
+            if let Some(driver) = Weak::upgrade(&weak) {
+                let notifications = driver.borrow_mut().tick(raw_time);
+                // Callbacks may drop the `CanvasConfetti`, which borrows the driver.
+                for f in notifications {
+                    f();
+                }
+            }
+        })));
+        let confetti = Self { driver };
+        confetti.set_config(config);
+        Ok(confetti)
+    }
+
+    /// Apply a changed config, keeping particles and the schedules of unchanged cannons.
+    pub fn set_config(&self, config: &ConfettiConfig) {
+        let mut driver = self.driver.borrow_mut();
+        driver.config = config.clone();
+        driver.reduced_motion = config.reduced_motion_response(prefers_reduced_motion());
+        let old = std::mem::take(&mut driver.emitters);
+        driver.emitters = config
+            .cannons
+            .iter()
+            .map(|cannon| {
+                let mut emitter = cannon.emitter();
+                emitter.clamp();
+                emitter
+            })
+            .collect();
+        let driver = &mut *driver;
+        let keys: Vec<_> = (0..driver.emitters.len()).map(ClockKey::Index).collect();
+        driver.engine.recolor(&old, &driver.emitters, &keys);
+        driver.engine.max_particles = config.max_particles;
+        driver.engine.eviction = config.eviction;
+        driver.engine.scheduling = !driver.is_disabled();
+        driver.engine.emission_scale = driver.reduced_motion.rate_scale();
+        driver.engine.speed_scale = driver.reduced_motion.speed_scale();
+        driver.engine.sync_clocks(
+            keys.into_iter()
+                .zip(driver.emitters.iter().map(|emitter| emitter.mode)),
+        );
+        driver.wake();
+    }
+
+    /// Emit a burst from every cannon, see [`ConfettiHandle::fire`](crate::ConfettiHandle::fire).
+    pub fn fire(&self) {
+        let len = self.driver.borrow().emitters.len();
+        for i in 0..len {
+            self.fire_cannon(i);
+        }
+    }
+
+    /// Emit a burst from the cannon at `index`, if any.
+    pub fn fire_cannon(&self, index: usize) {
+        let (on_burst, count) = {
+            let mut driver = self.driver.borrow_mut();
+            if driver.is_disabled() {
+                return;
+            }
+            let driver = &mut *driver;
+            let physics = driver.config.clamped_physics();
+            let Some(emitter) = driver.emitters.get(index) else {
+                return;
+            };
+            let count = emitter.mode.burst_count();
+            let count = driver.engine.emit(Some(index), emitter, count, &physics, 0);
+            driver.wake();
+            (driver.on_burst.clone(), count)
+        };
+        // Callbacks may drop the `CanvasConfetti`, which borrows the driver.
+        if let Some(on_burst) = on_burst {
+            on_burst(index, count);
+        }
+    }
+
+    /// Call `f` with the index of the cannon and the number of particles emitted whenever a
+    /// cannon bursts, like [`CannonProps::on_burst`](crate::CannonProps::on_burst).
+    pub fn set_on_burst(&self, f: impl Fn(usize, usize) + 'static) {
+        self.driver.borrow_mut().on_burst = Some(Rc::new(f));
+    }
+
+    /// Call `f` whenever the animation completes, like
+    /// [`ConfettiProps::on_complete`](crate::ConfettiProps::on_complete).
+    pub fn set_on_complete(&self, f: impl Fn() + 'static) {
+        self.driver.borrow_mut().on_complete = Some(Rc::new(f));
+    }
+
+    /// Whether all particles have expired and no more are scheduled.
+    pub fn is_idle(&self) -> bool {
+        self.driver.borrow().is_idle()
+    }
+
+    /// Remove all particles and stop the schedules until the config is next set, calling the
+    /// [`CanvasConfetti::on_idle`] callbacks.
+    pub fn stop(&self) {
+        let on_idle = {
+            let mut driver = self.driver.borrow_mut();
+            if let Some(id) = driver.animation_frame.take() {
+                scheduler::cancel(id);
+            }
+            driver.engine.particles.clear();
+            driver.engine.scheduling = false;
+            driver.last_raw_time = None;
+            let viewport = driver.viewport();
+            driver.renderer.begin(viewport);
+            driver.renderer.end();
+            std::mem::take(&mut driver.on_idle)
+        };
+        // Callbacks may drop the `CanvasConfetti`, which borrows the driver.
+        for f in on_idle {
+            f();
+        }
+    }
+
+    /// Call `f` once idle, or now if already idle.
+    pub fn on_idle(&self, f: impl FnOnce() + 'static) {
+        if self.is_idle() {
+            f();
+        } else {
+            self.driver.borrow_mut().on_idle.push(Box::new(f));
+        }
+    }
+}
+
+impl Drop for CanvasConfetti {
+    fn drop(&mut self) {
+        if let Some(id) = self.driver.borrow_mut().animation_frame.take() {
+            scheduler::cancel(id);
+        }
+    }
+}
+
+impl Driver {
+    fn is_idle(&self) -> bool {
+        self.engine.particles.is_empty()
+            && (!self.engine.scheduling || self.engine.is_done(&self.emitters))
+    }
+
+    fn is_disabled(&self) -> bool {
+        self.reduced_motion == ReducedMotion::Disable
+    }
+
+    /// Animate until idle.
+    fn wake(&mut self) {
+        if self.animation_frame.is_none() && !self.is_idle() {
+            self.animation_frame = self.frame.as_ref().map(scheduler::request);
+        }
+    }
+
+    /// The size of the canvas.
+    fn viewport(&self) -> Viewport {
+        Viewport {
+            width: self.canvas.width() as f32,
+            height: self.canvas.height() as f32,
+            scale: 1.0,
+        }
+    }
+
+    /// Returns the callbacks to call once the driver isn't borrowed.
+    fn tick(&mut self, raw_time: f64) -> Vec<Box<dyn FnOnce()>> {
+        self.animation_frame = None;
+        let delta = raw_time - self.last_raw_time.unwrap_or(raw_time);
+        self.last_raw_time = Some(raw_time);
+        let physics = self.config.clamped_physics();
+        let viewport = self.viewport();
+        self.engine.unit_scale = self
+            .config
+            .units
+            .scale(viewport, self.config.aspect_correct);
+        self.engine.step(delta as u64, &self.emitters, &physics);
+        self.renderer.begin(viewport);
+        self.renderer
+            .draw(&self.config.appearance(), viewport, &self.engine.particles);
+        self.renderer.end();
+        let mut notifications = Vec::<Box<dyn FnOnce()>>::new();
+        if let Some(on_burst) = &self.on_burst {
+            for &(i, count) in &self.engine.bursts {
+                let on_burst = Rc::clone(on_burst);
+                notifications.push(Box::new(move || on_burst(i, count)));
+            }
+        }
+        if self.is_idle() {
+            // Don't count time spent idle.
+            self.last_raw_time = None;
+            if let Some(on_complete) = self.on_complete.clone() {
+                notifications.push(Box::new(move || on_complete()));
+            }
+            notifications.append(&mut self.on_idle);
+        } else {
+            self.wake();
+        }
+        notifications
+    }
+}
@@ -0,0 +1,30 @@
+use crate::Backend;
+use std::fmt::{self, Display, Formatter};
+
+/// Why confetti can't be shown, see [`ConfettiProps::on_error`](crate::ConfettiProps::on_error).
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfettiError {
+    /// The browser can't draw with the backend, e.g. because the canvas context was blocked
+    /// by fingerprinting protection or too many contexts exist.
+    Unsupported(Backend),
+    /// The cannon at this index, counting [`ConfettiConfig`](crate::ConfettiConfig) cannons
+    /// after children, has no colors or no shapes, so it doesn't emit particles.
+    EmptyCannon(usize),
+    /// Recording video failed, with the browser's message, see
+    /// [`ConfettiHandle::record`](crate::ConfettiHandle::record).
+    #[cfg(feature = "video")]
+    Video(String),
+}
+
+impl Display for ConfettiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(backend) => write!(f, "can't draw confetti with {backend:?}"),
+            Self::EmptyCannon(index) => write!(f, "cannon {index} has no colors or no shapes"),
+            #[cfg(feature = "video")]
+            Self::Video(message) => write!(f, "can't record video: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfettiError {}
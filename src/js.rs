@@ -0,0 +1,69 @@
+//! Confetti for JavaScript, without Yew.
+
+use crate::{CanvasConfetti, ConfettiConfig};
+use js_sys::Promise;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use web_sys::{window, HtmlCanvasElement};
+
+/// A running animation, see [`confetti_js`].
+#[wasm_bindgen]
+pub struct ConfettiAnimation {
+    confetti: Rc<CanvasConfetti>,
+    done: Promise,
+}
+
+#[wasm_bindgen]
+impl ConfettiAnimation {
+    /// A promise that resolves once the animation completes, which continuous and repeating
+    /// cannons only do once stopped.
+    #[wasm_bindgen(getter)]
+    pub fn done(&self) -> Promise {
+        self.done.clone()
+    }
+
+    /// Remove all particles and stop the cannons, resolving [`ConfettiAnimation::done`].
+    pub fn stop(&self) {
+        self.confetti.stop();
+    }
+}
+
+/// Animate confetti on `target`, a `<canvas>` or a CSS selector for one, with the cannons and
+/// physics of `options`, an object in the JSON format of [`ConfettiConfig`]. Only scheduled
+/// cannons emit, since there are no handles to fire manual or hover cannons.
+#[wasm_bindgen(js_name = confetti)]
+pub fn confetti_js(target: JsValue, options: JsValue) -> Result<ConfettiAnimation, JsError> {
+    let canvas = match target.as_string() {
+        Some(selector) => window()
+            .and_then(|w| w.document())
+            .and_then(|document| document.query_selector(&selector).ok().flatten())
+            .ok_or_else(|| JsError::new(&format!("no element matches {selector:?}")))?
+            .dyn_into::<HtmlCanvasElement>()
+            .ok(),
+        None => target.dyn_into::<HtmlCanvasElement>().ok(),
+    }
+    .ok_or_else(|| JsError::new("target isn't a <canvas>"))?;
+    let config = if options.is_undefined() || options.is_null() {
+        ConfettiConfig::default()
+    } else {
+        let json = js_sys::JSON::stringify(&options)
+            .map_err(|_| JsError::new("options aren't JSON"))?
+            .as_string()
+            .unwrap_or_default();
+        ConfettiConfig::from_json(&json).map_err(|e| JsError::new(&e.to_string()))?
+    };
+
+    let confetti =
+        Rc::new(CanvasConfetti::new(&canvas, &config).map_err(|e| JsError::new(&e.to_string()))?);
+    let mut on_done = None;
+    let done = Promise::new(&mut |resolve, _reject| on_done = Some(resolve));
+    // Animate until idle, since nothing else holds the confetti.
+    let keep_alive = Rc::clone(&confetti);
+    confetti.on_idle(move || {
+        drop(keep_alive);
+        if let Some(resolve) = on_done {
+            let _ = resolve.call0(&JsValue::UNDEFINED);
+        }
+    });
+    Ok(ConfettiAnimation { confetti, done })
+}
@@ -0,0 +1,243 @@
+//! [`Confetti`] and [`Cannon`] for Leptos.
+
+use crate::{
+    CannonConfig, CanvasConfetti, ConfettiConfig, ConfettiError, Keyframe, Mode, Pattern, Shape,
+};
+use ::leptos::html::Canvas;
+use ::leptos::prelude::*;
+use implicit_clone::unsync::IString;
+
+/// Imperative control over a [`Confetti`], obtained from [`use_confetti`] and passed to
+/// `handle`, like the Yew [`ConfettiHandle`](crate::ConfettiHandle).
+#[derive(Clone, Copy)]
+pub struct ConfettiHandle(StoredValue<Option<CanvasConfetti>, LocalStorage>);
+
+impl ConfettiHandle {
+    /// Emit a burst from every cannon.
+    pub fn fire(&self) {
+        self.0.with_value(|confetti| {
+            if let Some(confetti) = confetti {
+                confetti.fire();
+            }
+        });
+    }
+
+    /// Emit a burst from the cannon at `index`, counting the cannons of the config first.
+    pub fn fire_cannon(&self, index: usize) {
+        self.0.with_value(|confetti| {
+            if let Some(confetti) = confetti {
+                confetti.fire_cannon(index);
+            }
+        });
+    }
+
+    /// Remove all particles and stop the schedules until the config or cannons change.
+    pub fn stop(&self) {
+        self.0.with_value(|confetti| {
+            if let Some(confetti) = confetti {
+                confetti.stop();
+            }
+        });
+    }
+}
+
+/// A [`ConfettiHandle`] to pass to a [`Confetti`].
+pub fn use_confetti() -> ConfettiHandle {
+    ConfettiHandle(StoredValue::new_local(None))
+}
+
+/// The [`Cannon`]s of a [`Confetti`], in the order they were created.
+#[derive(Clone, Copy)]
+struct Cannons {
+    list: RwSignal<Vec<CannonEntry>, LocalStorage>,
+    next_id: StoredValue<u64>,
+}
+
+struct CannonEntry {
+    id: u64,
+    config: CannonConfig,
+    on_burst: Option<Callback<usize>>,
+}
+
+/// Confetti animation for Leptos, like the Yew [`Confetti`](crate::Confetti), with the
+/// cannons of `config` followed by [`Cannon`] children.
+#[component]
+pub fn Confetti(
+    /// Physics and drawing. Changing it keeps particles, and the schedules of unchanged
+    /// cannons.
+    #[prop(optional, into)]
+    config: Signal<ConfettiConfig, LocalStorage>,
+    /// See [`use_confetti`].
+    #[prop(optional)]
+    handle: Option<ConfettiHandle>,
+    /// Inline style to apply to the canvas.
+    #[prop(optional, into)]
+    style: Option<String>,
+    /// See [`ConfettiProps::on_complete`](crate::ConfettiProps::on_complete).
+    #[prop(optional, into)]
+    on_complete: Option<Callback<()>>,
+    /// See [`ConfettiProps::on_error`](crate::ConfettiProps::on_error).
+    #[prop(optional, into)]
+    on_error: Option<Callback<ConfettiError>>,
+    /// [`Cannon`]s, and anything else to render after the canvas.
+    #[prop(optional)]
+    children: Option<Children>,
+) -> impl IntoView {
+    let handle = handle.unwrap_or_else(use_confetti);
+    let canvas = NodeRef::<Canvas>::new();
+    let cannons = Cannons {
+        list: RwSignal::new_local(Vec::new()),
+        next_id: StoredValue::new(0),
+    };
+    provide_context(cannons);
+    // By index, for `CanvasConfetti::set_on_burst`.
+    let on_bursts = StoredValue::new(Vec::<Option<Callback<usize>>>::new());
+    let failed = StoredValue::new(false);
+    Effect::new(move |_| {
+        let mut config = config.get();
+        cannons.list.with(|list| {
+            let mut callbacks = vec![None; config.cannons.len()];
+            callbacks.extend(list.iter().map(|entry| entry.on_burst));
+            on_bursts.set_value(callbacks);
+            config
+                .cannons
+                .extend(list.iter().map(|entry| entry.config.clone()));
+        });
+        let Some(canvas) = canvas.get() else {
+            return;
+        };
+        if failed.get_value() {
+            return;
+        }
+        let error = handle.0.try_update_value(|confetti| {
+            if let Some(confetti) = confetti {
+                confetti.set_config(&config);
+                return None;
+            }
+            match CanvasConfetti::new(&canvas, &config) {
+                Ok(new) => {
+                    new.set_on_burst(move |i, count| {
+                        let on_burst = on_bursts.with_value(|b| b.get(i).copied().flatten());
+                        if let Some(on_burst) = on_burst {
+                            on_burst.run(count);
+                        }
+                    });
+                    new.set_on_complete(move || {
+                        if let Some(on_complete) = on_complete {
+                            on_complete.run(());
+                        }
+                    });
+                    *confetti = Some(new);
+                    None
+                }
+                Err(error) => Some(error),
+            }
+        });
+        if let Some(error) = error.flatten() {
+            failed.set_value(true);
+            if let Some(on_error) = on_error {
+                on_error.run(error);
+            }
+        }
+    });
+    on_cleanup(move || {
+        handle.0.try_update_value(|confetti| *confetti = None);
+    });
+    view! {
+        <canvas
+            node_ref=canvas
+            width=move || config.with(|config| config.width.to_string())
+            height=move || config.with(|config| config.height.to_string())
+            style=style
+            aria-hidden="true"
+        />
+        {children.map(|children| children())}
+    }
+}
+
+/// A cannon of the enclosing [`Confetti`], like the Yew [`Cannon`](crate::Cannon). Props
+/// default to those of [`CannonConfig`], and reactive ones may change while animating.
+#[component]
+pub fn Cannon(
+    /// See [`CannonProps::x`](crate::CannonProps::x).
+    #[prop(optional, into)]
+    x: MaybeProp<f32>,
+    /// See [`CannonProps::y`](crate::CannonProps::y).
+    #[prop(optional, into)]
+    y: MaybeProp<f32>,
+    /// See [`CannonProps::path`](crate::CannonProps::path).
+    #[prop(optional)]
+    path: &'static [(f32, f32)],
+    /// See [`CannonProps::angle`](crate::CannonProps::angle).
+    #[prop(optional, into)]
+    angle: MaybeProp<f32>,
+    /// See [`CannonProps::spread`](crate::CannonProps::spread).
+    #[prop(optional, into)]
+    spread: MaybeProp<f32>,
+    /// See [`CannonProps::pattern`](crate::CannonProps::pattern).
+    #[prop(optional, into)]
+    pattern: MaybeProp<Pattern>,
+    /// See [`CannonProps::velocity`](crate::CannonProps::velocity).
+    #[prop(optional, into)]
+    velocity: MaybeProp<f32>,
+    /// See [`CannonProps::shapes`](crate::CannonProps::shapes).
+    #[prop(optional)]
+    shapes: Option<&'static [Shape]>,
+    /// See [`CannonProps::colors`](crate::CannonProps::colors).
+    #[prop(optional)]
+    colors: Option<&'static [&'static str]>,
+    /// See [`CannonProps::mode`](crate::CannonProps::mode).
+    #[prop(optional, into)]
+    mode: MaybeProp<Mode>,
+    /// See [`CannonProps::keyframes`](crate::CannonProps::keyframes).
+    #[prop(optional)]
+    keyframes: &'static [Keyframe],
+    /// See [`CannonProps::max_particles`](crate::CannonProps::max_particles).
+    #[prop(optional, into)]
+    max_particles: MaybeProp<usize>,
+    /// See [`CannonProps::group`](crate::CannonProps::group).
+    #[prop(optional, into)]
+    group: Option<String>,
+    /// See [`CannonProps::on_burst`](crate::CannonProps::on_burst).
+    #[prop(optional, into)]
+    on_burst: Option<Callback<usize>>,
+) -> impl IntoView {
+    let cannons = expect_context::<Cannons>();
+    let id = cannons.next_id.get_value();
+    cannons.next_id.set_value(id + 1);
+    Effect::new(move |_| {
+        let defaults = CannonConfig::default();
+        let config = CannonConfig {
+            x: x.get().unwrap_or(defaults.x),
+            y: y.get().unwrap_or(defaults.y),
+            path: path.to_vec(),
+            angle: angle.get().unwrap_or(defaults.angle),
+            spread: spread.get().unwrap_or(defaults.spread),
+            pattern: pattern.get().unwrap_or(defaults.pattern),
+            velocity: velocity.get().unwrap_or(defaults.velocity),
+            shapes: shapes.map_or(defaults.shapes, <[Shape]>::to_vec),
+            colors: colors.map_or(defaults.colors, |colors| {
+                colors.iter().map(|&c| IString::Static(c)).collect()
+            }),
+            mode: mode.get().unwrap_or(defaults.mode),
+            keyframes: keyframes.to_vec(),
+            max_particles: max_particles.get(),
+            group: group.clone().map(IString::from),
+        };
+        cannons
+            .list
+            .update(|list| match list.iter_mut().find(|entry| entry.id == id) {
+                Some(entry) => entry.config = config,
+                None => list.push(CannonEntry {
+                    id,
+                    config,
+                    on_burst,
+                }),
+            });
+    });
+    on_cleanup(move || {
+        cannons
+            .list
+            .try_update(|list| list.retain(|entry| entry.id != id));
+    });
+}
@@ -1,12 +1,122 @@
+// Much of the engine is only driven by the Yew component.
+#![cfg_attr(not(feature = "yew"), allow(dead_code))]
+
+#[cfg(feature = "yew")]
+use config::{DEFAULT_COLORS, DEFAULT_SHAPES};
+#[cfg(feature = "yew")]
+use core::{ClockKey, Engine, Viewport};
+use implicit_clone::unsync::IString;
 use js_sys::wasm_bindgen::{prelude::Closure, JsCast};
-use std::ops::Range;
-use web_sys::{window, CanvasRenderingContext2d, HtmlCanvasElement};
+#[cfg(feature = "yew")]
+use js_sys::{wasm_bindgen::JsValue, Array, Reflect};
+#[cfg(feature = "yew")]
+use render::Appearance;
+#[cfg(feature = "yew")]
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll, Waker},
+};
+use web_sys::{window, Event, EventTarget, MediaQueryList};
+#[cfg(feature = "yew")]
+use web_sys::{
+    CssStyleDeclaration, DomRect, Element, HtmlCanvasElement, HtmlElement, IntersectionObserver,
+    IntersectionObserverEntry, IntersectionObserverInit, PointerEvent, ResizeObserver,
+    ResizeObserverEntry,
+};
+#[cfg(feature = "yew")]
+use yew::virtual_dom::VNode;
+#[cfg(feature = "yew")]
 use yew::{
-    function_component, html, use_effect_with, use_mut_ref, use_node_ref, AttrValue,
-    ChildrenWithProps, Classes, Component, Html, Properties,
+    create_portal, function_component, hook, html, use_effect_with, use_mut_ref, use_node_ref,
+    use_state, use_state_eq, AttrValue, Callback, ChildrenWithProps, Classes, Component, Html,
+    NodeRef, Properties,
+};
+
+#[cfg(feature = "audio")]
+mod audio;
+#[cfg(feature = "bus")]
+pub mod bus;
+#[cfg(feature = "yew")]
+mod celebrate;
+#[cfg(feature = "codegen")]
+mod codegen;
+mod config;
+mod core;
+mod debug;
+#[cfg(feature = "dioxus")]
+pub mod dioxus;
+mod driver;
+mod error;
+#[cfg(feature = "js")]
+mod js;
+#[cfg(feature = "leptos")]
+pub mod leptos;
+#[cfg(feature = "yew")]
+mod overlay;
+#[cfg(feature = "yew")]
+mod power;
+#[cfg(feature = "yew")]
+mod progress;
+#[cfg(feature = "yew")]
+mod provider;
+#[cfg(feature = "yew")]
+mod queue;
+mod render;
+#[cfg(feature = "yew")]
+mod replay;
+#[cfg(feature = "router")]
+pub mod router;
+mod scheduler;
+#[cfg(all(test, feature = "tiny-skia"))]
+mod skia;
+#[cfg(feature = "yew")]
+mod toast;
+#[cfg(all(debug_assertions, feature = "yew"))]
+mod validate;
+#[cfg(feature = "video")]
+mod video;
+#[cfg(feature = "webgl")]
+mod webgl;
+
+#[cfg(feature = "audio")]
+pub use audio::Sound;
+#[cfg(feature = "yew")]
+pub use celebrate::*;
+pub use config::{CannonConfig, ConfettiConfig};
+pub use core::{
+    ConfettiSnapshot, Easing, Emitter, Eviction, Keyframe, Mode, ModeKind, Particle, ParticleInit,
+    Pattern, Physics, RandomSource, Randomness, Shape, Simulation, Track, Transform, Units,
 };
+pub use debug::ConfettiStats;
+/// Kept so `Mode::impl_ref` and `Mode::impl_mut` still compile.
+#[doc(hidden)]
+#[deprecated(note = "use `Mode::kind` and the setters of `Mode`")]
+pub type ModeImpl = core::ModeImpl;
+pub use driver::CanvasConfetti;
+pub use error::ConfettiError;
+#[cfg(feature = "yew")]
+pub use overlay::*;
+#[cfg(feature = "yew")]
+pub use progress::*;
+#[cfg(feature = "yew")]
+pub use provider::*;
+#[cfg(feature = "yew")]
+pub use queue::*;
+pub use render::Backend;
+#[cfg(feature = "yew")]
+pub use replay::{ConfettiRecording, RecordedFire};
+#[cfg(feature = "yew")]
+pub use toast::*;
+#[cfg(feature = "video")]
+pub use video::VideoRecording;
 
 /// Confetti animation options.
+#[cfg(feature = "yew")]
 #[derive(Clone, PartialEq, Properties)]
 pub struct ConfettiProps {
     /// Horizontal resolution of canvas.
@@ -15,6 +125,11 @@ pub struct ConfettiProps {
     /// Vertical resolution of canvas.
     #[prop_or(256)]
     pub height: u32,
+    /// Fill the parent element (unless [`ConfettiProps::style`] says otherwise) and track its
+    /// rendered size times the device pixel ratio, instead of using [`ConfettiProps::width`] and
+    /// [`ConfettiProps::height`]. Particles keep their relative positions across resizes.
+    #[prop_or(false)]
+    pub auto_size: bool,
     /// Velocity decay per second (0.5 means lose 50% of velocity per second).
     #[prop_or(0.3)]
     pub decay: f32,
@@ -27,486 +142,2646 @@ pub struct ConfettiProps {
     /// Number of seconds each particle lasts.
     #[prop_or(2.5)]
     pub lifespan: f32,
+    /// Overrides `decay`, `gravity`, `drift`, and `lifespan` together, e.g. with
+    /// [`Physics::floaty`].
+    #[prop_or(None)]
+    pub physics: Option<Physics>,
+    /// What velocities, `gravity`, and `drift` are measured in. With [`Units::Pixels`],
+    /// changing the canvas size doesn't change how fast particles appear to move.
+    #[prop_or_default]
+    pub units: Units,
+    /// Scale horizontal motion in [`Units::Normalized`] by the canvas's aspect ratio, so
+    /// angles and spreads look the same as on a square canvas, instead of flatter on a wide
+    /// one. Vertical motion is unchanged. Off by default, so existing trajectories don't
+    /// change, but recommended for new code.
+    #[prop_or(false)]
+    pub aspect_correct: bool,
+    /// Scale, rotate, and translate all confetti when drawn, e.g. to tilt it with a card or
+    /// zoom in for emphasis. Cannon positions stay where they are before the transform, and
+    /// pointers for [`Mode::drag`] and [`Mode::tap`] are mapped back through it.
+    #[prop_or(None)]
+    pub transform: Option<Transform>,
     /// Don't show any confetti if user prefers reduced motion, according to a CSS media query.
+    /// Follows changes to the preference while mounted. If `false`,
+    /// [`ConfettiProps::reduced_motion`] is ignored.
     #[prop_or(true)]
     pub disable_for_reduced_motion: bool,
+    /// How to respond if the user prefers reduced motion.
+    #[prop_or_default]
+    pub reduced_motion: ReducedMotion,
     /// Particle size.
     #[prop_or(5.0)]
     pub scalar: f32,
+    /// Scale cannon rates and burst counts by the canvas area relative to this width and
+    /// height, in CSS pixels, so the density looks the same at any size.
+    #[prop_or(None)]
+    pub density_reference: Option<(u32, u32)>,
+    /// Seed for particle randomness, so the same props and timing produce the same confetti,
+    /// e.g. for visual tests or celebrations synchronized across clients. Each cannon gets its
+    /// own sequence, so adding a cannon doesn't change the others' particles. Random if `None`.
+    #[prop_or(None)]
+    pub seed: Option<u64>,
+    /// Source of particle randomness, e.g. [`Randomness::math_random`] or, with the `rand`
+    /// feature, [`Randomness::small_rng`]. Defaults to a built-in generator.
+    #[prop_or_default]
+    pub randomness: Randomness,
+    /// Most particles alive at once, so a misconfigured cannon can't freeze the page.
+    #[prop_or(10_000)]
+    pub max_particles: usize,
+    /// What happens to particles beyond [`ConfettiProps::max_particles`].
+    #[prop_or_default]
+    pub eviction: Eviction,
+    /// Most frames rendered per second, e.g. 30 to save battery. Simulation time still
+    /// accumulates between rendered frames.
+    #[prop_or(None)]
+    pub fps_cap: Option<u16>,
+    /// Wait until this fraction (0.0 to 1.0) of the canvas is visible before starting the
+    /// animation. Cannon times are then relative to when the canvas was first seen, instead
+    /// of first render.
+    #[prop_or(None)]
+    pub emit_on_visible: Option<f32>,
+    /// Freeze the animation while the canvas is scrolled out of view, to save CPU. Time spent
+    /// off-screen doesn't count towards cannon schedules.
+    #[prop_or(true)]
+    pub pause_offscreen: bool,
+    /// What happens to cannon schedules while the tab is hidden.
+    #[prop_or_default]
+    pub hidden_policy: HiddenPolicy,
+    /// Interactions that [`ConfettiHandle::dismiss`] the confetti, for users who find it
+    /// distracting while they continue their task.
+    #[prop_or_default]
+    pub dismiss_on: DismissOn,
+    /// How to respond to a device saving power. Set to [`PowerSaving::Ignore`] to opt out.
+    #[prop_or_default]
+    pub power_saving: PowerSaving,
+    /// Element that triggers [`Mode::hover`] cannons when the pointer enters it. Defaults to
+    /// the canvas, in which case the canvas receives pointer events.
+    #[prop_or(None)]
+    pub hover_target: Option<NodeRef>,
+    /// Schedules shared by cannons, see [`CannonProps::group`].
+    #[prop_or_default]
+    pub groups: Vec<CannonGroup>,
     /// Classes to apply to the canvas.
     #[prop_or_default]
     pub class: Classes,
     /// Inline style to apply to the canvas.
     #[prop_or(None)]
     pub style: Option<AttrValue>,
+    /// Stacking order of the canvas, which is positioned relatively unless
+    /// [`ConfettiProps::style`] positions it otherwise. Only orders the canvas within its
+    /// stacking context, so combine with [`ConfettiProps::portal`] to order it against the
+    /// whole page, e.g. above modals.
+    #[prop_or(None)]
+    pub z_index: Option<i32>,
+    /// Region outside of which nothing is drawn, e.g. to keep confetti inside a card with
+    /// rounded corners.
+    #[prop_or(None)]
+    pub clip: Option<Clip>,
+    /// Alpha mask, where confetti is only visible to the extent the mask is opaque, e.g.
+    /// inside a logo's silhouette. Canvases use the 2D context while masked.
+    #[prop_or(None)]
+    pub mask: Option<Mask>,
     /// Id of the canvas.
     #[prop_or(None)]
     pub id: Option<AttrValue>,
-    /// `<Cannon/>`'s
+    /// Hide the canvas from assistive technology, since confetti is decorative. Turn off to
+    /// describe it with [`ConfettiProps::role`] and [`ConfettiProps::aria_label`] instead.
+    #[prop_or(true)]
+    pub aria_hidden: bool,
+    /// ARIA role of the canvas, e.g. `"img"`.
+    #[prop_or(None)]
+    pub role: Option<AttrValue>,
+    /// Accessible label of the canvas.
+    #[prop_or(None)]
+    pub aria_label: Option<AttrValue>,
+    /// Announced to screen readers by a visually hidden live region when a burst fires, e.g.
+    /// `"Celebration!"`. Announced once per animation, not once per burst. Ignored with
+    /// [`ConfettiProps::host`].
+    #[prop_or(None)]
+    pub announcement: Option<AttrValue>,
+    /// Whether cannons emit particles. Turning this on starts cannon schedules as if the
+    /// [`Confetti`] was first rendered now. Turning it off stops emission, but lets existing
+    /// particles finish.
+    #[prop_or(true)]
+    pub active: bool,
+    /// Freeze the animation, keeping particles where they are. Time spent paused doesn't count
+    /// towards cannon schedules. See also [`ConfettiHandle::pause`].
+    #[prop_or(false)]
+    pub paused: bool,
+    /// Called when the first particle is emitted, e.g. to play a sound in sync with a delayed
+    /// burst. Called again after [`ConfettiHandle::restart`].
+    #[prop_or_default]
+    pub on_start: Callback<()>,
+    /// Called when the animation completes, i.e. all particles have expired and no more are
+    /// scheduled.
+    #[prop_or_default]
+    pub on_complete: Callback<()>,
+    /// Called every rendered frame with its [`ConfettiStats`], e.g. to see the performance
+    /// impact of cannon rates and counts. Throttle re-rendering in response, which has its own
+    /// cost.
+    #[prop_or_default]
+    pub on_stats: Callback<ConfettiStats>,
+    /// Called if the confetti can't be shown, e.g. because the canvas context is unavailable,
+    /// or if a cannon can't emit particles.
+    #[prop_or_default]
+    pub on_error: Callback<ConfettiError>,
+    /// Rendered instead of the canvas if the confetti can't be shown. Nothing if `None`.
+    #[prop_or(None)]
+    pub fallback: Option<Html>,
+    /// Rendered instead of the canvas while the animation is disabled for reduced motion, e.g.
+    /// a static banner or a still image of confetti, so the moment still registers. The empty
+    /// canvas if `None`.
+    #[prop_or(None)]
+    pub reduced_motion_fallback: Option<Html>,
+    /// Imperative control, see [`use_confetti`].
+    #[prop_or(None)]
+    pub handle: Option<ConfettiHandle>,
+    /// Imperative control for struct components, see [`ConfettiRef`].
+    #[prop_or_default]
+    pub confetti_ref: ConfettiRef,
+    /// Render the canvas into `document.body` via a portal, so it escapes `overflow: hidden`
+    /// and `transform` ancestors. Position it with [`ConfettiProps::style`]. Nothing is
+    /// rendered until mounted, e.g. on the server.
+    #[prop_or(false)]
+    pub portal: bool,
+    /// Draw on the canvas of another [`Confetti`], e.g. a [`ConfettiOverlay`], instead of
+    /// rendering one. This keeps its own cannons, physics, and lifetimes, but positions are
+    /// relative to the host's canvas, and [`ConfettiProps::emit_on_visible`] is ignored.
+    #[prop_or(None)]
+    pub host: Option<ConfettiHandle>,
+    /// How particles are drawn.
+    #[prop_or_default]
+    pub backend: Backend,
+    /// Draw each cannon's origin, emission cone, and initial velocity, and count particles
+    /// and frames per second, e.g. to tune angles and spreads. Only drawn with
+    /// [`Backend::Canvas`], which then doesn't use WebGL, and [`Backend::Canvas2d`].
+    #[prop_or(false)]
+    pub debug: bool,
+    /// Called once on mount with a callback that controls the animation, for declarative use
+    /// without a [`ConfettiHandle`]. The parent may store it and emit [`EmitCommand`]s later.
+    #[prop_or_default]
+    pub emitter: Callback<Callback<EmitCommand>>,
+    /// Configuration from data, e.g. [`ConfettiConfig::from_json`]. Its cannons are added
+    /// after the children, and its other fields replace the corresponding props.
+    #[prop_or(None)]
+    pub config: Option<Rc<ConfettiConfig>>,
+    /// `<Cannon/>`'s. Changing them keeps existing particles, and the schedules of unchanged
+    /// cannons. Give cannons a `key` if others may be inserted or removed before them.
     #[prop_or_default]
     pub children: ChildrenWithProps<Cannon>,
 }
 
-fn request_animation_frame(f: &Closure<dyn FnMut(f64)>) -> i32 {
-    window()
-        .unwrap()
-        .request_animation_frame(f.as_ref().unchecked_ref())
-        .expect("should register `requestAnimationFrame`")
-}
+#[cfg(feature = "yew")]
+impl ConfettiProps {
+    /// With the fields of [`ConfettiProps::config`], if any, replacing the props.
+    fn configured(&self) -> Cow<'_, Self> {
+        match &self.config {
+            Some(config) => {
+                let mut props = self.clone();
+                config.apply(&mut props);
+                Cow::Owned(props)
+            }
+            None => Cow::Borrowed(self),
+        }
+    }
 
-#[derive(Default)]
-struct State {
-    confetti: Vec<Fetti>,
-    callback: Option<Closure<dyn FnMut(f64)>>,
-    animation_frame: Option<i32>,
-    last_raw_time: Option<f64>,
-    last_time: u64,
-}
+    /// Cannons from [`ConfettiProps::config`], which come after the children.
+    fn config_cannons(&self) -> &[CannonConfig] {
+        self.config
+            .as_ref()
+            .map(|config| config.cannons.as_slice())
+            .unwrap_or_default()
+    }
 
-/// Confetti emitter options.
-#[derive(Clone, PartialEq, Properties)]
-pub struct CannonProps {
-    /// Emitter horizontal position. 0.0 means left edge, 1.0 means right edge.
-    #[prop_or(0.5)]
-    pub x: f32,
-    /// Emitter vertical position. 0.0 means bottom edge, 1.0 means top edge.
-    #[prop_or(0.5)]
-    pub y: f32,
-    /// Launch angle (0 = right, PI/2 = up, etc.).
-    #[prop_or(90f32.to_radians())]
-    pub angle: f32,
-    /// Random variation in launch angle (PI/2 = PI/4 on each side).
-    #[prop_or(45f32.to_radians())]
-    pub spread: f32,
-    /// Initial velocity.
-    #[prop_or(2.0)]
-    pub velocity: f32,
-    /// Shape probability distribution. Repeated shapes are more likely.
-    #[prop_or(&[Shape::Circle, Shape::Square])]
-    pub shapes: &'static [Shape],
-    /// CSS color probability distribution. Repeated colors are more likely.
-    #[prop_or(&["#26ccff", "#a25afd", "#ff5e7e", "#88ff5a", "#fcff42", "#ffa62d", "#ff36ff"])]
-    pub colors: &'static [&'static str],
-    /// How to emit particles.
-    #[prop_or_default]
-    pub mode: Mode,
-}
+    /// The [`CannonGroup`] called `name`, if any.
+    fn group(&self, name: Option<&AttrValue>) -> Option<&CannonGroup> {
+        name.and_then(|name| self.groups.iter().find(|group| &group.name == name))
+    }
 
-/// How to emit particles. Times are precise to the nearest millisecond.
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub struct Mode(ModeImpl);
+    fn cannon_group(&self, cannon: &CannonProps) -> Option<&CannonGroup> {
+        self.group(cannon.group.as_ref())
+    }
 
-impl Default for Mode {
-    fn default() -> Self {
-        Self::continuous(100)
+    /// The mode of `cannon`, taking [`CannonProps::group`] into account.
+    fn cannon_mode(&self, cannon: &CannonProps) -> Mode {
+        self.cannon_group(cannon)
+            .map(|group| group.mode)
+            .unwrap_or(cannon.mode)
     }
-}
 
-#[derive(Copy, Clone, Debug, PartialEq)]
-#[doc(hidden)]
-pub enum ModeImpl {
-    /// Emit all particles at a certain time.
-    Burst {
-        /// How many particles to emit.
-        count: usize,
-        /// Time, in seconds since first render.
-        delay: u64,
-    },
-    /// Constant stream of particles.
-    Continuous {
-        /// How many particles are emitted per second. Max is 1000.
-        rate: u16,
-        /// When the particles start being emitted, in seconds since first render.
-        start: u64,
-        /// When the particles stop being emitted, in seconds since first render.
-        end: u64,
-    },
-}
+    /// `cannon`, taking [`CannonProps::group`] into account.
+    fn emitter(&self, cannon: &CannonProps) -> Emitter {
+        Emitter {
+            mode: self.cannon_mode(cannon),
+            ..cannon.emitter()
+        }
+    }
 
-fn round_time(seconds: f32) -> u64 {
-    (seconds * 1000.0).round() as u64
-}
+    /// Whether any cannon, including those of [`ConfettiProps::config`], has a mode for which
+    /// `f` is true.
+    fn has_mode(&self, f: fn(&Mode) -> bool) -> bool {
+        self.children
+            .iter()
+            .any(|cannon| f(&self.cannon_mode(&cannon.props)))
+            || self.config_cannons().iter().any(|cannon| {
+                f(&self
+                    .group(cannon.group.as_ref())
+                    .map_or(cannon.mode, |group| group.mode))
+            })
+    }
 
-impl Mode {
-    /// Emit `count` particles upon first render.
-    pub fn burst(count: usize) -> Self {
-        Self(ModeImpl::Burst { count, delay: 0 })
+    /// Whether any cannon uses [`Mode::hover`].
+    fn has_hover(&self) -> bool {
+        self.has_mode(Mode::is_hover)
     }
 
-    /// Emit `count` particles after `delay` seconds after first render.
-    pub fn delayed_burst(count: usize, delay: f32) -> Self {
-        assert!(delay >= 0.0);
-        Self(ModeImpl::Burst {
-            count,
-            delay: round_time(delay),
-        })
+    /// Whether any cannon uses [`Mode::drag`] or [`Mode::tap`], so needs pointers on the canvas.
+    fn has_pointers(&self) -> bool {
+        self.has_mode(|mode| mode.is_drag() || mode.is_tap())
     }
 
-    pub fn is_burst(&self) -> bool {
-        matches!(self.0, ModeImpl::Burst { .. })
+    /// [`CannonProps::on_burst`] of the cannon at index `i`, if a child.
+    fn on_burst(&self, i: usize) -> Callback<usize> {
+        self.children
+            .iter()
+            .nth(i)
+            .map(|cannon| cannon.props.burst_callback())
+            .unwrap_or_default()
     }
 
-    /// Constantly emit `rate` particles per second.
-    ///
-    /// # Panics
-    /// - If `rate` > 1000.
-    pub fn continuous(rate: usize) -> Self {
-        assert!(rate <= 1000);
-        Self(ModeImpl::Continuous {
-            rate: rate as u16,
-            start: 0,
-            end: u64::MAX,
-        })
+    /// The response to reduced motion, given whether the user `prefers` it.
+    fn reduced_motion_response(&self, prefers: bool) -> ReducedMotion {
+        if prefers && self.disable_for_reduced_motion {
+            self.reduced_motion
+        } else {
+            ReducedMotion::Ignore
+        }
     }
 
-    /// Constantly emit `rate` particles per second, starting `delay` seconds after first render.
-    ///
-    /// # Panics
-    /// - If `rate` > 1000.
-    /// - If `delay` isn't positive.
-    pub fn delayed_continuous(rate: usize, delay: f32) -> Self {
-        assert!(rate <= 1000);
-        assert!(delay >= 0.0);
-        Self(ModeImpl::Continuous {
-            rate: rate as u16,
-            start: round_time(delay) as u64,
-            end: u64::MAX,
+    /// [`ConfettiProps::physics`], or the individual props, before clamping.
+    fn raw_physics(&self) -> Physics {
+        self.physics.unwrap_or(Physics {
+            decay: self.decay,
+            gravity: self.gravity,
+            drift: self.drift,
+            lifespan: self.lifespan,
         })
     }
 
-    /// Constantly emit `rate` particles per second, for the first `duration` seconds after first render.
-    ///
-    /// # Panics
-    /// - If `rate` > 1000.
-    /// - If `duration` isn't positive.
-    pub fn finite_continuous(rate: usize, duration: f32) -> Self {
-        assert!(rate <= 1000);
-        assert!(duration >= 0.0);
-        Self(ModeImpl::Continuous {
-            rate: rate as u16,
-            start: 0,
-            end: round_time(duration),
-        })
+    fn physics(&self) -> Physics {
+        let mut physics = self.raw_physics();
+        physics.clamp();
+        physics
     }
 
-    /// Constantly emit `rate` particles per second, starting `delay` seconds after first render
-    /// and for `duration` seconds thereafter.
-    ///
-    /// # Panics
-    /// - If `rate` > 1000.
-    /// - If `delay` isn't positive.
-    /// - If `duration` isn't positive.
-    pub fn delayed_finite_continuous(rate: usize, delay: f32, duration: f32) -> Self {
-        assert!(rate <= 1000);
-        assert!(delay >= 0.0);
-        assert!(duration >= 0.0);
-        Self(ModeImpl::Continuous {
-            rate: rate as u16,
-            start: round_time(delay),
-            end: round_time(delay + duration),
+    /// [`ConfettiProps::transform`], clamped.
+    fn transform(&self) -> Option<Transform> {
+        self.transform.map(|mut transform| {
+            transform.clamp();
+            transform
         })
     }
 
-    pub fn is_continuous(&self) -> bool {
-        matches!(self.0, ModeImpl::Continuous { .. })
+    /// What renderers need of the props.
+    fn appearance(&self) -> Appearance<'_> {
+        Appearance {
+            lifespan: self.physics().lifespan,
+            scalar: self.scalar,
+            transform: self.transform(),
+            mask: self.mask.as_ref(),
+        }
     }
+}
+
+/// A region of the canvas, in CSS pixels from its top left corner, see
+/// [`ConfettiProps::clip`].
+#[cfg(feature = "yew")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Clip {
+    Rect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+    /// Like [`Clip::Rect`], with corners rounded like `border-radius`.
+    RoundedRect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        radius: f32,
+    },
+    Circle {
+        x: f32,
+        y: f32,
+        radius: f32,
+    },
+    /// SVG path data, e.g. `"M 0 0 L 100 0 L 50 80 Z"`.
+    Path(AttrValue),
+}
 
-    #[doc(hidden)]
-    pub fn impl_ref(&self) -> &ModeImpl {
-        &self.0
+#[cfg(feature = "yew")]
+impl Clip {
+    /// The CSS `clip-path`, which clips every backend alike.
+    fn css(&self) -> String {
+        match self {
+            Self::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => inset(*x, *y, *width, *height, ""),
+            Self::RoundedRect {
+                x,
+                y,
+                width,
+                height,
+                radius,
+            } => inset(*x, *y, *width, *height, &format!(" round {radius}px")),
+            Self::Circle { x, y, radius } => format!("circle({radius}px at {x}px {y}px)"),
+            Self::Path(path) => format!("path({})", css_string(path)),
+        }
     }
+}
 
-    #[doc(hidden)]
-    pub fn impl_mut(&mut self) -> &mut ModeImpl {
-        &mut self.0
+/// An alpha mask, see [`ConfettiProps::mask`]. Canvases composite it offscreen, and the DOM
+/// backend uses CSS `mask`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Mask {
+    /// URL of an image with transparency, e.g. a PNG or SVG, scaled to fit within the canvas
+    /// and centered. Confetti is hidden until it loads. Cross-origin images need CORS to record
+    /// video.
+    Image(IString),
+    /// A gradient covering the canvas along `angle` degrees, clockwise from upward like CSS
+    /// `linear-gradient`, through `stops` of offset from 0 to 1 and CSS color.
+    LinearGradient {
+        angle: f32,
+        stops: &'static [(f32, &'static str)],
+    },
+    /// A circular gradient from the center to the farthest corner, through `stops` of offset
+    /// from 0 to 1 and CSS color.
+    RadialGradient {
+        stops: &'static [(f32, &'static str)],
+    },
+}
+
+#[cfg(feature = "yew")]
+impl Mask {
+    /// CSS declarations for the DOM backend, prefixed for Safari.
+    fn css(&self) -> String {
+        let stops = |stops: &[(f32, &str)]| {
+            stops
+                .iter()
+                // Colors can't end the declaration.
+                .filter(|(_, color)| !color.contains([';', '{', '}']))
+                .map(|(offset, color)| format!(", {color} {}%", offset * 100.0))
+                .collect::<String>()
+        };
+        let (image, layout) = match self {
+            Self::Image(url) => (
+                format!("url({})", css_string(url)),
+                "no-repeat center / contain",
+            ),
+            Self::LinearGradient { angle, stops: s } => (
+                format!("linear-gradient({angle}deg{})", stops(s)),
+                "no-repeat 0 0 / 100% 100%",
+            ),
+            Self::RadialGradient { stops: s } => (
+                format!("radial-gradient(circle farthest-corner{})", stops(s)),
+                "no-repeat 0 0 / 100% 100%",
+            ),
+        };
+        format!("mask: {image} {layout}; -webkit-mask: {image} {layout}; ")
     }
 }
 
-/// Confetti emitter component.
-pub struct Cannon;
-impl Component for Cannon {
-    type Properties = CannonProps;
-    type Message = ();
-    fn create(_ctx: &yew::Context<Self>) -> Self {
-        Self
+/// `value` as a quoted CSS string.
+#[cfg(feature = "yew")]
+fn css_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            // Followed by a space, which ends the escape.
+            c if c.is_control() => quoted.push_str(&format!("\\{:x} ", c as u32)),
+            c => quoted.push(c),
+        }
     }
-    fn view(&self, _ctx: &yew::Context<Self>) -> Html {
-        panic!("<Cannon> must be inside <Confetti>");
+    quoted.push('"');
+    quoted
+}
+
+/// A CSS `inset` of a rectangle, since the size of the canvas may not be known.
+#[cfg(feature = "yew")]
+fn inset(x: f32, y: f32, width: f32, height: f32, round: &str) -> String {
+    format!(
+        "inset({y}px calc(100% - {}px) calc(100% - {}px) {x}px{round})",
+        x + width,
+        y + height
+    )
+}
+
+/// What happens to cannon schedules while the tab is hidden, and animation frames stop.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HiddenPolicy {
+    /// Time spent hidden doesn't count, so everything resumes where it left off.
+    #[default]
+    Pause,
+    /// Schedules skip ahead by the time spent hidden, staying in sync with wall time, without
+    /// emitting what they missed. Particles resume where they left off.
+    CatchUp,
+}
+
+/// Interactions that dismiss the confetti, see [`ConfettiProps::dismiss_on`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DismissOn {
+    /// Only [`ConfettiHandle::dismiss`] does.
+    #[default]
+    Never,
+    /// A click or tap on the canvas, which then receives pointer events.
+    Canvas,
+    /// A click, tap, or key press anywhere on the page.
+    Document,
+}
+
+/// Milliseconds that [`ConfettiHandle::dismiss`] fades out over.
+#[cfg(feature = "yew")]
+const DISMISS_FADE: i32 = 300;
+
+/// Fading out the canvas, see [`ConfettiHandle::dismiss`]. Restores the inline style when
+/// dropped.
+#[cfg(feature = "yew")]
+struct Fade {
+    style: CssStyleDeclaration,
+    transition: String,
+    opacity: String,
+}
+
+#[cfg(feature = "yew")]
+impl Fade {
+    fn start(element: &Element) -> Option<Self> {
+        let style = element.dyn_ref::<HtmlElement>()?.style();
+        let fade = Self {
+            transition: style.get_property_value("transition").ok()?,
+            opacity: style.get_property_value("opacity").ok()?,
+            style,
+        };
+        let transition = format!("opacity {DISMISS_FADE}ms");
+        let _ = fade.style.set_property("transition", &transition);
+        let _ = fade.style.set_property("opacity", "0");
+        Some(fade)
     }
 }
 
-/// Confetti animation component.
-#[function_component(Confetti)]
-pub fn confetti(props: &ConfettiProps) -> Html {
-    let canvas = use_node_ref();
-    let state = use_mut_ref(|| State::default());
-
-    use_effect_with((canvas.clone(), props.clone()), move |(canvas, props)| {
-        let disable_for_reduced_motion = props.disable_for_reduced_motion;
-        let context = canvas
-            .cast::<HtmlCanvasElement>()
-            .unwrap()
-            .get_context("2d")
-            .unwrap()
-            .unwrap()
-            .dyn_into::<CanvasRenderingContext2d>()
-            .unwrap();
-        let props = props.clone();
-        let state_2 = state.clone();
-        state_2.borrow_mut().callback = Some(Closure::new(move |raw_time: f64| {
-            let mut state = state.borrow_mut();
-
-            let mut total_delta_time = (raw_time - state.last_raw_time.unwrap_or(raw_time)) as u64;
-            // TODO: use lifespan instead of constant?
-            if total_delta_time > 500 {
-                // Skip some time.
-                state.last_time += total_delta_time - 500;
-                total_delta_time = 500;
-            }
-            state.last_raw_time = Some(raw_time);
-            let substeps = (total_delta_time / 100).max(1);
-            let delta_time = total_delta_time / substeps;
-            let raw_delta = delta_time as f32 * 0.001;
-            for _ in 0..substeps {
-                // Inclusive.
-                let start_time = state.last_time;
-                // Exclusive.
-                let end_time = start_time + delta_time;
-                for cannon in props.children.iter() {
-                    let count = match cannon.props.mode.0 {
-                        ModeImpl::Burst { count, delay } => {
-                            if (start_time..end_time).contains(&delay) {
-                                count
-                            } else {
-                                0
-                            }
-                        }
-                        ModeImpl::Continuous { rate, start, end } => {
-                            /*
-                            fn main() {
-                                let mut sus: [u16; 1000] = std::array::from_fn(|i| i as u16);
-                                fn key(n: u16) -> u16 {
-                                    (1..100).filter(|k| n % ((1000 + k - 1) / k) == 0).min().unwrap_or(u16::MAX)
-                                }
-                                sus.sort_by_key(|n| key(*n));
-                                let mut hash = 0u16;
-                                for i in 0..1000 {
-                                    hash = hash.wrapping_mul(37).wrapping_add(i);
-                                    let i0 = (hash % 1000) as usize;
-                                    hash = hash.wrapping_mul(79).wrapping_add(i);
-                                    let i1 = (hash % 1000) as usize;
-                                    if key(sus[i0]) == key(sus[i1]) {
-                                        sus.swap(i0 as usize, i1 as usize);
-                                    }
-                                }
-                                // println!("{sus:?}");
-                                let ret: [u16; 1000] = std::array::from_fn(|i| sus.iter().position(|k| *k == i as u16).unwrap() as u16);
-                                println!("{ret:?}");
-                            }
-                            */
-                            #[rustfmt::skip]
-                            static ORDER: [u16; 1000] = [0, 603, 549, 550, 551, 813, 553, 758, 751, 556, 557, 534, 524, 513, 504, 497, 491, 474, 469, 452, 447, 439, 433, 418, 415, 406, 397, 383, 381, 352, 346, 315, 306, 288, 268, 255, 248, 991, 228, 210, 201, 552, 196, 773, 180, 498, 159, 561, 147, 562, 139, 475, 398, 121, 384, 535, 117, 453, 353, 101, 347, 563, 316, 91, 307, 514, 289, 77, 269, 419, 256, 564, 65, 565, 968, 407, 229, 54, 211, 567, 202, 385, 690, 569, 45, 476, 695, 368, 181, 571, 348, 35, 160, 317, 881, 454, 148, 894, 505, 290, 31, 574, 270, 575, 399, 257, 122, 932, 249, 577, 434, 866, 23, 649, 230, 420, 355, 212, 102, 477, 203, 536, 580, 581, 318, 19, 92, 582, 308, 996, 400, 579, 182, 455, 78, 386, 271, 585, 161, 586, 258, 587, 588, 13, 66, 356, 589, 440, 570, 591, 140, 861, 231, 478, 55, 319, 213, 790, 627, 123, 204, 421, 387, 656, 596, 291, 744, 10, 46, 515, 272, 456, 808, 599, 357, 259, 183, 103, 705, 583, 250, 757, 36, 904, 162, 604, 320, 479, 605, 93, 232, 606, 149, 607, 804, 214, 382, 767, 292, 610, 6, 79, 611, 358, 273, 612, 613, 422, 401, 457, 197, 614, 124, 615, 953, 617, 67, 321, 618, 619, 184, 481, 960, 840, 24, 408, 622, 623, 233, 595, 163, 56, 359, 718, 215, 584, 107, 896, 274, 628, 150, 629, 435, 392, 630, 260, 631, 458, 322, 708, 4, 633, 47, 423, 634, 480, 309, 635, 636, 816, 402, 367, 638, 639, 185, 125, 234, 712, 88, 641, 349, 642, 275, 37, 643, 409, 164, 644, 645, 323, 118, 646, 797, 672, 985, 459, 14, 829, 68, 482, 361, 872, 652, 974, 198, 105, 654, 293, 869, 424, 32, 755, 657, 568, 235, 659, 276, 651, 57, 661, 324, 662, 216, 779, 664, 94, 715, 887, 126, 362, 205, 736, 165, 460, 251, 410, 668, 669, 725, 671, 294, 650, 673, 600, 2, 81, 25, 675, 403, 676, 277, 325, 236, 677, 624, 425, 679, 924, 363, 681, 141, 217, 186, 682, 106, 683, 684, 441, 685, 849, 69, 461, 558, 295, 38, 990, 689, 728, 166, 721, 692, 127, 326, 832, 278, 20, 694, 364, 95, 720, 237, 863, 697, 698, 151, 58, 699, 862, 701, 702, 218, 426, 119, 703, 954, 660, 187, 984, 707, 442, 7, 696, 82, 327, 709, 389, 365, 537, 279, 710, 711, 839, 560, 104, 167, 714, 310, 772, 238, 716, 48, 939, 992, 693, 128, 411, 590, 999, 722, 15, 723, 724, 70, 845, 328, 366, 726, 427, 727, 936, 188, 96, 280, 548, 525, 730, 731, 982, 26, 733, 142, 538, 949, 735, 640, 39, 239, 737, 738, 390, 168, 739, 59, 740, 376, 329, 741, 742, 219, 83, 743, 780, 108, 539, 745, 412, 281, 129, 746, 747, 152, 516, 748, 428, 189, 749, 391, 750, 880, 625, 261, 827, 526, 354, 240, 296, 330, 754, 688, 756, 1, 11, 885, 598, 49, 759, 169, 220, 602, 761, 282, 762, 311, 388, 763, 764, 527, 540, 506, 765, 206, 620, 369, 976, 691, 262, 912, 331, 153, 429, 130, 109, 241, 517, 770, 573, 84, 846, 962, 60, 252, 774, 753, 566, 283, 777, 40, 778, 678, 554, 143, 370, 170, 781, 782, 499, 783, 826, 332, 518, 27, 297, 785, 786, 528, 787, 788, 97, 831, 937, 242, 791, 16, 864, 507, 413, 71, 793, 284, 794, 371, 795, 796, 131, 784, 221, 798, 621, 50, 333, 110, 952, 492, 801, 298, 263, 802, 830, 171, 680, 8, 805, 508, 85, 806, 541, 807, 593, 243, 372, 809, 519, 253, 632, 811, 500, 61, 812, 594, 814, 334, 393, 815, 686, 154, 21, 817, 299, 888, 483, 98, 819, 820, 821, 822, 823, 132, 41, 373, 578, 207, 825, 898, 944, 172, 501, 244, 828, 72, 111, 144, 335, 647, 958, 766, 666, 493, 833, 509, 834, 190, 835, 836, 222, 799, 264, 470, 374, 3, 838, 86, 542, 28, 559, 837, 394, 404, 964, 842, 843, 208, 844, 336, 717, 245, 928, 510, 847, 496, 133, 173, 929, 957, 62, 850, 851, 375, 484, 789, 853, 33, 597, 223, 462, 191, 502, 855, 994, 112, 857, 616, 859, 971, 337, 199, 17, 592, 841, 626, 608, 73, 865, 246, 824, 867, 360, 300, 868, 42, 395, 776, 485, 529, 775, 871, 265, 174, 87, 472, 810, 448, 224, 134, 873, 338, 920, 875, 576, 192, 877, 5, 878, 495, 879, 377, 555, 51, 909, 882, 301, 209, 883, 884, 856, 886, 486, 891, 113, 155, 670, 63, 889, 890, 752, 471, 339, 892, 443, 893, 463, 225, 543, 175, 378, 29, 874, 895, 818, 897, 732, 899, 900, 74, 520, 901, 135, 902, 903, 200, 487, 9, 959, 905, 544, 80, 266, 340, 906, 907, 908, 350, 983, 379, 910, 436, 911, 156, 464, 769, 43, 449, 913, 914, 915, 916, 302, 114, 771, 176, 918, 919, 870, 312, 488, 921, 12, 193, 341, 922, 923, 52, 380, 601, 925, 926, 521, 473, 64, 136, 927, 145, 430, 530, 860, 511, 465, 760, 719, 18, 931, 450, 444, 876, 933, 75, 665, 935, 489, 342, 545, 351, 89, 713, 700, 177, 22, 531, 938, 800, 940, 194, 941, 99, 942, 285, 115, 943, 663, 416, 945, 946, 303, 947, 466, 948, 667, 30, 226, 950, 343, 34, 137, 437, 445, 951, 854, 858, 706, 955, 956, 44, 637, 157, 546, 609, 503, 729, 648, 286, 961, 178, 930, 963, 522, 53, 414, 917, 965, 313, 966, 344, 467, 967, 655, 969, 490, 76, 970, 90, 653, 451, 972, 973, 431, 116, 100, 438, 852, 532, 523, 146, 975, 120, 658, 138, 803, 978, 304, 979, 980, 158, 345, 405, 981, 934, 768, 179, 687, 195, 468, 792, 986, 254, 987, 988, 227, 494, 989, 704, 547, 267, 572, 734, 993, 417, 674, 287, 446, 247, 432, 305, 995, 314, 848, 512, 997, 533, 998, 977, 396];
-
-                            let effective_start_time = start_time.max(start);
-                            let effective_end_time = end_time.min(end);
-                            if rate > 0 && effective_end_time > effective_start_time {
-                                //let relative_start_time = effective_start_time % 1000;
-                                //let effective_delta_time = effective_end_time - effective_start_time;
-                                (effective_start_time..effective_end_time)
-                                    .filter(|effective_time| {
-                                        rate > ORDER[(effective_time % 1000) as usize]
-                                    })
-                                    .count()
-                            } else {
-                                0
-                            }
-                        }
-                    };
-                    for _ in 0..count {
-                        state.confetti.push(Fetti::new(&props, &cannon.props));
-                    }
-                }
-                state.last_time = end_time;
+#[cfg(feature = "yew")]
+impl Drop for Fade {
+    fn drop(&mut self) {
+        // Empty values remove the properties.
+        let _ = self.style.set_property("transition", &self.transition);
+        let _ = self.style.set_property("opacity", &self.opacity);
+    }
+}
 
-                state
-                    .confetti
-                    .retain_mut(|fetti| fetti.update(raw_delta, &props));
-            }
+/// How to respond to a user who prefers reduced motion, see
+/// [`ConfettiProps::reduced_motion`].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReducedMotion {
+    /// Cannons stop emitting.
+    #[default]
+    Disable,
+    /// Cannons emit `rate_scale` of their particles, launched at `speed_scale` of their
+    /// velocity. Gravity and drift are scaled by the square of `speed_scale`, so trajectories
+    /// keep their shape.
+    Degrade { rate_scale: f32, speed_scale: f32 },
+    /// Animate normally.
+    Ignore,
+}
 
-            // This is like `context.reset()` but works in older browsers.
-            context.clear_rect(0.0, 0.0, props.width as f64, props.height as f64);
+impl ReducedMotion {
+    /// Fraction of particles that cannons emit.
+    pub(crate) fn rate_scale(self) -> f32 {
+        match self {
+            Self::Degrade { rate_scale, .. } => rate_scale.max(0.0),
+            Self::Disable | Self::Ignore => 1.0,
+        }
+    }
 
-            for fetti in &state.confetti {
-                fetti.draw(&props, &context);
-            }
+    /// Factor applied to launch velocities.
+    pub(crate) fn speed_scale(self) -> f32 {
+        match self {
+            Self::Degrade { speed_scale, .. } => speed_scale.max(0.0),
+            Self::Disable | Self::Ignore => 1.0,
+        }
+    }
+}
 
-            let done = state.confetti.is_empty()
-                && props.children.iter().all(|c| match c.props.mode.0 {
-                    ModeImpl::Burst { delay, .. } => state.last_time > delay,
-                    ModeImpl::Continuous { end, .. } => state.last_time > end,
-                });
-            if done {
-                state.last_raw_time = None;
-                state.animation_frame = None;
-            } else {
-                state.animation_frame =
-                    Some(request_animation_frame(state.callback.as_ref().unwrap()));
-            }
-        }));
-
-        if !disable_for_reduced_motion
-            || !window()
-                .unwrap()
-                .match_media("(prefers-reduced-motion: reduce)")
-                .ok()
-                .flatten()
-                .map(|m| m.matches())
-                .unwrap_or(false)
-        {
-            let mut state = state_2.borrow_mut();
-            state.animation_frame = Some(request_animation_frame(state.callback.as_ref().unwrap()));
+/// Style of the live region, see [`ConfettiProps::announcement`].
+#[cfg(feature = "yew")]
+const VISUALLY_HIDDEN: &str = "position: absolute; width: 1px; height: 1px; margin: -1px; \
+    padding: 0; overflow: hidden; clip: rect(0, 0, 0, 0); white-space: nowrap; border: 0;";
+
+/// Fraction of particles emitted under [`PowerSaving::Reduce`].
+#[cfg(feature = "yew")]
+const LOW_POWER_EMISSION: f32 = 0.25;
+
+/// How to respond to a device saving power, i.e. a discharging battery below 20%, or a user
+/// saving data, with Save-Data or `prefers-reduced-data`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PowerSaving {
+    /// Animate normally.
+    Ignore,
+    /// Cannons emit a quarter of their particles.
+    #[default]
+    Reduce,
+    /// Cannons stop emitting, as if disabled for reduced motion.
+    Disable,
+}
+
+/// A named [`Mode`] shared by several cannons, so they fire in sync.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CannonGroup {
+    /// Name referred to by [`CannonProps::group`].
+    pub name: IString,
+    /// How cannons in the group emit particles.
+    pub mode: Mode,
+}
+
+impl CannonGroup {
+    pub fn new(name: impl Into<IString>, mode: Mode) -> Self {
+        Self {
+            name: name.into(),
+            mode,
         }
+    }
+}
 
-        move || {
-            let mut state = state_2.borrow_mut();
-            if let Some(animation_frame) = state.animation_frame.take() {
-                let _ = window().unwrap().cancel_animation_frame(animation_frame);
-            }
-            drop(state.callback.take());
+/// Event listener that is removed when dropped.
+struct Listener {
+    target: EventTarget,
+    event: &'static str,
+    callback: Closure<dyn FnMut(Event)>,
+}
+
+impl Listener {
+    fn new(
+        target: EventTarget,
+        event: &'static str,
+        callback: impl FnMut(Event) + 'static,
+    ) -> Self {
+        let callback = Closure::<dyn FnMut(Event)>::new(callback);
+        let _ = target.add_event_listener_with_callback(event, callback.as_ref().unchecked_ref());
+        Self {
+            target,
+            event,
+            callback,
         }
-    });
+    }
+}
 
-    html! {
-        <canvas
-            ref={canvas}
-            id={props.id.clone()}
-            width={props.width.to_string()}
-            height={props.height.to_string()}
-            style={format!("pointer-events: none;{}", props.style.as_ref().map(|s| s.as_str()).unwrap_or(""))}
-            class={props.class.clone()}
-        />
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = self.target.remove_event_listener_with_callback(
+            self.event,
+            self.callback.as_ref().unchecked_ref(),
+        );
     }
 }
 
-/// Particle shape.
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-pub enum Shape {
-    Circle,
-    Square,
+#[cfg(feature = "yew")]
+#[derive(Default)]
+struct State {
+    sim: Engine,
+    /// Cannons of the latest props, including the mode of their group.
+    emitters: Vec<Emitter>,
+    callback: Option<scheduler::Frame>,
+    /// Id of the requested frame, see [`scheduler::request`].
+    animation_frame: Option<u64>,
+    last_raw_time: Option<f64>,
+    /// Whether the canvas has been visible, for [`ConfettiProps::emit_on_visible`].
+    seen: bool,
+    observer: Option<IntersectionObserver>,
+    observer_callback: Option<Closure<dyn FnMut(Array, IntersectionObserver)>>,
+    /// Whether the canvas is out of view, for [`ConfettiProps::pause_offscreen`].
+    offscreen: bool,
+    offscreen_observer: Option<IntersectionObserver>,
+    offscreen_callback: Option<Closure<dyn FnMut(Array, IntersectionObserver)>>,
+    /// Wall time the tab was hidden, for [`ConfettiProps::hidden_policy`].
+    hidden_at: Option<f64>,
+    visibility_listener: Option<Listener>,
+    /// Measured size, for [`ConfettiProps::auto_size`].
+    size: Option<Viewport>,
+    resize_observer: Option<ResizeObserver>,
+    resize_callback: Option<Closure<dyn FnMut(Array)>>,
+    hover_listener: Option<Listener>,
+    /// Wall time each [`Mode::hover`] cannon last fired, by index.
+    hover_fired_at: Vec<f64>,
+    /// Pointers pressing the canvas, by ID, for [`Mode::drag`] and [`Mode::tap`].
+    pointers: Vec<Pointer>,
+    pointer_listeners: Vec<Listener>,
+    /// Bursts waiting for the next frame.
+    pending: Vec<(FireRequest, u32)>,
+    /// Bursts from pointers waiting for the next frame, which aren't recorded.
+    pointer_pending: Vec<PointerRequest>,
+    next_trigger: u32,
+    /// See [`ConfettiHandle::start_recording`].
+    recording: Option<ConfettiRecording>,
+    /// Bursts of a [`ConfettiHandle::replay`], by simulated time.
+    replay: VecDeque<(u64, RecordedFire)>,
+    /// Callbacks for when all particles of a trigger have expired.
+    tracked: Vec<(u32, Box<dyn FnOnce()>)>,
+    /// Whether particle randomness was seeded, which waits for the browser.
+    seeded: bool,
+    /// The response to reduced motion, [`ReducedMotion::Ignore`] unless the user prefers it.
+    reduced_motion: ReducedMotion,
+    /// Whether the device is saving power, for [`ConfettiProps::power_saving`].
+    low_power: bool,
+    /// Whether the simulation is frozen by [`ConfettiHandle::pause`].
+    paused: bool,
+    /// Whether the simulation is frozen by [`ConfettiProps::paused`].
+    paused_by_prop: bool,
+    /// Whether cannon schedules were cancelled by [`ConfettiHandle::clear`].
+    cancelled: bool,
+    /// See [`ConfettiHandle::dismiss`].
+    fade: Option<Rc<Fade>>,
+    /// See [`ConfettiProps::dismiss_on`].
+    dismiss_listeners: Vec<Listener>,
+    /// See [`ConfettiProps::active`].
+    active: bool,
+    /// Whether any particles were emitted since first render or restart.
+    started: bool,
+    /// Callbacks to emit once the state is no longer borrowed.
+    notifications: Vec<Notification>,
+    /// Whether the animation is running, as opposed to idle or paused.
+    awake: bool,
+    /// The canvas, or container for [`Backend::Dom`].
+    element: Option<Element>,
+    /// Whose canvas this draws on, see [`ConfettiProps::host`].
+    host: Weak<RefCell<State>>,
+    /// Instances drawing on this canvas, see [`ConfettiProps::host`].
+    scenes: Vec<Rc<RefCell<State>>>,
+    /// Warnings already logged for the current props, so re-renders don't repeat them.
+    #[cfg(debug_assertions)]
+    warnings: Vec<String>,
+    /// Latest props, read every frame so changes apply without rebuilding the animation.
+    props: Option<Rc<ConfettiProps>>,
+}
+
+/// A burst fired by a [`ConfettiHandle`], regardless of cannon schedules.
+#[cfg(feature = "yew")]
+#[derive(Clone, PartialEq)]
+pub enum FireRequest {
+    /// Every cannon, see [`ConfettiHandle::fire`].
+    All,
+    /// The cannon at an index, see [`ConfettiHandle::fire_cannon`].
+    Cannon(usize),
+    /// A cannon that isn't a child, see [`ConfettiHandle::fire_with`].
+    With(Box<CannonProps>),
+    /// Particles with explicit initial states, see [`ConfettiHandle::spawn`].
+    Particles(Vec<ParticleInit>),
 }
 
-struct Fetti {
-    x: f32,
-    y: f32,
-    wobble: f32,
-    wobble_speed: f32,
-    velocity: f32,
-    angle_2d: f32,
-    tilt_angle: f32,
-    color: &'static str,
-    shape: Shape,
-    life_remaining: f32,
+/// A burst from pointer input, which depends on the canvas so isn't a [`FireRequest`].
+#[cfg(feature = "yew")]
+enum PointerRequest {
+    /// Particles along a pointer's movement, see [`Mode::drag`].
+    Drag {
+        /// Index of the cannon.
+        cannon: usize,
+        /// Where the pointer moved from, in normalized coordinates.
+        from: (f32, f32),
+        /// Where the pointer moved to, in normalized coordinates.
+        to: (f32, f32),
+        /// How many seconds the movement took.
+        duration: f32,
+        /// How many particles.
+        count: usize,
+    },
+    /// A burst where a pointer pressed, see [`Mode::tap`].
+    Tap {
+        /// Index of the cannon.
+        cannon: usize,
+        /// Where the pointer pressed, in normalized coordinates.
+        at: (f32, f32),
+    },
 }
 
-fn rand_unit() -> f32 {
-    js_sys::Math::random() as f32
+/// Fraction of the pointer's velocity that [`Mode::drag`] particles inherit.
+#[cfg(feature = "yew")]
+const DRAG_INHERITANCE: f32 = 0.5;
+
+/// A pointer pressing the canvas, see [`Mode::drag`] and [`Mode::tap`].
+#[cfg(feature = "yew")]
+struct Pointer {
+    pointer: i32,
+    /// Normalized position.
+    position: (f32, f32),
+    /// Event time, in milliseconds.
+    time: f64,
+    /// Seconds spent dragging, so emission doesn't depend on how often events fire.
+    dragged: f32,
 }
 
-fn rand_max(max: f32) -> f32 {
-    rand_unit() * max
+/// `emitter` along a pointer's movement from `from` to `to` in `duration` seconds, with
+/// [`Units::scale`] `scale`.
+#[cfg(feature = "yew")]
+fn dragged(
+    emitter: &Emitter,
+    from: (f32, f32),
+    to: (f32, f32),
+    duration: f32,
+    scale: (f32, f32),
+) -> Emitter {
+    let (dx, dy) = ((to.0 - from.0) / scale.0, (to.1 - from.1) / scale.1);
+    Emitter {
+        path: vec![from, to].into(),
+        angle: dy.atan2(dx),
+        velocity: emitter.velocity + dx.hypot(dy) / duration * DRAG_INHERITANCE,
+        ..emitter.clone()
+    }
 }
 
-fn rand_range(min: f32, max: f32) -> f32 {
-    min + rand_max(max - min)
+/// The media query for whether the user prefers reduced motion.
+fn reduced_motion_query() -> Option<MediaQueryList> {
+    window()?
+        .match_media("(prefers-reduced-motion: reduce)")
+        .ok()
+        .flatten()
 }
 
-impl Fetti {
-    fn new(props: &ConfettiProps, cannon: &CannonProps) -> Self {
-        let (sin, cos) = rand_max(std::f32::consts::TAU).sin_cos();
-        let mag = rand_unit().sqrt();
-        Self {
-            x: cannon.x,
-            y: cannon.y,
-            wobble: rand_unit(),
-            wobble_speed: rand_range(0.01, 0.015),
-            velocity: cannon.velocity * (0.9 + 0.1 * sin * mag),
-            angle_2d: cannon.angle + cos * cannon.spread * 0.5 * mag,
-            tilt_angle: rand_max(std::f32::consts::TAU),
-            color: cannon.colors[rand_max(cannon.colors.len() as f32) as usize],
-            shape: cannon.shapes[rand_max(cannon.shapes.len() as f32) as usize],
-            life_remaining: props.lifespan,
-        }
-    }
-
-    fn update(&mut self, delta: f32, props: &ConfettiProps) -> bool {
-        self.x += (self.angle_2d.cos() * self.velocity + props.drift) * delta;
-        self.y += (self.angle_2d.sin() * self.velocity - props.gravity) * delta;
-        self.velocity *= props.decay.powf(delta);
-        self.wobble += self.wobble_speed * delta;
-        self.tilt_angle += 0.1 * delta;
-        self.life_remaining -= delta;
-        if self.life_remaining > 0.0 {
-            true
+/// Whether the user currently prefers reduced motion.
+fn prefers_reduced_motion() -> bool {
+    reduced_motion_query().is_some_and(|query| query.matches())
+}
+
+/// Vibrate with `pattern`, if supported.
+#[cfg(feature = "yew")]
+fn vibrate(pattern: &[u32]) {
+    let Some(navigator) = window().map(|w| w.navigator()) else {
+        return;
+    };
+    // Missing in some browsers, e.g. Safari.
+    if Reflect::has(&navigator, &"vibrate".into()).unwrap_or(false) {
+        let pattern = pattern
+            .iter()
+            .map(|&ms| JsValue::from(ms))
+            .collect::<Array>();
+        navigator.vibrate_with_pattern(&pattern);
+    }
+}
+
+/// Seed for an [`Rng`], from `Math.random()`.
+fn random_seed() -> u64 {
+    (js_sys::Math::random() * u64::MAX as f64) as u64
+}
+
+/// `Math.random()`, which ignores seeds.
+struct MathRandom;
+
+impl RandomSource for MathRandom {
+    fn next_u64(&mut self) -> u64 {
+        random_seed()
+    }
+}
+
+impl Randomness {
+    /// Use `Math.random()`, so [`ConfettiProps::seed`] has no effect.
+    pub fn math_random() -> Self {
+        Self::new(|_| MathRandom)
+    }
+}
+
+/// Emit `notifications` to the parent component, and announce bursts in `live_region`.
+#[cfg(feature = "yew")]
+fn notify(props: &ConfettiProps, live_region: &NodeRef, notifications: Vec<Notification>) {
+    let announce = |text: Option<&str>| {
+        if let Some(element) = live_region.get() {
+            // Setting the same text again isn't announced, so clear it once complete.
+            if element.text_content().as_deref() != text {
+                element.set_text_content(text);
+            }
+        }
+    };
+    for notification in notifications {
+        match notification {
+            Notification::Start => props.on_start.emit(()),
+            Notification::Burst(on_burst, count) => {
+                announce(props.announcement.as_deref());
+                on_burst.emit(count);
+            }
+            Notification::Complete => {
+                announce(None);
+                props.on_complete.emit(());
+            }
+            Notification::Call(f) => f(),
+        }
+    }
+}
+
+/// Something to tell the parent component about.
+#[cfg(feature = "yew")]
+enum Notification {
+    /// See [`ConfettiProps::on_start`].
+    Start,
+    /// See [`CannonProps::on_burst`].
+    Burst(Callback<usize>, usize),
+    /// See [`ConfettiProps::on_complete`].
+    Complete,
+    Call(Box<dyn FnOnce()>),
+}
+
+#[cfg(feature = "yew")]
+impl State {
+    /// Run the animation, unless disabled or waiting to be visible.
+    fn wake(&mut self) {
+        if self.is_disabled() || (self.observer.is_some() && !self.seen) {
+            return;
+        }
+        self.awake = true;
+        if let Some(host) = self.host.upgrade() {
+            host.borrow_mut().request_frame();
         } else {
-            false
-        }
-    }
-
-    fn draw(&self, props: &ConfettiProps, context: &CanvasRenderingContext2d) {
-        let center_x = map_ranges(self.x, 0.0..1.0, 0.0..props.width as f32);
-        let center_y = map_ranges(self.y, 0.0..1.0, props.height as f32..0.0);
-
-        let wobble_x = center_x + self.wobble.cos() * props.scalar;
-        let wobble_y = center_y + self.wobble.sin() * props.scalar;
-        let tilt_sin = self.tilt_angle.sin();
-        let tilt_cos = self.tilt_angle.cos();
-
-        let random = rand_range(2.0, 3.0);
-        let x1 = center_x + tilt_cos * random;
-        let y1 = center_y + tilt_sin * random;
-        let x2 = wobble_x + tilt_cos * random;
-        let y2 = wobble_y + tilt_sin * random;
-
-        context.set_fill_style_str(&self.color);
-        // TODO: Dirty state.
-        context.set_global_alpha((self.life_remaining / props.lifespan) as f64);
-
-        context.begin_path();
-        match self.shape {
-            Shape::Circle => {
-                let _ = context.ellipse(
-                    center_x as f64,
-                    center_y as f64,
-                    ((x2 - x1).abs() * 0.5) as f64,
-                    ((y2 - y1).abs() * 0.5) as f64,
-                    self.wobble as f64,
-                    0.0,
-                    std::f64::consts::TAU,
-                );
+            self.request_frame();
+        }
+    }
+
+    /// Request an animation frame, unless one is already pending.
+    fn request_frame(&mut self) {
+        if self.animation_frame.is_none() {
+            if let Some(callback) = &self.callback {
+                self.animation_frame = Some(scheduler::request(callback));
+            }
+        }
+    }
+
+    /// Advance a running animation to `raw_time`, and put it to sleep once paused or idle.
+    fn tick(&mut self, props: &ConfettiProps, raw_time: f64) {
+        if !self.awake || self.offscreen {
+            return;
+        }
+        let paused = self.is_paused();
+        if !paused {
+            self.simulate(props, raw_time);
+        }
+        self.check_tracked();
+        if paused || self.is_idle() {
+            // Don't count time spent paused.
+            self.last_raw_time = None;
+            self.awake = false;
+            if !paused {
+                self.notifications.push(Notification::Complete);
+            }
+        }
+    }
+
+    /// Advance the simulation to `raw_time`, emitting and updating particles.
+    fn simulate(&mut self, props: &ConfettiProps, raw_time: f64) {
+        let delta = (raw_time - self.last_raw_time.unwrap_or(raw_time)) as u64;
+        self.last_raw_time = Some(raw_time);
+        let physics = props.physics();
+        self.sim.scheduling = self.is_scheduling();
+        self.sim.emission_scale = self.emission_scale(props);
+        self.sim.speed_scale = self.reduced_motion.speed_scale();
+        self.sim.max_particles = props.max_particles;
+        self.sim.eviction = props.eviction;
+        self.sim.unit_scale = props
+            .units
+            .scale(self.viewport(props), props.aspect_correct);
+
+        self.fire_pending(props, &physics);
+        // Replayed bursts are emitted at their recorded times, regardless of frame times.
+        let mut delta = delta;
+        while let Some((time, _)) = self.replay.front() {
+            let until = time.saturating_sub(self.sim.time);
+            if until > delta {
+                break;
             }
-            Shape::Square => {
-                context.move_to(center_x.floor() as f64, center_y.floor() as f64);
-                context.line_to(wobble_x.floor() as f64, y1 as f64);
-                context.line_to(x2.floor() as f64, y2.floor() as f64);
-                context.line_to(x1.floor() as f64, wobble_y.floor() as f64);
+            if until > 0 {
+                self.step(props, &physics, until);
+                delta -= until;
             }
+            if let Some((_, fire)) = self.replay.pop_front() {
+                self.fire_recorded(props, &physics, fire, &Callback::default(), 0);
+            }
+            self.fire_pending(props, &physics);
         }
+        self.step(props, &physics, delta);
+    }
 
-        context.close_path();
-        context.fill();
+    /// Emit `fire` and record it, calling `on_burst` if it fires a cannon that isn't a child.
+    fn fire_recorded(
+        &mut self,
+        props: &ConfettiProps,
+        physics: &Physics,
+        fire: RecordedFire,
+        on_burst: &Callback<usize>,
+        trigger: u32,
+    ) {
+        if let Some(recording) = &mut self.recording {
+            recording.fires.push((self.sim.time, fire.clone()));
+        }
+        match fire {
+            RecordedFire::All => {
+                for i in 0..self.emitters.len() {
+                    self.burst(physics, i, &props.on_burst(i), trigger);
+                }
+            }
+            RecordedFire::Cannon(i) => {
+                self.burst(physics, i, &props.on_burst(i), trigger);
+            }
+            RecordedFire::With(cannon) => {
+                let mut emitter = cannon.emitter();
+                emitter.clamp();
+                let count = emitter.mode.burst_count();
+                let count = self.sim.emit(None, &emitter, count, physics, trigger);
+                self.burst_emitted(on_burst, count);
+            }
+            RecordedFire::Particles(particles) => {
+                let count = particles.len();
+                self.sim.spawn(particles, physics, trigger);
+                self.emitted(count);
+            }
+        }
+    }
+
+    /// Emit bursts waiting for this frame.
+    fn fire_pending(&mut self, props: &ConfettiProps, physics: &Physics) {
+        // Keep the buffer, to avoid allocating every frame.
+        let mut pending = std::mem::take(&mut self.pending);
+        for (fire, trigger) in pending.drain(..) {
+            let on_burst = match &fire {
+                FireRequest::With(cannon) => cannon.burst_callback(),
+                _ => Callback::default(),
+            };
+            self.fire_recorded(props, physics, fire.into(), &on_burst, trigger);
+        }
+        self.pending = pending;
+        let mut pointer_pending = std::mem::take(&mut self.pointer_pending);
+        for request in pointer_pending.drain(..) {
+            match request {
+                PointerRequest::Drag {
+                    cannon,
+                    from,
+                    to,
+                    duration,
+                    count,
+                } => {
+                    if let Some(emitter) = self.emitters.get(cannon) {
+                        let scale = self.sim.unit_scale;
+                        let emitter = dragged(emitter, from, to, duration, scale);
+                        let count = self.sim.emit(Some(cannon), &emitter, count, physics, 0);
+                        self.emitted(count);
+                    }
+                }
+                PointerRequest::Tap { cannon, at: (x, y) } => {
+                    if let Some(emitter) = self.emitters.get(cannon) {
+                        let emitter = Emitter {
+                            x,
+                            y,
+                            path: Default::default(),
+                            ..emitter.clone()
+                        };
+                        let count = emitter.mode.burst_count();
+                        let count = self.sim.emit(Some(cannon), &emitter, count, physics, 0);
+                        self.emitted(count);
+                    }
+                }
+            }
+        }
+        self.pointer_pending = pointer_pending;
     }
-}
 
-#[inline]
-fn map_ranges(number: f32, old: Range<f32>, new: Range<f32>) -> f32 {
-    let old_range = old.end - old.start;
-    let new_range = new.end - new.start;
-    let mul: f32 = new_range / old_range;
-    let add: f32 = -old.start * mul + new.start;
+    /// Advance the simulation by `delta` milliseconds.
+    fn step(&mut self, props: &ConfettiProps, physics: &Physics, delta: u64) {
+        let emitted = self.sim.step(delta, &self.emitters, physics);
+        self.emitted(emitted);
+        for &(i, count) in &self.sim.bursts {
+            self.notifications
+                .push(Notification::Burst(props.on_burst(i), count));
+        }
+    }
 
-    if cfg!(target_feature = "fma") {
-        number.mul_add(mul, add)
-    } else {
-        number * mul + add
+    /// Drawing area, in canvas pixels.
+    fn viewport(&self, props: &ConfettiProps) -> Viewport {
+        self.size.unwrap_or(Viewport {
+            width: props.width as f32,
+            height: props.height as f32,
+            scale: 1.0,
+        })
+    }
+
+    /// Whether the animation is disabled, due to reduced motion or [`PowerSaving::Disable`].
+    fn is_disabled(&self) -> bool {
+        self.reduced_motion == ReducedMotion::Disable
+            || (self.low_power && self.power_saving() == PowerSaving::Disable)
+    }
+
+    fn power_saving(&self) -> PowerSaving {
+        self.props
+            .as_ref()
+            .map(|props| props.power_saving)
+            .unwrap_or_default()
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused || self.paused_by_prop
+    }
+
+    /// Whether cannons may emit on their schedules.
+    fn is_scheduling(&self) -> bool {
+        self.active && !self.cancelled && !self.is_disabled()
+    }
+
+    /// Update the cannons, starting the schedules of new or changed cannons now, and keeping
+    /// the rest. Particles in flight take on changed colors.
+    fn sync_emitters(&mut self, props: &ConfettiProps) {
+        let old = std::mem::take(&mut self.emitters);
+        let mut keys = Vec::new();
+        for (i, cannon) in props.children.iter().enumerate() {
+            let key = props
+                .cannon_group(&cannon.props)
+                .map(|group| ClockKey::Group(group.name.clone()))
+                .or_else(|| {
+                    let key = VNode::from(cannon.clone()).key()?.to_string();
+                    Some(ClockKey::Key(key.into()))
+                })
+                .unwrap_or(ClockKey::Index(i));
+            keys.push(key);
+            self.emitters.push(props.emitter(&cannon.props));
+            #[cfg(feature = "audio")]
+            if let Some(sound) = &cannon.props.sound {
+                audio::prepare(sound);
+            }
+        }
+        for cannon in props.config_cannons() {
+            let group = props.group(cannon.group.as_ref());
+            let key = group
+                .map(|group| ClockKey::Group(group.name.clone()))
+                .unwrap_or(ClockKey::Index(self.emitters.len()));
+            keys.push(key);
+            self.emitters.push(Emitter {
+                mode: group.map_or(cannon.mode, |group| group.mode),
+                ..cannon.emitter()
+            });
+        }
+        #[cfg(debug_assertions)]
+        validate::warn(props, &self.emitters, &mut self.warnings);
+        for emitter in &mut self.emitters {
+            emitter.clamp();
+        }
+        self.sim.recolor(&old, &self.emitters, &keys);
+        self.sim.sync_clocks(
+            keys.into_iter()
+                .zip(self.emitters.iter().map(|emitter| emitter.mode)),
+        );
+    }
+
+    /// Replay cannon schedules from the beginning.
+    fn restart(&mut self) {
+        self.finish_fade();
+        self.sim.restart();
+        self.last_raw_time = None;
+        self.cancelled = false;
+        self.started = false;
+    }
+
+    /// Remove the particles of a [`ConfettiHandle::dismiss`] that is fading out, if any.
+    fn finish_fade(&mut self) {
+        if self.fade.take().is_some() {
+            self.sim.particles.clear();
+        }
+    }
+
+    /// Whether there is nothing left to animate.
+    fn is_idle(&self) -> bool {
+        self.pending.is_empty()
+            && self.pointer_pending.is_empty()
+            && self.replay.is_empty()
+            && self.sim.particles.is_empty()
+            && (!self.is_scheduling() || self.sim.is_done(&self.emitters))
+    }
+
+    /// The canvas, or the host's for a scene.
+    fn element(&self) -> Option<Element> {
+        self.element
+            .clone()
+            .or_else(|| self.host.upgrade()?.borrow().element.clone())
+    }
+
+    /// Cancel the pending animation frame, if any.
+    fn cancel(&mut self) {
+        if let Some(animation_frame) = self.animation_frame.take() {
+            scheduler::cancel(animation_frame);
+        }
+    }
+
+    /// Fire the cannon at index `i` once, and call its `on_burst`.
+    fn burst(&mut self, physics: &Physics, i: usize, on_burst: &Callback<usize>, trigger: u32) {
+        let Some(emitter) = self.emitters.get(i) else {
+            return;
+        };
+        let count = self.sim.emit(
+            Some(i),
+            emitter,
+            emitter.mode.burst_count(),
+            physics,
+            trigger,
+        );
+        self.burst_emitted(on_burst, count);
+    }
+
+    /// Record that a burst emitted `count` particles.
+    fn burst_emitted(&mut self, on_burst: &Callback<usize>, count: usize) {
+        self.emitted(count);
+        if count > 0 {
+            self.notifications
+                .push(Notification::Burst(on_burst.clone(), count));
+        }
+    }
+
+    /// Fraction of particles that cannons emit, for [`ConfettiProps::density_reference`],
+    /// [`PowerSaving::Reduce`], and [`ReducedMotion::Degrade`].
+    fn emission_scale(&self, props: &ConfettiProps) -> f32 {
+        let mut scale = 1.0;
+        if let Some((width, height)) = props.density_reference {
+            let viewport = self.viewport(props);
+            let area = viewport.width * viewport.height / (viewport.scale * viewport.scale);
+            scale *= area / (width.max(1) as f32 * height.max(1) as f32);
+        }
+        if self.low_power && props.power_saving == PowerSaving::Reduce {
+            scale *= LOW_POWER_EMISSION;
+        }
+        scale * self.reduced_motion.rate_scale()
+    }
+
+    /// Record that `count` particles were just emitted.
+    fn emitted(&mut self, count: usize) {
+        #[cfg(feature = "tracing")]
+        if count > 0 {
+            tracing::trace!(count, "confetti emitted");
+        }
+        if count > 0 && !self.started {
+            self.started = true;
+            self.notifications.push(Notification::Start);
+        }
+    }
+
+    /// Queue a burst for the next frame.
+    fn fire(&mut self, fire: FireRequest) {
+        self.fire_tracked(fire, 0);
+    }
+
+    /// Apply [`ConfettiProps::randomness`] and [`ConfettiProps::seed`], restarting randomness
+    /// only if they changed, since re-renders build new props.
+    fn seed_randomness(&mut self, props: &ConfettiProps) {
+        if props.randomness != *self.sim.randomness() {
+            self.sim.set_randomness(props.randomness.clone());
+        }
+        match props.seed {
+            Some(seed) if seed != self.sim.seed() => self.sim.reseed(seed),
+            None if !self.seeded => self.sim.reseed(random_seed()),
+            _ => {}
+        }
+        self.seeded = true;
+    }
+
+    /// Map a normalized position on the canvas back through [`ConfettiProps::transform`].
+    fn untransform(&self, position: (f32, f32)) -> (f32, f32) {
+        let Some(props) = &self.props else {
+            return position;
+        };
+        props.transform().map_or(position, |transform| {
+            transform.invert(position, self.viewport(props))
+        })
+    }
+
+    /// Track `pointer` pressing at `position` at `time`, and burst [`Mode::tap`] cannons there.
+    fn press(&mut self, pointer: i32, position: (f32, f32), time: f64) {
+        let position = self.untransform(position);
+        // In case the release was missed.
+        self.pointers.retain(|p| p.pointer != pointer);
+        self.pointers.push(Pointer {
+            pointer,
+            position,
+            time,
+            dragged: 0.0,
+        });
+        if self.props.is_none() || !self.active {
+            return;
+        }
+        let requests = self
+            .emitters
+            .iter()
+            .enumerate()
+            .filter(|(_, emitter)| emitter.mode.is_tap())
+            .map(|(cannon, _)| PointerRequest::Tap {
+                cannon,
+                at: position,
+            })
+            .collect::<Vec<_>>();
+        for request in requests {
+            self.fire_pointer(request);
+        }
+    }
+
+    /// Emit [`Mode::drag`] particles for `pointer` moving to `position` at `time`.
+    fn drag_to(&mut self, pointer: i32, position: (f32, f32), time: f64) {
+        let position = self.untransform(position);
+        let Some(drag) = self.pointers.iter_mut().find(|p| p.pointer == pointer) else {
+            return;
+        };
+        let duration = ((time - drag.time) * 0.001) as f32;
+        if duration <= 0.0 {
+            return;
+        }
+        let from = std::mem::replace(&mut drag.position, position);
+        drag.time = time;
+        let dragged = drag.dragged;
+        drag.dragged += duration;
+        if self.props.is_none() || !self.active {
+            return;
+        }
+        let requests = self
+            .emitters
+            .iter()
+            .enumerate()
+            .filter_map(|(cannon, emitter)| {
+                let core::ModeImpl::Drag { rate } = emitter.mode.0 else {
+                    return None;
+                };
+                let emitted = |seconds: f32| (seconds * rate as f32) as usize;
+                let count = emitted(dragged + duration) - emitted(dragged);
+                (count > 0).then_some(PointerRequest::Drag {
+                    cannon,
+                    from,
+                    to: position,
+                    duration,
+                    count,
+                })
+            })
+            .collect::<Vec<_>>();
+        for request in requests {
+            self.fire_pointer(request);
+        }
+    }
+
+    /// Queue a burst for the next frame, tagging its particles with `trigger`.
+    fn fire_tracked(&mut self, fire: FireRequest, trigger: u32) {
+        if !self.is_disabled() {
+            // Don't fade out the new burst.
+            self.finish_fade();
+            self.pending.push((fire, trigger));
+            self.wake();
+        }
+    }
+
+    /// Queue a burst from pointer input for the next frame.
+    fn fire_pointer(&mut self, request: PointerRequest) {
+        if !self.is_disabled() {
+            self.finish_fade();
+            self.pointer_pending.push(request);
+            self.wake();
+        }
+    }
+
+    /// Move callbacks of triggers whose particles have all expired to notifications.
+    fn check_tracked(&mut self) {
+        let mut i = 0;
+        while i < self.tracked.len() {
+            let trigger = self.tracked[i].0;
+            if self.pending.iter().any(|(_, t)| *t == trigger)
+                || self.sim.particles.has_trigger(trigger)
+            {
+                i += 1;
+            } else {
+                let (_, f) = self.tracked.swap_remove(i);
+                self.notifications.push(Notification::Call(f));
+            }
+        }
+    }
+}
+
+/// Imperative control over a [`Confetti`], obtained from [`use_confetti`] and passed to
+/// [`ConfettiProps::handle`].
+///
+/// Bursts fired before the component is mounted are emitted once it starts.
+#[cfg(feature = "yew")]
+#[derive(Clone)]
+pub struct ConfettiHandle {
+    state: Rc<RefCell<State>>,
+}
+
+#[cfg(feature = "yew")]
+impl Default for ConfettiHandle {
+    fn default() -> Self {
+        Self {
+            state: Rc::new(RefCell::new(State::default())),
+        }
+    }
+}
+
+#[cfg(feature = "yew")]
+impl PartialEq for ConfettiHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+#[cfg(feature = "yew")]
+impl ConfettiHandle {
+    /// Fire every cannon once, regardless of its schedule. Each emits a burst of
+    /// [`Mode::burst_count`] particles.
+    pub fn fire(&self) {
+        self.fire_request(FireRequest::All);
+    }
+
+    /// Fire a burst described by `request`.
+    pub fn fire_request(&self, request: FireRequest) {
+        self.state.borrow_mut().fire(request);
+    }
+
+    /// Fire a burst described by `request`, and call `on_done` once all of its particles have
+    /// expired. `on_done` is called immediately if the animation is disabled, and never if the
+    /// [`Confetti`] is unmounted first.
+    pub fn fire_then(&self, request: FireRequest, on_done: impl FnOnce() + 'static) {
+        let mut state = self.state.borrow_mut();
+        if state.is_disabled() {
+            drop(state);
+            on_done();
+            return;
+        }
+        // Zero means untracked.
+        state.next_trigger = state.next_trigger.wrapping_add(1).max(1);
+        let trigger = state.next_trigger;
+        state.tracked.push((trigger, Box::new(on_done)));
+        state.fire_tracked(request, trigger);
+    }
+
+    /// Like [`ConfettiHandle::fire_then`], but returns a future, e.g. to redirect after the
+    /// confetti is done.
+    pub fn fire_async(&self, request: FireRequest) -> Completion {
+        let completion = Completion::default();
+        let inner = Rc::clone(&completion.0);
+        self.fire_then(request, move || {
+            let mut inner = inner.borrow_mut();
+            inner.done = true;
+            if let Some(waker) = inner.waker.take() {
+                waker.wake();
+            }
+        });
+        completion
+    }
+
+    /// Fire the cannon at `index` (in children order, then those of [`ConfettiProps::config`])
+    /// once, regardless of its schedule.
+    pub fn fire_cannon(&self, index: usize) {
+        self.fire_request(FireRequest::Cannon(index));
+    }
+
+    /// Fire a burst from a cannon that isn't a child of the [`Confetti`].
+    pub fn fire_with(&self, cannon: CannonProps) {
+        self.fire_request(FireRequest::With(Box::new(cannon)));
+    }
+
+    /// Fire a burst from a cannon that isn't a child of the [`Confetti`], at normalized
+    /// coordinates (see [`CannonProps::x`] and [`CannonProps::y`]) instead of at `cannon.x`
+    /// and `cannon.y`.
+    pub fn fire_at(&self, x: f32, y: f32, mut cannon: CannonProps) {
+        cannon.x = x;
+        cannon.y = y;
+        self.fire_with(cannon);
+    }
+
+    /// Fire a burst from a cannon that isn't a child of the [`Confetti`], at client (CSS
+    /// pixel) coordinates, such as those of a [`MouseEvent`](web_sys::MouseEvent), instead of
+    /// at `cannon.x` and `cannon.y`. Does nothing if the [`Confetti`] isn't mounted.
+    pub fn fire_at_client(&self, client_x: f64, client_y: f64, cannon: CannonProps) {
+        let Some(element) = self.state.borrow().element() else {
+            return;
+        };
+        let (x, y) = normalize(&element.get_bounding_client_rect(), client_x, client_y);
+        self.fire_at(x, y, cannon);
+    }
+
+    /// Fire a burst from a cannon that isn't a child of the [`Confetti`], positioned at the
+    /// center of `element` instead of at `cannon.x` and `cannon.y`. Does nothing if the
+    /// [`Confetti`] isn't mounted.
+    pub fn fire_from(&self, element: &Element, cannon: CannonProps) {
+        let rect = element.get_bounding_client_rect();
+        self.fire_at_client(
+            rect.left() + rect.width() * 0.5,
+            rect.top() + rect.height() * 0.5,
+            cannon,
+        );
+    }
+
+    /// Emit particles with explicit initial states, e.g. to explode an element into pieces.
+    pub fn spawn(&self, particles: impl IntoIterator<Item = ParticleInit>) {
+        self.fire_request(FireRequest::Particles(particles.into_iter().collect()));
+    }
+
+    /// Freeze the animation, keeping particles where they are. Time spent paused doesn't
+    /// count towards cannon schedules.
+    pub fn pause(&self) {
+        let mut state = self.state.borrow_mut();
+        state.paused = true;
+        state.awake = false;
+        state.last_raw_time = None;
+    }
+
+    /// Unfreeze the animation after [`ConfettiHandle::pause`]. The animation stays frozen while
+    /// [`ConfettiProps::paused`] is set.
+    pub fn resume(&self) {
+        let mut state = self.state.borrow_mut();
+        state.paused = false;
+        state.wake();
+    }
+
+    /// Whether the animation is frozen, by [`ConfettiHandle::pause`] or
+    /// [`ConfettiProps::paused`].
+    pub fn is_paused(&self) -> bool {
+        let state = self.state.borrow();
+        state.paused || state.paused_by_prop
+    }
+
+    /// Remove all particles, including bursts that haven't been emitted yet, and stop cannons
+    /// from emitting on their schedules. Cannons may still be fired imperatively, or by
+    /// [`Mode::hover`].
+    pub fn clear(&self) {
+        let mut state = self.state.borrow_mut();
+        state.sim.particles.clear();
+        state.pending.clear();
+        state.pointer_pending.clear();
+        state.replay.clear();
+        state.cancelled = true;
+        // Redraw the empty canvas.
+        state.wake();
+    }
+
+    /// Fade out and then [`ConfettiHandle::clear`], e.g. when the user continues their task,
+    /// see [`ConfettiProps::dismiss_on`]. Firing during the fade removes the old particles at
+    /// once.
+    pub fn dismiss(&self) {
+        let mut state = self.state.borrow_mut();
+        state.pending.clear();
+        state.pointer_pending.clear();
+        state.replay.clear();
+        state.cancelled = true;
+        if state.fade.is_some() {
+            return;
+        }
+        let fade = state
+            .element
+            .as_ref()
+            .filter(|_| !state.sim.particles.is_empty())
+            .and_then(Fade::start);
+        let Some((fade, window)) = fade.zip(window()) else {
+            // Nothing to fade, e.g. a scene, which doesn't have its own canvas.
+            state.sim.particles.clear();
+            state.wake();
+            return;
+        };
+        let fade = Rc::new(fade);
+        let weak = Rc::downgrade(&fade);
+        state.fade = Some(fade);
+        let state = Rc::clone(&self.state);
+        let finish = Closure::once_into_js(move || {
+            // Unless already finished, e.g. by a new burst.
+            if weak.strong_count() > 0 {
+                let mut state = state.borrow_mut();
+                state.finish_fade();
+                state.wake();
+            }
+        });
+        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            finish.unchecked_ref(),
+            DISMISS_FADE,
+        );
+    }
+
+    /// Replay all cannon schedules from the beginning, as if the [`Confetti`] was first
+    /// rendered now. Existing particles are kept, so call [`ConfettiHandle::clear`] first for
+    /// a clean slate.
+    pub fn restart(&self) {
+        let mut state = self.state.borrow_mut();
+        state.restart();
+        state.wake();
+    }
+
+    /// Run an [`EmitCommand`].
+    pub fn apply(&self, command: EmitCommand) {
+        match command {
+            EmitCommand::Fire(request) => self.fire_request(request),
+            EmitCommand::Pause => self.pause(),
+            EmitCommand::Resume => self.resume(),
+            EmitCommand::Clear => self.clear(),
+            EmitCommand::Restart => self.restart(),
+        }
+    }
+
+    /// Number of particles currently alive.
+    pub fn particle_count(&self) -> usize {
+        self.state.borrow().sim.particles.len()
+    }
+
+    /// Clear the animation, restart cannon schedules, and record the seed and every burst fired
+    /// from now on, until [`ConfettiHandle::stop_recording`]. Replaying the recording with the
+    /// same props, and cannons, reproduces the animation.
+    pub fn start_recording(&self) {
+        let mut state = self.state.borrow_mut();
+        state.sim.particles.clear();
+        state.pending.clear();
+        state.pointer_pending.clear();
+        state.replay.clear();
+        state.restart();
+        state.recording = Some(ConfettiRecording {
+            seed: state.sim.seed(),
+            fires: Vec::new(),
+        });
+        state.wake();
+    }
+
+    /// Stop recording, returning the recording since [`ConfettiHandle::start_recording`], if
+    /// any.
+    pub fn stop_recording(&self) -> Option<ConfettiRecording> {
+        self.state.borrow_mut().recording.take()
+    }
+
+    /// Clear the animation and play `recording` from the beginning, with its seed, and its
+    /// bursts at the same simulated times. Cannons keep emitting on their schedules, so the
+    /// props should match those recorded.
+    pub fn replay(&self, recording: ConfettiRecording) {
+        let mut state = self.state.borrow_mut();
+        state.sim.particles.clear();
+        state.pending.clear();
+        state.pointer_pending.clear();
+        state.sim.reseed(recording.seed);
+        state.seeded = true;
+        state.restart();
+        state.replay = recording.fires.into();
+        state.wake();
+    }
+
+    /// Record `duration` seconds of the canvas to a WebM video, e.g. for sharing, from now on.
+    /// Fire cannons afterwards, since nothing is recorded while the animation is idle. Fails
+    /// with [`Backend::Dom`] or if the browser can't record the canvas.
+    #[cfg(feature = "video")]
+    pub fn record(&self, duration: f32) -> VideoRecording {
+        let canvas = self
+            .state
+            .borrow()
+            .element()
+            .and_then(|element| element.dyn_into::<HtmlCanvasElement>().ok());
+        match canvas {
+            Some(canvas) => video::record(&canvas, duration),
+            None => VideoRecording::failed(ConfettiError::Video("no canvas".to_owned())),
+        }
+    }
+
+    /// Capture live particles and cannon schedules, e.g. to continue the animation on another
+    /// [`Confetti`] with [`ConfettiHandle::restore`] after a route change or remount.
+    pub fn snapshot(&self) -> ConfettiSnapshot {
+        self.state.borrow().sim.snapshot()
+    }
+
+    /// Replace particles and cannon schedules with those of `snapshot`, without a visual reset.
+    /// Schedules continue for cannons with the same key (or index) and mode. Particle
+    /// randomness restarts from the seed, and [`ConfettiHandle::fire_then`]
+    /// callbacks aren't carried over.
+    pub fn restore(&self, snapshot: ConfettiSnapshot) {
+        let mut state = self.state.borrow_mut();
+        state.started |= snapshot.particle_count() > 0;
+        state.sim.restore(snapshot);
+        if let Some(props) = state.props.clone() {
+            state.sync_emitters(&props);
+        }
+        state.wake();
+    }
+}
+
+/// Command for the callback handed out by [`ConfettiProps::emitter`].
+#[cfg(feature = "yew")]
+#[derive(Clone, PartialEq)]
+pub enum EmitCommand {
+    /// See [`ConfettiHandle::fire_request`].
+    Fire(FireRequest),
+    /// See [`ConfettiHandle::pause`].
+    Pause,
+    /// See [`ConfettiHandle::resume`].
+    Resume,
+    /// See [`ConfettiHandle::clear`].
+    Clear,
+    /// See [`ConfettiHandle::restart`].
+    Restart,
+}
+
+/// Convert client coordinates to normalized canvas coordinates.
+#[cfg(feature = "yew")]
+fn normalize(canvas_rect: &DomRect, client_x: f64, client_y: f64) -> (f32, f32) {
+    (
+        ((client_x - canvas_rect.left()) / canvas_rect.width()) as f32,
+        (1.0 - (client_y - canvas_rect.top()) / canvas_rect.height()) as f32,
+    )
+}
+
+/// Resolves once all particles of a [`ConfettiHandle::fire_async`] burst have expired.
+#[cfg(feature = "yew")]
+#[derive(Default)]
+pub struct Completion(Rc<RefCell<CompletionInner>>);
+
+#[cfg(feature = "yew")]
+#[derive(Default)]
+struct CompletionInner {
+    done: bool,
+    waker: Option<Waker>,
+}
+
+#[cfg(feature = "yew")]
+impl Future for Completion {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut inner = self.0.borrow_mut();
+        if inner.done {
+            Poll::Ready(())
+        } else {
+            inner.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Reference to a mounted [`Confetti`], analogous to [`NodeRef`]. Struct components, which
+/// can't call [`use_confetti`], can store one and pass it to [`ConfettiProps::confetti_ref`].
+#[cfg(feature = "yew")]
+#[derive(Clone, Default)]
+pub struct ConfettiRef(Rc<RefCell<Option<ConfettiHandle>>>);
+
+#[cfg(feature = "yew")]
+impl PartialEq for ConfettiRef {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(feature = "yew")]
+impl ConfettiRef {
+    /// Get a handle to the referenced [`Confetti`], if it is mounted.
+    pub fn get(&self) -> Option<ConfettiHandle> {
+        self.0.borrow().clone()
+    }
+}
+
+/// Create a [`ConfettiHandle`] for use with [`ConfettiProps::handle`].
+#[cfg(feature = "yew")]
+#[hook]
+pub fn use_confetti() -> ConfettiHandle {
+    (*use_state(ConfettiHandle::default)).clone()
+}
+
+/// Confetti emitter options.
+#[cfg(feature = "yew")]
+#[derive(Clone, PartialEq, Properties)]
+pub struct CannonProps {
+    /// Emitter horizontal position. 0.0 means left edge, 1.0 means right edge.
+    #[prop_or(0.5)]
+    pub x: f32,
+    /// Emitter vertical position. 0.0 means bottom edge, 1.0 means top edge.
+    #[prop_or(0.5)]
+    pub y: f32,
+    /// Points, in the coordinates of `x` and `y`, to emit along instead, e.g. the outline of
+    /// a card. Particles are spread evenly by length, so short segments get fewer of them.
+    #[prop_or_default]
+    pub path: &'static [(f32, f32)],
+    /// Launch angle (0 = right, PI/2 = up, etc.).
+    #[prop_or(90f32.to_radians())]
+    pub angle: f32,
+    /// Random variation in launch angle (PI/2 = PI/4 on each side).
+    #[prop_or(45f32.to_radians())]
+    pub spread: f32,
+    /// How launch angles and velocities are distributed, e.g. [`Pattern::Ring`] for a
+    /// firework.
+    #[prop_or_default]
+    pub pattern: Pattern,
+    /// Initial velocity.
+    #[prop_or(2.0)]
+    pub velocity: f32,
+    /// Shape probability distribution. Repeated shapes are more likely.
+    #[prop_or(DEFAULT_SHAPES)]
+    pub shapes: &'static [Shape],
+    /// CSS color probability distribution. Repeated colors are more likely. Changing it
+    /// recolors particles in flight, by index, without firing again.
+    #[prop_or(DEFAULT_COLORS)]
+    pub colors: &'static [&'static str],
+    /// How to emit particles.
+    #[prop_or_default]
+    pub mode: Mode,
+    /// Animate `x`, `y`, `angle`, and `velocity` over the schedule, e.g. to sweep across the
+    /// bottom edge while firing, without re-rendering.
+    #[prop_or_default]
+    pub keyframes: &'static [Keyframe],
+    /// Most live particles from this cannon, e.g. so an ambient cannon can't crowd out bursts
+    /// under [`ConfettiProps::max_particles`]. Emission beyond it is skipped. Doesn't apply to
+    /// [`ConfettiHandle::fire_with`].
+    #[prop_or(None)]
+    pub max_particles: Option<usize>,
+    /// Name of a [`CannonGroup`] in [`ConfettiProps::groups`] whose mode is used instead of
+    /// `mode`.
+    #[prop_or(None)]
+    pub group: Option<AttrValue>,
+    /// Called with the number of particles emitted whenever the cannon bursts, whether on
+    /// schedule, by [`Mode::hover`], or when fired by a [`ConfettiHandle`]. Not called for
+    /// continuous emission.
+    #[prop_or_default]
+    pub on_burst: Callback<usize>,
+    /// Played whenever the cannon bursts, like [`CannonProps::on_burst`]. Browsers only allow
+    /// audio after the user interacts with the page, so bursts before that are silent, as are
+    /// bursts before a [`Sound::Url`] loads.
+    #[cfg(feature = "audio")]
+    #[prop_or(None)]
+    pub sound: Option<Sound>,
+    /// Vibrate whenever the cannon bursts, like [`CannonProps::on_burst`], with alternating
+    /// milliseconds of vibration and pause, e.g. `&[30, 50, 30]`, on devices that support it.
+    /// Browsers ignore it until the user interacts with the page. Off if empty.
+    #[prop_or_default]
+    pub vibration: &'static [u32],
+}
+
+#[cfg(feature = "yew")]
+impl CannonProps {
+    /// [`CannonProps::on_burst`], after playing [`CannonProps::sound`] and
+    /// [`CannonProps::vibration`].
+    fn burst_callback(&self) -> Callback<usize> {
+        #[cfg(feature = "audio")]
+        let sound = self.sound.clone();
+        let vibration = self.vibration;
+        let on_burst = self.on_burst.clone();
+        Callback::from(move |count| {
+            #[cfg(feature = "audio")]
+            if let Some(sound) = &sound {
+                audio::play(sound);
+            }
+            if !vibration.is_empty() {
+                vibrate(vibration);
+            }
+            on_burst.emit(count);
+        })
+    }
+
+    fn emitter(&self) -> Emitter {
+        Emitter {
+            x: self.x,
+            y: self.y,
+            path: self.path.into(),
+            angle: self.angle,
+            spread: self.spread,
+            pattern: self.pattern,
+            velocity: self.velocity,
+            shapes: self.shapes.into(),
+            colors: self.colors.iter().map(|&c| AttrValue::Static(c)).collect(),
+            mode: self.mode,
+            keyframes: self.keyframes.into(),
+            max_particles: self.max_particles,
+        }
+    }
+}
+
+/// Confetti emitter component.
+#[cfg(feature = "yew")]
+pub struct Cannon;
+#[cfg(feature = "yew")]
+impl Component for Cannon {
+    type Properties = CannonProps;
+    type Message = ();
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self
+    }
+    fn view(&self, _ctx: &yew::Context<Self>) -> Html {
+        web_sys::console::error_1(&"yew_confetti: <Cannon> must be inside <Confetti>".into());
+        Html::default()
+    }
+}
+
+/// Confetti animation component.
+#[cfg(feature = "yew")]
+#[function_component(Confetti)]
+pub fn confetti(props: &ConfettiProps) -> Html {
+    let props = props.configured();
+    let props = &*props;
+    let canvas = use_node_ref();
+    let live_region = use_node_ref();
+    let own_state = use_mut_ref(State::default);
+    let state = props
+        .handle
+        .as_ref()
+        .map(|handle| handle.state.clone())
+        .unwrap_or(own_state);
+
+    {
+        let handle = ConfettiHandle {
+            state: state.clone(),
+        };
+        let emitter = props.emitter.clone();
+        use_effect_with((), move |_| {
+            emitter.emit(Callback::from(move |command| handle.apply(command)));
+        });
+    }
+
+    // Whether the animation is disabled for reduced motion, for the fallback.
+    let disabled_for_reduced_motion = use_state_eq(|| false);
+
+    // Apply changes to physics, colors, cannons, and flags in place, keeping particles.
+    {
+        let state = state.clone();
+        let set_disabled = disabled_for_reduced_motion.setter();
+        use_effect_with(props.clone(), move |props| {
+            let reduced_motion = props.reduced_motion_response(prefers_reduced_motion());
+            set_disabled.set(reduced_motion == ReducedMotion::Disable);
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                cannons = props.children.len(),
+                ?reduced_motion,
+                "confetti props changed"
+            );
+            let mut state_mut = state.borrow_mut();
+            state_mut.seed_randomness(props);
+            state_mut.sync_emitters(props);
+            state_mut.props = Some(Rc::new(props.clone()));
+            state_mut.reduced_motion = reduced_motion;
+            state_mut.paused_by_prop = props.paused;
+            if props.active && !state_mut.active {
+                state_mut.restart();
+            }
+            state_mut.active = props.active;
+            if state_mut.is_disabled() {
+                state_mut.pending.clear();
+                state_mut.pointer_pending.clear();
+                state_mut.replay.clear();
+            }
+            // Otherwise, the structural effect below will wake it once mounted.
+            if state_mut.element().is_some() && !state_mut.is_idle() {
+                state_mut.wake();
+            }
+            let empty = state_mut
+                .emitters
+                .iter()
+                .enumerate()
+                .filter(|(_, emitter)| !emitter.can_emit())
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+            drop(state_mut);
+            for i in empty {
+                props.on_error.emit(ConfettiError::EmptyCannon(i));
+            }
+
+            move || {
+                // The props may hold a handle to the state.
+                state.borrow_mut().props = None;
+            }
+        });
+    }
+
+    // Start or stop when the preference changes while mounted.
+    {
+        let state = state.clone();
+        let set_disabled = disabled_for_reduced_motion.setter();
+        let responds = props.reduced_motion_response(true) != ReducedMotion::Ignore;
+        use_effect_with(responds, move |&responds| {
+            let listener = reduced_motion_query().filter(|_| responds).map(|query| {
+                let target = query.clone().into();
+                Listener::new(target, "change", move |_| {
+                    let mut state = state.borrow_mut();
+                    let Some(props) = state.props.clone() else {
+                        return;
+                    };
+                    let reduced_motion = props.reduced_motion_response(query.matches());
+                    if state.reduced_motion == reduced_motion {
+                        return;
+                    }
+                    state.reduced_motion = reduced_motion;
+                    let disabled = reduced_motion == ReducedMotion::Disable;
+                    set_disabled.set(disabled);
+                    if disabled {
+                        state.sim.particles.clear();
+                        state.pending.clear();
+                        state.pointer_pending.clear();
+                        state.replay.clear();
+                        // Draw once more, to clear the canvas.
+                        state.request_frame();
+                    } else if state.element().is_some() && !state.is_idle() {
+                        state.wake();
+                    }
+                })
+            });
+
+            move || drop(listener)
+        });
+    }
+
+    {
+        let state = state.clone();
+        use_effect_with(props.power_saving, move |&power_saving| {
+            let monitor = (power_saving != PowerSaving::Ignore).then(|| {
+                let state = state.clone();
+                power::PowerMonitor::new(move |low_power| {
+                    let mut state = state.borrow_mut();
+                    state.low_power = low_power;
+                    if state.is_disabled() {
+                        state.pending.clear();
+                        state.pointer_pending.clear();
+                        state.replay.clear();
+                    } else if state.element().is_some() && !state.is_idle() {
+                        state.wake();
+                    }
+                })
+            });
+
+            move || {
+                drop(monitor);
+                state.borrow_mut().low_power = false;
+            }
+        });
+    }
+
+    // Browser APIs are only used once mounted, so rendering on the server works, and the
+    // portal starts out empty to match the server's markup when hydrating.
+    let portal_target = use_state(|| None::<HtmlElement>);
+    {
+        let portal_target = portal_target.clone();
+        use_effect_with(props.portal, move |&portal| {
+            let body = portal.then(|| window()?.document()?.body()).flatten();
+            if *portal_target != body {
+                portal_target.set(body);
+            }
+        });
+    }
+
+    // The backend that the browser can't draw with, if any.
+    let failed = use_state(|| None::<Backend>);
+    let set_failed = failed.setter();
+
+    // Rebuild the renderer, observers, and listeners only when they are affected.
+    let has_hover = props.has_hover();
+    let has_pointers = props.has_pointers();
+    let live_region_2 = live_region.clone();
+    use_effect_with(
+        (
+            canvas.clone(),
+            props.backend.clone(),
+            (props.debug, props.mask.is_some()),
+            props.auto_size,
+            props.pause_offscreen,
+            props.emit_on_visible,
+            props.hover_target.clone(),
+            // Tuples only compare up to 12 elements.
+            (has_hover, has_pointers),
+            props.dismiss_on,
+            props.host.clone(),
+            props.confetti_ref.clone(),
+            // The canvas moves.
+            (*portal_target).clone(),
+        ),
+        move |(
+            canvas,
+            backend,
+            (debug, masked),
+            auto_size,
+            pause_offscreen,
+            emit_on_visible,
+            hover_target,
+            (has_hover, has_pointers),
+            dismiss_on,
+            host,
+            confetti_ref,
+            _,
+        )| {
+            #[cfg(feature = "tracing")]
+            {
+                // `tracing::debug!` shadows `debug`.
+                let overlay = *debug;
+                tracing::debug!(
+                    ?backend,
+                    debug = overlay,
+                    auto_size = *auto_size,
+                    "confetti effect setup"
+                );
+            }
+            let dom = backend.is_dom();
+            let confetti_ref = confetti_ref.clone();
+            let host = host.clone();
+            // Scenes don't render an element.
+            let element = host.is_none().then(|| canvas.cast::<Element>()).flatten();
+            let state_2 = state.clone();
+            let seed = state.borrow().props.as_ref().and_then(|props| props.seed);
+            let renderer = element.as_ref().and_then(|element| {
+                let seed = seed.unwrap_or_else(random_seed);
+                render::renderer(element, backend, *debug || *masked, seed)
+            });
+            if element.is_some() && renderer.is_none() {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(?backend, "confetti renderer unsupported");
+                set_failed.set(Some(backend.clone()));
+                let props = state.borrow().props.clone();
+                if let Some(props) = props {
+                    props
+                        .on_error
+                        .emit(ConfettiError::Unsupported(backend.clone()));
+                }
+            }
+            if let Some(mut renderer) = renderer {
+                let mut last_render = None::<f64>;
+                let mut frame_rate = debug::FrameRate::default();
+                let performance = window().and_then(|w| w.performance());
+                let now = move || performance.as_ref().map_or(0.0, |p| p.now());
+                state_2.borrow_mut().callback =
+                    Some(Rc::new(RefCell::new(move |raw_time: f64| {
+                        let mut state = state.borrow_mut();
+                        let Some(props) = state.props.clone() else {
+                            return;
+                        };
+                        if let Some(fps_cap) = props.fps_cap.filter(|&fps_cap| fps_cap > 0) {
+                            let min_interval = 1000.0 / fps_cap as f64;
+                            // Tolerate jitter, so 30 fps on a 60 Hz display renders every other frame.
+                            if last_render.is_some_and(|last| raw_time - last + 2.0 < min_interval)
+                            {
+                                state.animation_frame =
+                                    state.callback.as_ref().map(scheduler::request);
+                                return;
+                            }
+                            last_render = Some(raw_time);
+                        }
+                        #[cfg(feature = "tracing")]
+                        let _span = tracing::trace_span!(
+                            "confetti_frame",
+                            particles = state.sim.particles.len()
+                        )
+                        .entered();
+                        let started = now();
+                        state.tick(&props, raw_time);
+
+                        let viewport = state.viewport(&props);
+                        renderer.begin(viewport);
+                        renderer.draw(&props.appearance(), viewport, &state.sim.particles);
+                        let mut awake = state.awake && !state.offscreen;
+                        let own_notifications = std::mem::take(&mut state.notifications);
+                        let mut notifications = Vec::new();
+                        for scene in &state.scenes {
+                            let mut scene_state = scene.borrow_mut();
+                            let Some(scene_props) = scene_state.props.clone() else {
+                                continue;
+                            };
+                            scene_state.tick(&scene_props, raw_time);
+                            let appearance = scene_props.appearance();
+                            renderer.draw(&appearance, viewport, &scene_state.sim.particles);
+                            awake |= scene_state.awake;
+                            if !scene_state.notifications.is_empty() {
+                                notifications.push((
+                                    scene_props,
+                                    std::mem::take(&mut scene_state.notifications),
+                                ));
+                            }
+                        }
+                        frame_rate.frame(raw_time);
+                        let mut stats = ConfettiStats {
+                            particles: state.sim.particles.len()
+                                + state
+                                    .scenes
+                                    .iter()
+                                    .map(|scene| scene.borrow().sim.particles.len())
+                                    .sum::<usize>(),
+                            fps: frame_rate.fps(),
+                            frame_time: 0.0,
+                        };
+                        if props.debug {
+                            renderer.debug(viewport, state.sim.unit_scale, &state.emitters, &stats);
+                        }
+                        renderer.end();
+                        stats.frame_time = now() - started;
+
+                        state.animation_frame = state
+                            .callback
+                            .as_ref()
+                            .filter(|_| awake)
+                            .map(scheduler::request);
+
+                        // Callbacks may use a handle, which borrows the state.
+                        drop(state);
+                        props.on_stats.emit(stats);
+                        notify(&props, &live_region_2, own_notifications);
+                        for (props, notifications) in notifications {
+                            // Scenes don't render a live region.
+                            notify(&props, &NodeRef::default(), notifications);
+                        }
+                    })));
+            }
+
+            let mut state = state_2.borrow_mut();
+            if let Some(host) = &host {
+                state.host = Rc::downgrade(&host.state);
+                let mut host_state = host.state.borrow_mut();
+                host_state
+                    .scenes
+                    .retain(|scene| !Rc::ptr_eq(scene, &state_2));
+                host_state.scenes.push(state_2.clone());
+            }
+            if let (true, Some(element)) = (*auto_size, &element) {
+                let state_3 = state_2.clone();
+                let resize_callback = Closure::new(move |entries: Array| {
+                    let Ok(entry) = entries.get(0).dyn_into::<ResizeObserverEntry>() else {
+                        return;
+                    };
+                    let rect = entry.content_rect();
+                    // Elements are already laid out in CSS pixels.
+                    let scale = if dom {
+                        1.0
+                    } else {
+                        window().map_or(1.0, |w| w.device_pixel_ratio())
+                    };
+                    let width = (rect.width() * scale).round().max(1.0);
+                    let height = (rect.height() * scale).round().max(1.0);
+                    if !dom {
+                        let canvas = entry.target().unchecked_into::<HtmlCanvasElement>();
+                        canvas.set_width(width as u32);
+                        canvas.set_height(height as u32);
+                    }
+                    let mut state = state_3.borrow_mut();
+                    state.size = Some(Viewport {
+                        width: width as f32,
+                        height: height as f32,
+                        scale: scale as f32,
+                    });
+                    // Resizing clears the canvas.
+                    state.request_frame();
+                });
+                // Otherwise, keep the canvas's own size.
+                if let Ok(resize_observer) =
+                    ResizeObserver::new(resize_callback.as_ref().unchecked_ref())
+                {
+                    resize_observer.observe(element);
+                    state.resize_observer = Some(resize_observer);
+                    state.resize_callback = Some(resize_callback);
+                }
+            } else {
+                state.size = None;
+            }
+            match (*pause_offscreen, &element) {
+                (true, Some(element)) => {
+                    let state_3 = state_2.clone();
+                    let offscreen_callback =
+                        Closure::new(move |entries: Array, _observer: IntersectionObserver| {
+                            let Ok(entry) = entries
+                                .iter()
+                                .last()
+                                .unwrap_or_default()
+                                .dyn_into::<IntersectionObserverEntry>()
+                            else {
+                                return;
+                            };
+                            let mut state = state_3.borrow_mut();
+                            state.offscreen = !entry.is_intersecting();
+                            if state.offscreen {
+                                // Don't count time spent off-screen.
+                                state.last_raw_time = None;
+                            } else if state.awake {
+                                state.request_frame();
+                            }
+                        });
+                    if let Ok(offscreen_observer) =
+                        IntersectionObserver::new(offscreen_callback.as_ref().unchecked_ref())
+                    {
+                        offscreen_observer.observe(element);
+                        state.offscreen_observer = Some(offscreen_observer);
+                        state.offscreen_callback = Some(offscreen_callback);
+                    }
+                }
+                _ => state.offscreen = false,
+            }
+            let document = window().and_then(|w| w.document());
+            let state_3 = state_2.clone();
+            state.visibility_listener = document.map(|document| {
+                Listener::new(document.clone().into(), "visibilitychange", move |_| {
+                    let mut state = state_3.borrow_mut();
+                    let now = js_sys::Date::now();
+                    if document.hidden() {
+                        state.hidden_at = Some(now);
+                        // Resume from here, instead of skipping up to 500ms.
+                        state.last_raw_time = None;
+                    } else if let Some(hidden_at) = state.hidden_at.take() {
+                        let catch_up = state
+                            .props
+                            .as_ref()
+                            .is_some_and(|props| props.hidden_policy == HiddenPolicy::CatchUp);
+                        if catch_up {
+                            state.sim.time += (now - hidden_at).max(0.0) as u64;
+                        }
+                    }
+                })
+            });
+            match (*emit_on_visible, &element) {
+                (Some(threshold), Some(element)) if !state.seen => {
+                    let state_3 = state_2.clone();
+                    let observer_callback =
+                        Closure::new(move |entries: Array, observer: IntersectionObserver| {
+                            let visible = entries.iter().any(|entry| {
+                                let entry = entry.unchecked_into::<IntersectionObserverEntry>();
+                                entry.is_intersecting()
+                                    && entry.intersection_ratio() >= threshold as f64
+                            });
+                            if visible {
+                                observer.disconnect();
+                                let mut state = state_3.borrow_mut();
+                                state.seen = true;
+                                state.wake();
+                            }
+                        });
+                    let init = IntersectionObserverInit::new();
+                    init.set_threshold_f64(threshold.clamp(0.0, 1.0) as f64);
+                    if let Ok(observer) = IntersectionObserver::new_with_options(
+                        observer_callback.as_ref().unchecked_ref(),
+                        &init,
+                    ) {
+                        observer.observe(element);
+                        state.observer = Some(observer);
+                        state.observer_callback = Some(observer_callback);
+                    } else {
+                        // Assume it's visible.
+                        state.seen = true;
+                        state.wake();
+                    }
+                }
+                _ => {
+                    // Don't complete again if only rebuilding.
+                    if !state.is_idle() {
+                        state.wake();
+                    }
+                }
+            }
+
+            let hover_target = hover_target
+                .as_ref()
+                .and_then(|target| target.cast::<Element>())
+                .or_else(|| element.clone());
+            if let Some(target) = hover_target.filter(|_| *has_hover) {
+                let state_3 = state_2.clone();
+                state.hover_listener =
+                    Some(Listener::new(target.into(), "pointerenter", move |_| {
+                        let mut state = state_3.borrow_mut();
+                        if state.props.is_none() || !state.active {
+                            return;
+                        }
+                        let now = js_sys::Date::now();
+                        let len = state.emitters.len();
+                        state.hover_fired_at.resize(len, f64::NEG_INFINITY);
+                        for i in 0..len {
+                            if let core::ModeImpl::Hover { cooldown, .. } = state.emitters[i].mode.0
+                            {
+                                if now - state.hover_fired_at[i] >= cooldown as f64 {
+                                    state.hover_fired_at[i] = now;
+                                    state.fire(FireRequest::Cannon(i));
+                                }
+                            }
+                        }
+                    }));
+            }
+            state.pointers.clear();
+            state.pointer_listeners = element
+                .iter()
+                .filter(|_| *has_pointers)
+                .flat_map(|element| {
+                    let state_3 = state_2.clone();
+                    let element_2 = element.clone();
+                    let down = move |event: Event| {
+                        let Some(event) = event.dyn_ref::<PointerEvent>() else {
+                            return;
+                        };
+                        let pointer = event.pointer_id();
+                        // Keep receiving moves if the pointer leaves the canvas.
+                        let _ = element_2.set_pointer_capture(pointer);
+                        let position = normalize(
+                            &element_2.get_bounding_client_rect(),
+                            event.client_x() as f64,
+                            event.client_y() as f64,
+                        );
+                        state_3
+                            .borrow_mut()
+                            .press(pointer, position, event.time_stamp());
+                    };
+                    let state_3 = state_2.clone();
+                    let element_2 = element.clone();
+                    let moved = move |event: Event| {
+                        let Some(event) = event.dyn_ref::<PointerEvent>() else {
+                            return;
+                        };
+                        let position = normalize(
+                            &element_2.get_bounding_client_rect(),
+                            event.client_x() as f64,
+                            event.client_y() as f64,
+                        );
+                        state_3.borrow_mut().drag_to(
+                            event.pointer_id(),
+                            position,
+                            event.time_stamp(),
+                        );
+                    };
+                    let up = |state: Rc<RefCell<State>>| {
+                        move |event: Event| {
+                            if let Some(event) = event.dyn_ref::<PointerEvent>() {
+                                let pointer = event.pointer_id();
+                                state.borrow_mut().pointers.retain(|p| p.pointer != pointer);
+                            }
+                        }
+                    };
+                    let target = EventTarget::from(element.clone());
+                    [
+                        Listener::new(target.clone(), "pointerdown", down),
+                        Listener::new(target.clone(), "pointermove", moved),
+                        Listener::new(target.clone(), "pointerup", up(state_2.clone())),
+                        Listener::new(target, "pointercancel", up(state_2.clone())),
+                    ]
+                })
+                .collect();
+            let dismiss_targets: Vec<(EventTarget, &str)> = match dismiss_on {
+                DismissOn::Never => Vec::new(),
+                DismissOn::Canvas => element
+                    .iter()
+                    .map(|element| (element.clone().into(), "pointerdown"))
+                    .collect(),
+                DismissOn::Document => window()
+                    .into_iter()
+                    .flat_map(|window| {
+                        [
+                            (window.clone().into(), "pointerdown"),
+                            (window.into(), "keydown"),
+                        ]
+                    })
+                    .collect(),
+            };
+            state.dismiss_listeners = dismiss_targets
+                .into_iter()
+                .map(|(target, event)| {
+                    let handle = ConfettiHandle {
+                        state: state_2.clone(),
+                    };
+                    Listener::new(target, event, move |_| {
+                        if !handle.state.borrow().is_idle() {
+                            handle.dismiss();
+                        }
+                    })
+                })
+                .collect();
+            state.element = element;
+            drop(state);
+
+            *confetti_ref.0.borrow_mut() = Some(ConfettiHandle {
+                state: state_2.clone(),
+            });
+
+            move || {
+                *confetti_ref.0.borrow_mut() = None;
+                let mut state = state_2.borrow_mut();
+                state.cancel();
+                if let Some(observer) = state.observer.take() {
+                    observer.disconnect();
+                }
+                drop(state.observer_callback.take());
+                if let Some(offscreen_observer) = state.offscreen_observer.take() {
+                    offscreen_observer.disconnect();
+                }
+                drop(state.offscreen_callback.take());
+                if let Some(resize_observer) = state.resize_observer.take() {
+                    resize_observer.disconnect();
+                }
+                drop(state.resize_callback.take());
+                drop(state.hover_listener.take());
+                state.pointer_listeners.clear();
+                state.pointers.clear();
+                state.dismiss_listeners.clear();
+                drop(state.visibility_listener.take());
+                drop(state.callback.take());
+                state.element = None;
+                state.host = Weak::new();
+                drop(state);
+                if let Some(host) = host {
+                    let mut host_state = host.state.borrow_mut();
+                    host_state
+                        .scenes
+                        .retain(|scene| !Rc::ptr_eq(scene, &state_2));
+                    // Erase the scene's particles.
+                    host_state.request_frame();
+                }
+            }
+        },
+    );
+
+    if props.host.is_some() {
+        return Html::default();
+    }
+    if failed.as_ref() == Some(&props.backend) {
+        return props.fallback.clone().unwrap_or_default();
+    }
+    if let (true, Some(fallback)) = (*disabled_for_reduced_motion, &props.reduced_motion_fallback) {
+        return fallback.clone();
+    }
+
+    let pointer_events = if props.has_pointers() {
+        // Touches would otherwise scroll or zoom.
+        "touch-action: none;"
+    } else if (props.hover_target.is_none() && props.has_hover())
+        || props.dismiss_on == DismissOn::Canvas
+    {
+        ""
+    } else {
+        "pointer-events: none;"
+    };
+
+    let style = props.style.as_ref().map(|s| s.as_str()).unwrap_or("");
+    // Before the style, so it can override the position.
+    let layer = props
+        .z_index
+        .map(|z_index| format!("position: relative; z-index: {z_index}; "))
+        .unwrap_or_default();
+    let clip = props
+        .clip
+        .as_ref()
+        .map(|clip| format!("clip-path: {}; ", clip.css()))
+        .unwrap_or_default();
+    // Canvases composite the mask themselves.
+    let mask = props
+        .mask
+        .as_ref()
+        .filter(|_| props.backend.is_dom())
+        .map(Mask::css)
+        .unwrap_or_default();
+    let size = if props.auto_size {
+        "display: block; width: 100%; height: 100%; ".to_owned()
+    } else if props.backend.is_dom() {
+        format!("width: {}px; height: {}px; ", props.width, props.height)
+    } else {
+        String::new()
+    };
+    let aria_hidden = props.aria_hidden.then_some("true");
+    let html = match props.backend {
+        Backend::Canvas | Backend::Canvas2d => html! {
+            <canvas
+                // A canvas can't switch from WebGL to the 2D context.
+                key={(props.debug || props.mask.is_some() || props.backend == Backend::Canvas2d)
+                    .to_string()}
+                ref={canvas}
+                id={props.id.clone()}
+                width={(!props.auto_size).then(|| props.width.to_string())}
+                height={(!props.auto_size).then(|| props.height.to_string())}
+                style={format!("{layer}{clip}{mask}{size}{pointer_events}{style}")}
+                class={props.class.clone()}
+                aria-hidden={aria_hidden}
+                role={props.role.clone()}
+                aria-label={props.aria_label.clone()}
+            />
+        },
+        #[cfg(feature = "dom")]
+        Backend::Dom { .. } => html! {
+            <div
+                ref={canvas}
+                id={props.id.clone()}
+                style={format!("position: relative; overflow: hidden; {layer}{clip}{mask}{size}{pointer_events}{style}")}
+                class={props.class.clone()}
+                aria-hidden={aria_hidden}
+                role={props.role.clone()}
+                aria-label={props.aria_label.clone()}
+            />
+        },
+    };
+    // Always a fragment, so adding an announcement doesn't replace the canvas.
+    let html = html! {
+        <>
+            {html}
+            if props.announcement.is_some() {
+                <span ref={live_region} role="status" aria-live="polite" style={VISUALLY_HIDDEN} />
+            }
+        </>
+    };
+
+    match (props.portal, (*portal_target).clone()) {
+        (true, Some(body)) => create_portal(html, body.into()),
+        (true, None) => Html::default(),
+        (false, _) => html,
+    }
+}
+
+#[cfg(all(test, feature = "yew"))]
+mod tests {
+    use crate::{
+        CannonProps, Clip, ConfettiProps, Emitter, Mask, Mode, Physics, Randomness, State,
+    };
+    use yew::html::ChildrenRenderer;
+    use yew::props;
+    use yew::virtual_dom::VChild;
+
+    fn props(cannons: [&'static [&'static str]; 2], gravity: f32) -> ConfettiProps {
+        let cannons = cannons.map(|colors| {
+            let cannon = props!(CannonProps {
+                colors,
+                mode: Mode::burst(50),
+            });
+            VChild::new(cannon, None)
+        });
+        props!(ConfettiProps {
+            gravity,
+            drift: gravity,
+            decay: gravity * 0.1,
+            children: ChildrenRenderer::new(cannons.into()),
+        })
+    }
+
+    #[test]
+    fn changes_apply_in_flight() {
+        // Cannons that share a color only recolor their own particles.
+        let before = props([&["red", "green"], &["red"]], 1.0);
+        let after = props([&["blue", "yellow"], &["green"]], 2.0);
+        let [mut state, mut unchanged] = [State::default(), State::default()];
+        for state in [&mut state, &mut unchanged] {
+            state.sync_emitters(&before);
+            state.sim.step(100, &state.emitters, &before.physics());
+        }
+        assert_eq!(state.sim.particles.len(), 100);
+
+        state.sync_emitters(&after);
+        // Doesn't fire again.
+        assert_eq!(state.sim.step(100, &state.emitters, &after.physics()), 0);
+        assert_eq!(state.sim.particles.len(), 100);
+        unchanged
+            .sim
+            .step(100, &unchanged.emitters, &before.physics());
+        for i in 0..state.sim.particles.len() {
+            let (fetti, old) = (state.sim.particles.get(i), unchanged.sim.particles.get(i));
+            let expected = match (old.cannon, &*old.color) {
+                (1, "red") => "blue",
+                (1, _) => "yellow",
+                _ => "green",
+            };
+            assert_eq!(fetti.color, expected);
+            assert!(fetti.x != old.x && fetti.y != old.y);
+        }
+    }
+
+    #[test]
+    fn clips_are_css() {
+        let rounded = Clip::RoundedRect {
+            x: 10.0,
+            y: 20.0,
+            width: 100.0,
+            height: 50.0,
+            radius: 8.0,
+        };
+        assert_eq!(
+            rounded.css(),
+            "inset(20px calc(100% - 110px) calc(100% - 70px) 10px round 8px)"
+        );
+        assert_eq!(
+            Clip::Path("M 0 0 L 10 0 Z".into()).css(),
+            r#"path("M 0 0 L 10 0 Z")"#
+        );
+    }
+
+    #[test]
+    fn masks_are_css() {
+        assert_eq!(
+            Mask::Image("logo.svg".into()).css(),
+            "mask: url(\"logo.svg\") no-repeat center / contain; \
+            -webkit-mask: url(\"logo.svg\") no-repeat center / contain; "
+        );
+        let stops = &[(0.0, "black"), (0.5, "red; x: y"), (1.0, "transparent")];
+        assert_eq!(
+            Mask::LinearGradient { angle: 90.0, stops }.css(),
+            "mask: linear-gradient(90deg, black 0%, transparent 100%) no-repeat 0 0 / 100% 100%; \
+            -webkit-mask: linear-gradient(90deg, black 0%, transparent 100%) no-repeat 0 0 / 100% \
+            100%; "
+        );
+        assert!(Mask::Image("a\"b\\c\né.png".into())
+            .css()
+            .starts_with(r#"mask: url("a\"b\\c\a é.png")"#));
+    }
+
+    #[test]
+    fn rerenders_keep_randomness() {
+        assert_eq!(Randomness::default(), Randomness::default());
+        let mut state = State::default();
+        let emitter = Emitter::default();
+        let physics = Physics::default();
+        for _ in 0..2 {
+            // Each render builds new props.
+            state.seed_randomness(&props!(ConfettiProps { seed: Some(1) }));
+            state.sim.emit(None, &emitter, 1, &physics, 0);
+        }
+        let (first, second) = (state.sim.particles.get(0), state.sim.particles.get(1));
+        assert_ne!(first.dir_x, second.dir_x, "randomness restarted");
     }
 }
@@ -0,0 +1,56 @@
+use crate::{Cannon, Confetti, ConfettiConfig, ConfettiHandle};
+use std::rc::Rc;
+use yew::{function_component, html, Callback, ChildrenWithProps, Html, Properties};
+
+/// Full-window confetti overlay options.
+#[derive(Clone, PartialEq, Properties)]
+pub struct ConfettiOverlayProps {
+    /// Stacking order of the overlay canvas. Since it's rendered into `document.body`, this
+    /// orders it against the whole page. To draw above modals but below tooltips, pick a value
+    /// between theirs, e.g. 1060 for Bootstrap 5.
+    #[prop_or(1000)]
+    pub z_index: i32,
+    /// Imperative control, see [`use_confetti`](crate::use_confetti).
+    #[prop_or(None)]
+    pub handle: Option<ConfettiHandle>,
+    /// See [`ConfettiProps::on_complete`](crate::ConfettiProps::on_complete).
+    #[prop_or_default]
+    pub on_complete: Callback<()>,
+    /// See [`ConfettiProps::config`](crate::ConfettiProps::config), except that the overlay
+    /// always tracks the window size.
+    #[prop_or(None)]
+    pub config: Option<Rc<ConfettiConfig>>,
+    /// `<Cannon/>`'s
+    #[prop_or_default]
+    pub children: ChildrenWithProps<Cannon>,
+}
+
+/// Confetti over the whole window, above everything with a lower
+/// [`ConfettiOverlayProps::z_index`], that never intercepts pointer events. The canvas
+/// resolution tracks the window size.
+#[function_component(ConfettiOverlay)]
+pub fn confetti_overlay(props: &ConfettiOverlayProps) -> Html {
+    let style =
+        "position: fixed; left: 0; top: 0; width: 100vw; height: 100vh; pointer-events: none;";
+
+    let config = props.config.as_ref().map(|config| {
+        Rc::new(ConfettiConfig {
+            auto_size: true,
+            ..(**config).clone()
+        })
+    });
+
+    html! {
+        <Confetti
+            auto_size={true}
+            portal={true}
+            z_index={props.z_index}
+            {style}
+            handle={props.handle.clone()}
+            on_complete={props.on_complete.clone()}
+            {config}
+        >
+            {props.children.clone()}
+        </Confetti>
+    }
+}
@@ -0,0 +1,119 @@
+use crate::Listener;
+use js_sys::wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use js_sys::{Function, Reflect};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use web_sys::{window, BatteryManager, EventTarget, MediaQueryList, Navigator};
+
+/// Battery level, as a fraction, below which a discharging device is saving power.
+const LOW_BATTERY: f64 = 0.2;
+
+/// Watches the battery and data saver, for [`crate::ConfettiProps::power_saving`].
+pub(crate) struct PowerMonitor {
+    /// Whether the monitor is still in use, since the battery is queried asynchronously.
+    alive: Rc<Cell<bool>>,
+    listeners: Rc<RefCell<Vec<Listener>>>,
+}
+
+impl PowerMonitor {
+    /// Call `on_change` with whether the device is saving power, once known and whenever it
+    /// changes.
+    pub(crate) fn new(on_change: impl Fn(bool) + 'static) -> Self {
+        let alive = Rc::new(Cell::new(true));
+        let listeners = Rc::new(RefCell::new(Vec::new()));
+        let Some(navigator) = window().map(|w| w.navigator()) else {
+            return Self { alive, listeners };
+        };
+        let on_change = Rc::new(on_change);
+        let battery = Rc::new(RefCell::new(None::<BatteryManager>));
+
+        let check = {
+            let navigator = navigator.clone();
+            let battery = battery.clone();
+            Rc::new(move || {
+                let low_battery = battery
+                    .borrow()
+                    .as_ref()
+                    .is_some_and(|b| !b.charging() && b.level() <= LOW_BATTERY);
+                on_change(save_data(&navigator) || low_battery);
+            })
+        };
+        check();
+
+        let targets = [connection(&navigator), reduced_data_query().map(Into::into)];
+        for target in targets.into_iter().flatten() {
+            let check = check.clone();
+            listeners
+                .borrow_mut()
+                .push(Listener::new(target, "change", move |_| check()));
+        }
+
+        let get_battery = Reflect::get(&navigator, &"getBattery".into())
+            .ok()
+            .and_then(|f| f.dyn_into::<Function>().ok());
+        if let Some(promise) = get_battery.and_then(|f| f.call0(&navigator).ok()) {
+            let alive = alive.clone();
+            let listeners = listeners.clone();
+            let resolved = Closure::once_into_js(move |manager: JsValue| {
+                let Ok(manager) = manager.dyn_into::<BatteryManager>() else {
+                    return;
+                };
+                if !alive.get() {
+                    return;
+                }
+                let mut listeners = listeners.borrow_mut();
+                for event in ["chargingchange", "levelchange"] {
+                    let check = check.clone();
+                    listeners.push(Listener::new(manager.clone().into(), event, move |_| {
+                        check()
+                    }));
+                }
+                *battery.borrow_mut() = Some(manager);
+                drop(listeners);
+                check();
+            });
+            // The closure frees itself once called, and resolution may outlive the monitor.
+            if let Some(then) = Reflect::get(&promise, &"then".into())
+                .ok()
+                .and_then(|f| f.dyn_into::<Function>().ok())
+            {
+                let _ = then.call1(&promise, &resolved);
+            }
+        }
+
+        Self { alive, listeners }
+    }
+}
+
+impl Drop for PowerMonitor {
+    fn drop(&mut self) {
+        self.alive.set(false);
+        self.listeners.borrow_mut().clear();
+    }
+}
+
+/// `navigator.connection`, where supported.
+fn connection(navigator: &Navigator) -> Option<EventTarget> {
+    Reflect::get(navigator, &"connection".into())
+        .ok()?
+        .dyn_into()
+        .ok()
+}
+
+/// The `prefers-reduced-data` media query, where supported.
+fn reduced_data_query() -> Option<MediaQueryList> {
+    window()?
+        .match_media("(prefers-reduced-data: reduce)")
+        .ok()
+        .flatten()
+}
+
+/// Whether the user asked to reduce data usage, with Save-Data or `prefers-reduced-data`, which
+/// often accompanies a power saving mode or a constrained device.
+fn save_data(navigator: &Navigator) -> bool {
+    connection(navigator)
+        .and_then(|c| Reflect::get(&c, &"saveData".into()).ok())
+        .and_then(|s| s.as_bool())
+        .unwrap_or(false)
+        || reduced_data_query().is_some_and(|query| query.matches())
+}
@@ -0,0 +1,103 @@
+use crate::{use_confetti, CannonProps, ConfettiOverlay, Mode};
+use web_sys::Element;
+use yew::{
+    function_component, html, props, use_effect_with, use_mut_ref, use_node_ref, Children, Html,
+    Properties,
+};
+
+/// Progress bar confetti options.
+#[derive(Clone, PartialEq, Properties)]
+pub struct ConfettiProgressProps {
+    /// Fraction complete, from 0.0 to 1.0.
+    pub progress: f32,
+    /// Particles streamed from the tip over the whole bar, in proportion to each advance.
+    #[prop_or(100)]
+    pub stream_particles: usize,
+    /// Cannon the stream comes from, ignoring its `x`, `y`, and `mode`.
+    #[prop_or_else(default_stream)]
+    pub stream: CannonProps,
+    /// Cannon that bursts from the tip on reaching 1.0, ignoring its `x` and `y`.
+    #[prop_or_else(|| props!(CannonProps {}))]
+    pub finish: CannonProps,
+    /// Stacking order of the overlay canvas.
+    #[prop_or(1000)]
+    pub z_index: i32,
+    /// The progress bar, whose left edge is 0.0 and right edge is 1.0. Wrapped in a block.
+    #[prop_or_default]
+    pub children: Children,
+}
+
+fn default_stream() -> CannonProps {
+    props!(CannonProps {
+        spread: 60f32.to_radians(),
+        velocity: 1.0,
+    })
+}
+
+/// Where the stream left off.
+#[derive(Default)]
+struct Tip {
+    /// `None` before the first render.
+    progress: Option<f32>,
+    /// Fraction of a particle not yet streamed.
+    carry: f32,
+}
+
+/// Streams confetti from the leading edge of its children, a progress bar, as
+/// [`ConfettiProgressProps::progress`] advances, then bursts once it's complete. The confetti
+/// is drawn on a full-window overlay, so it isn't clipped to the bar.
+#[function_component(ConfettiProgress)]
+pub fn confetti_progress(props: &ConfettiProgressProps) -> Html {
+    let handle = use_confetti();
+    let node = use_node_ref();
+    let tip = use_mut_ref(Tip::default);
+
+    {
+        let handle = handle.clone();
+        let node = node.clone();
+        let props = props.clone();
+        use_effect_with(props.progress, move |&progress| {
+            // `f32::clamp` keeps NaN.
+            let progress = if progress.is_nan() {
+                0.0
+            } else {
+                progress.clamp(0.0, 1.0)
+            };
+            let mut tip = tip.borrow_mut();
+            let Some(previous) = tip.progress.replace(progress) else {
+                return;
+            };
+            if progress < previous {
+                // E.g. the next download.
+                tip.carry = 0.0;
+                return;
+            }
+            let Some(element) = node.cast::<Element>() else {
+                return;
+            };
+            let rect = element.get_bounding_client_rect();
+            let x = rect.left() + rect.width() * progress as f64;
+            let y = rect.top() + rect.height() * 0.5;
+            tip.carry += (progress - previous) * props.stream_particles as f32;
+            let count = tip.carry.floor();
+            tip.carry -= count;
+            if count >= 1.0 {
+                let stream = CannonProps {
+                    mode: Mode::burst(count as usize),
+                    ..props.stream
+                };
+                handle.fire_at_client(x, y, stream);
+            }
+            if progress >= 1.0 && previous < 1.0 {
+                handle.fire_at_client(x, y, props.finish);
+            }
+        });
+    }
+
+    html! {
+        <>
+            <div ref={node}>{props.children.clone()}</div>
+            <ConfettiOverlay z_index={props.z_index} handle={Some(handle)}/>
+        </>
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{Cannon, CannonProps, ConfettiHandle, ConfettiOverlay, Listener, Mode};
+use js_sys::wasm_bindgen::JsCast;
+use web_sys::{window, HtmlElement, KeyboardEvent};
+use yew::{
+    function_component, hook, html, props, use_context, use_effect_with, use_state, Children,
+    ContextProvider, Html, Properties,
+};
+
+/// A key combination, see [`ConfettiProviderProps::hotkey`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hotkey {
+    /// A [`KeyboardEvent::key`], compared case-insensitively, e.g. `"c"` or `"F9"`.
+    pub key: &'static str,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+}
+
+impl Hotkey {
+    /// `key` without modifiers.
+    pub const fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+        }
+    }
+
+    /// Also require Control.
+    pub const fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    /// Also require Alt, or Option on macOS.
+    pub const fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// Also require Shift.
+    pub const fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    /// Also require Meta, i.e. Command on macOS or the Windows key.
+    pub const fn meta(mut self) -> Self {
+        self.meta = true;
+        self
+    }
+
+    /// Whether `event` presses the combination. Other modifiers must not be held.
+    fn matches(&self, event: &KeyboardEvent) -> bool {
+        event.key().eq_ignore_ascii_case(self.key)
+            && event.ctrl_key() == self.ctrl
+            && event.alt_key() == self.alt
+            && event.shift_key() == self.shift
+            && event.meta_key() == self.meta
+    }
+}
+
+/// Whether `event` is typing into a text field.
+fn is_typing(event: &KeyboardEvent) -> bool {
+    event
+        .target()
+        .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+        .is_some_and(|element| {
+            element.is_content_editable()
+                || matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+        })
+}
+
+/// Full-screen confetti overlay options.
+#[derive(Clone, PartialEq, Properties)]
+pub struct ConfettiProviderProps {
+    /// Stacking order of the overlay canvas.
+    #[prop_or(1000)]
+    pub z_index: i32,
+    /// Cannon fired by [`ConfettiHandle::fire`].
+    #[prop_or_else(default_cannon)]
+    pub cannon: CannonProps,
+    /// Handle to the overlay, in case the provider's owner wants to fire confetti too.
+    #[prop_or(None)]
+    pub handle: Option<ConfettiHandle>,
+    /// Key combination that fires [`ConfettiProviderProps::cannon`] anywhere on the page, e.g.
+    /// for demos or easter eggs. Without Control, Alt, or Meta, it's ignored while typing into
+    /// a text field.
+    #[prop_or(None)]
+    pub hotkey: Option<Hotkey>,
+    /// Descendants may call [`use_confetti_context`].
+    #[prop_or_default]
+    pub children: Children,
+}
+
+fn default_cannon() -> CannonProps {
+    props!(CannonProps {
+        mode: Mode::manual(200),
+    })
+}
+
+/// Owns a full-screen overlay canvas that any descendant can fire confetti on, via
+/// [`use_confetti_context`].
+#[function_component(ConfettiProvider)]
+pub fn confetti_provider(props: &ConfettiProviderProps) -> Html {
+    let own_handle = use_state(ConfettiHandle::default);
+    let handle = props
+        .handle
+        .clone()
+        .unwrap_or_else(|| (*own_handle).clone());
+
+    #[cfg(feature = "bus")]
+    {
+        let handle = handle.clone();
+        yew::use_effect_with(handle, |handle| {
+            let handle = handle.clone();
+            let subscription = crate::bus::subscribe(move |request| handle.fire_request(request));
+            move || drop(subscription)
+        });
+    }
+
+    {
+        let handle = handle.clone();
+        use_effect_with((handle, props.hotkey), |(handle, hotkey)| {
+            let handle = handle.clone();
+            let listener = hotkey.zip(window()).map(|(hotkey, window)| {
+                Listener::new(window.into(), "keydown", move |event| {
+                    let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+                        return;
+                    };
+                    let modified = hotkey.ctrl || hotkey.alt || hotkey.meta;
+                    if event.repeat() || !hotkey.matches(event) || (!modified && is_typing(event)) {
+                        return;
+                    }
+                    event.prevent_default();
+                    handle.fire();
+                })
+            });
+            move || drop(listener)
+        });
+    }
+
+    html! {
+        <ContextProvider<ConfettiHandle> context={handle.clone()}>
+            {props.children.clone()}
+            <ConfettiOverlay z_index={props.z_index} {handle}>
+                <Cannon ..props.cannon.clone()/>
+            </ConfettiOverlay>
+        </ContextProvider<ConfettiHandle>>
+    }
+}
+
+/// Get a handle to the nearest [`ConfettiProvider`]'s overlay, if any. Use
+/// [`ConfettiHandle::fire_from`] to fire from a descendant's own element.
+#[hook]
+pub fn use_confetti_context() -> Option<ConfettiHandle> {
+    use_context::<ConfettiHandle>()
+}
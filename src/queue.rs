@@ -0,0 +1,104 @@
+use crate::{ConfettiHandle, FireRequest};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// What a [`CelebrationQueue`] does with requests that arrive while a celebration is playing.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Coalesce {
+    /// Play every request, in order.
+    #[default]
+    None,
+    /// Only keep the most recent waiting request.
+    Latest,
+    /// Ignore requests while a celebration is playing.
+    Drop,
+}
+
+/// Plays bursts on a [`ConfettiHandle`] one celebration at a time, waiting for the particles of
+/// each to expire (see [`ConfettiHandle::fire_then`]) before firing the next, regardless of
+/// other cannons, so a flood of events doesn't produce an unreadable storm of particles.
+#[derive(Clone)]
+pub struct CelebrationQueue(Rc<RefCell<QueueInner>>);
+
+struct QueueInner {
+    handle: ConfettiHandle,
+    coalesce: Coalesce,
+    waiting: VecDeque<FireRequest>,
+    playing: bool,
+}
+
+impl PartialEq for CelebrationQueue {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl CelebrationQueue {
+    pub fn new(handle: ConfettiHandle, coalesce: Coalesce) -> Self {
+        Self(Rc::new(RefCell::new(QueueInner {
+            handle,
+            coalesce,
+            waiting: VecDeque::new(),
+            playing: false,
+        })))
+    }
+
+    /// Play `request` now if nothing is playing, otherwise after the waiting requests.
+    pub fn push(&self, request: FireRequest) {
+        let mut inner = self.0.borrow_mut();
+        if !inner.playing {
+            drop(inner);
+            self.play(request);
+            return;
+        }
+        match inner.coalesce {
+            Coalesce::None => inner.waiting.push_back(request),
+            Coalesce::Latest => {
+                inner.waiting.clear();
+                inner.waiting.push_back(request);
+            }
+            Coalesce::Drop => {}
+        }
+    }
+
+    /// Number of requests waiting for the current celebration to complete.
+    pub fn len(&self) -> usize {
+        self.0.borrow().waiting.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether a celebration is playing.
+    pub fn is_playing(&self) -> bool {
+        self.0.borrow().playing
+    }
+
+    /// Forget waiting requests, without affecting the current celebration.
+    pub fn clear(&self) {
+        self.0.borrow_mut().waiting.clear();
+    }
+
+    fn play(&self, request: FireRequest) {
+        let handle = {
+            let mut inner = self.0.borrow_mut();
+            inner.playing = true;
+            inner.handle.clone()
+        };
+        let queue = self.clone();
+        handle.fire_then(request, move || queue.next());
+    }
+
+    fn next(&self) {
+        let next = {
+            let mut inner = self.0.borrow_mut();
+            inner.playing = false;
+            inner.waiting.pop_front()
+        };
+        if let Some(request) = next {
+            self.play(request);
+        }
+    }
+}
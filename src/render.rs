@@ -0,0 +1,506 @@
+use crate::core::{Emitter, Fetti, Geometry, Particles, Rng, Shape, Transform, Viewport};
+use crate::debug::{self, ConfettiStats};
+use crate::Mask;
+use implicit_clone::unsync::IString;
+use js_sys::wasm_bindgen::JsCast;
+use web_sys::{
+    window, CanvasRenderingContext2d, Element, HtmlCanvasElement, HtmlImageElement, Path2d,
+};
+#[cfg(feature = "dom")]
+use web_sys::{Document, HtmlElement};
+
+/// How particles are drawn, see [`ConfettiProps::backend`](crate::ConfettiProps::backend).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Backend {
+    /// Draw on a `<canvas>`, using WebGL2 if the `webgl` feature is enabled and available.
+    #[default]
+    Canvas,
+    /// Draw on a `<canvas>` with the 2D context, even if WebGL is available, e.g. to compare
+    /// performance.
+    Canvas2d,
+    /// Animate up to `max_particles` absolutely-positioned elements with CSS transforms, e.g.
+    /// where canvas is unavailable or to keep particles in screenshots and print. Particles
+    /// beyond the limit are simulated but not drawn. Requires the `dom` feature.
+    #[cfg(feature = "dom")]
+    Dom { max_particles: usize },
+}
+
+impl Backend {
+    /// Whether particles are elements, which are laid out in CSS pixels.
+    pub(crate) fn is_dom(&self) -> bool {
+        #[cfg(feature = "dom")]
+        if let Self::Dom { .. } = self {
+            return true;
+        }
+        false
+    }
+}
+
+/// What renderers need of the props or config.
+pub(crate) struct Appearance<'a> {
+    /// Seconds each particle lasts, clamped.
+    pub(crate) lifespan: f32,
+    /// See [`ConfettiProps::scalar`](crate::ConfettiProps::scalar).
+    pub(crate) scalar: f32,
+    /// See [`ConfettiProps::transform`](crate::ConfettiProps::transform), clamped.
+    pub(crate) transform: Option<Transform>,
+    /// See [`ConfettiProps::mask`](crate::ConfettiProps::mask).
+    pub(crate) mask: Option<&'a Mask>,
+}
+
+impl Appearance<'_> {
+    /// Outline of `fetti` to draw, see [`Fetti::geometry`].
+    pub(crate) fn geometry(&self, fetti: &Fetti, viewport: Viewport, tilt: f32) -> Geometry {
+        let geometry = fetti.geometry(self.scalar, viewport, tilt);
+        match &self.transform {
+            Some(transform) => geometry.transform(transform, viewport),
+            None => geometry,
+        }
+    }
+}
+
+/// Draws particles into an element, once per frame.
+pub(crate) trait Renderer {
+    /// Clear the element.
+    fn begin(&mut self, viewport: Viewport);
+    /// Draw `confetti`, over anything drawn since [`Renderer::begin`].
+    fn draw(&mut self, appearance: &Appearance, viewport: Viewport, confetti: &Particles);
+    /// Draw the overlay of [`ConfettiProps::debug`](crate::ConfettiProps::debug), if supported,
+    /// over the particles. Velocities are in units scaled by `unit_scale`, see
+    /// [`ConfettiProps::units`](crate::ConfettiProps::units).
+    fn debug(
+        &mut self,
+        _viewport: Viewport,
+        _unit_scale: (f32, f32),
+        _emitters: &[Emitter],
+        _stats: &ConfettiStats,
+    ) {
+    }
+    /// Finish the frame.
+    fn end(&mut self) {}
+}
+
+/// Pick the fastest available renderer for `element` and `backend`, seeding its jitter with
+/// `seed`. The `debug` overlay and [`ConfettiProps::mask`](crate::ConfettiProps::mask) need a
+/// 2D canvas, per `canvas_2d`, so toggling them must recreate the canvas. Returns `None` if the browser can't draw on it.
+pub(crate) fn renderer(
+    element: &Element,
+    backend: &Backend,
+    canvas_2d: bool,
+    seed: u64,
+) -> Option<Box<dyn Renderer>> {
+    Some(match *backend {
+        Backend::Canvas => {
+            let canvas = element.unchecked_ref::<HtmlCanvasElement>();
+            if !canvas_2d {
+                #[cfg(feature = "webgl")]
+                if crate::webgl::WebGlRenderer::supported() {
+                    if let Some(renderer) = crate::webgl::WebGlRenderer::new(canvas, seed) {
+                        return Some(Box::new(renderer));
+                    }
+                }
+            }
+            Box::new(Canvas2dRenderer::new(canvas, seed)?)
+        }
+        Backend::Canvas2d => Box::new(Canvas2dRenderer::new(
+            element.unchecked_ref::<HtmlCanvasElement>(),
+            seed,
+        )?),
+        #[cfg(feature = "dom")]
+        Backend::Dom { max_particles } => Box::new(DomRenderer::new(element, max_particles, seed)?),
+    })
+}
+
+/// Opacity levels, so particles with similar opacity can be filled together.
+const ALPHA_LEVELS: f32 = 32.0;
+
+/// Fills a cached unit path per particle, sorted by fill style.
+pub(crate) struct Canvas2dRenderer {
+    context: CanvasRenderingContext2d,
+    /// Unit square.
+    square: Path2d,
+    /// Circle inscribed in the unit square.
+    circle: Path2d,
+    /// Particle indices, sorted by fill style.
+    order: Vec<u32>,
+    /// Last values set on `context`, to skip redundant calls into JS.
+    fill_style: Option<IString>,
+    alpha: Option<u8>,
+    /// Resizing the canvas resets `context`.
+    viewport: Option<[f32; 2]>,
+    /// Bounds `[min_x, min_y, max_x, max_y]` of everything drawn since the last clear.
+    dirty: Option<[f32; 4]>,
+    /// Whether the transform isn't the identity.
+    transformed: bool,
+    /// See [`ConfettiProps::mask`](crate::ConfettiProps::mask).
+    mask: Option<MaskLayer>,
+    /// Jitters particle sizes.
+    rng: Rng,
+}
+
+impl Canvas2dRenderer {
+    /// Returns `None` if the 2D context is unavailable.
+    pub(crate) fn new(canvas: &HtmlCanvasElement, seed: u64) -> Option<Self> {
+        let context = canvas
+            .get_context("2d")
+            .ok()
+            .flatten()?
+            .dyn_into::<CanvasRenderingContext2d>()
+            .ok()?;
+        let square = Path2d::new().ok()?;
+        square.rect(0.0, 0.0, 1.0, 1.0);
+        let circle = Path2d::new().ok()?;
+        let _ = circle.arc(0.5, 0.5, 0.5, 0.0, std::f64::consts::TAU);
+        Some(Self {
+            square,
+            circle,
+            context,
+            order: Vec::new(),
+            fill_style: None,
+            alpha: None,
+            viewport: None,
+            dirty: None,
+            transformed: false,
+            mask: None,
+            rng: Rng::new(seed),
+        })
+    }
+
+    /// Keep only what was drawn where `mask` is opaque.
+    fn composite(&mut self, mask: &Mask, viewport: Viewport) {
+        if self.dirty.is_none() {
+            return;
+        }
+        if self.mask.as_ref().map(|layer| &layer.mask) != Some(mask) {
+            self.mask = MaskLayer::new(mask);
+        }
+        self.end();
+        self.context.set_global_alpha(1.0);
+        self.alpha = None;
+        let _ = self
+            .context
+            .set_global_composite_operation("destination-in");
+        match self
+            .mask
+            .as_mut()
+            .and_then(|layer| layer.draw(viewport).then_some(&layer.canvas))
+        {
+            Some(canvas) => {
+                let _ = self
+                    .context
+                    .draw_image_with_html_canvas_element(canvas, 0.0, 0.0);
+            }
+            // Nothing is visible until the mask is.
+            None => {
+                self.context
+                    .clear_rect(0.0, 0.0, viewport.width as f64, viewport.height as f64)
+            }
+        }
+        let _ = self.context.set_global_composite_operation("source-over");
+    }
+}
+
+/// A [`Mask`] drawn offscreen at the size of the canvas.
+struct MaskLayer {
+    mask: Mask,
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d,
+    /// Loading, for [`Mask::Image`].
+    image: Option<HtmlImageElement>,
+    /// Size `canvas` was drawn at, if it was.
+    drawn: Option<[f32; 2]>,
+}
+
+impl MaskLayer {
+    fn new(mask: &Mask) -> Option<Self> {
+        let canvas = window()?
+            .document()?
+            .create_element("canvas")
+            .ok()?
+            .unchecked_into::<HtmlCanvasElement>();
+        let context = canvas
+            .get_context("2d")
+            .ok()
+            .flatten()?
+            .dyn_into::<CanvasRenderingContext2d>()
+            .ok()?;
+        let image = match mask {
+            Mask::Image(url) => {
+                let image = HtmlImageElement::new().ok()?;
+                image.set_src(url);
+                Some(image)
+            }
+            _ => None,
+        };
+        Some(Self {
+            mask: mask.clone(),
+            canvas,
+            context,
+            image,
+            drawn: None,
+        })
+    }
+
+    /// Draw the mask at the size of `viewport`, unless it already is. Returns `false` while
+    /// an image is loading, or if it failed to.
+    fn draw(&mut self, viewport: Viewport) -> bool {
+        let (width, height) = (viewport.width, viewport.height);
+        if self.drawn == Some([width, height]) {
+            return true;
+        }
+        let image = self.image.as_ref();
+        if image.is_some_and(|image| !image.complete() || image.natural_width() == 0) {
+            return false;
+        }
+        self.canvas.set_width(width as u32);
+        self.canvas.set_height(height as u32);
+        let context = &self.context;
+        let (center_x, center_y) = (width as f64 * 0.5, height as f64 * 0.5);
+        let gradient = match (&self.mask, image) {
+            (Mask::Image(_), Some(image)) => {
+                let (image_width, image_height) =
+                    (image.natural_width() as f64, image.natural_height() as f64);
+                // Contain, and center.
+                let scale = (width as f64 / image_width).min(height as f64 / image_height);
+                let (w, h) = (image_width * scale, image_height * scale);
+                let _ = context.draw_image_with_html_image_element_and_dw_and_dh(
+                    image,
+                    center_x - w * 0.5,
+                    center_y - h * 0.5,
+                    w,
+                    h,
+                );
+                None
+            }
+            (Mask::LinearGradient { angle, stops }, _) => {
+                let (sin, cos) = (angle.to_radians() as f64).sin_cos();
+                // Long enough for the corners to reach the first and last stops, like CSS.
+                let half = (width as f64 * sin.abs() + height as f64 * cos.abs()) * 0.5;
+                let (dx, dy) = (sin * half, -cos * half);
+                let gradient = context.create_linear_gradient(
+                    center_x - dx,
+                    center_y - dy,
+                    center_x + dx,
+                    center_y + dy,
+                );
+                Some((gradient, stops))
+            }
+            (Mask::RadialGradient { stops }, _) => {
+                let radius = center_x.hypot(center_y);
+                context
+                    .create_radial_gradient(center_x, center_y, 0.0, center_x, center_y, radius)
+                    .ok()
+                    .map(|gradient| (gradient, stops))
+            }
+            (Mask::Image(_), None) => None,
+        };
+        if let Some((gradient, stops)) = gradient {
+            for &(offset, color) in stops.iter() {
+                // Invalid colors are skipped.
+                let _ = gradient.add_color_stop(offset.clamp(0.0, 1.0), color);
+            }
+            context.set_fill_style_canvas_gradient(&gradient);
+            context.fill_rect(0.0, 0.0, width as f64, height as f64);
+        }
+        self.drawn = Some([width, height]);
+        true
+    }
+}
+
+impl Renderer for Canvas2dRenderer {
+    fn begin(&mut self, viewport: Viewport) {
+        let size = Some([viewport.width, viewport.height]);
+        if self.viewport != size {
+            // Everything was reset, and may be stretched.
+            self.viewport = size;
+            self.fill_style = None;
+            self.alpha = None;
+            self.transformed = false;
+            self.dirty = Some([0.0, 0.0, viewport.width, viewport.height]);
+        }
+        // Only clear what was drawn, and don't reset state like `context.reset()` would.
+        if let Some([min_x, min_y, max_x, max_y]) = self.dirty.take() {
+            // Margin for antialiasing.
+            let (min_x, min_y) = (min_x.floor() - 1.0, min_y.floor() - 1.0);
+            let (max_x, max_y) = (max_x.ceil() + 1.0, max_y.ceil() + 1.0);
+            self.context.clear_rect(
+                min_x as f64,
+                min_y as f64,
+                (max_x - min_x) as f64,
+                (max_y - min_y) as f64,
+            );
+        }
+    }
+
+    fn draw(&mut self, appearance: &Appearance, viewport: Viewport, confetti: &Particles) {
+        let lifespan = appearance.lifespan;
+        let style = |i: &u32| {
+            let i = *i as usize;
+            let alpha = (confetti.alpha(i, lifespan) * ALPHA_LEVELS).ceil() as u8;
+            (confetti.color(i), alpha)
+        };
+        self.order.clear();
+        self.order.extend(0..confetti.len() as u32);
+        self.order.sort_unstable_by(|a, b| style(a).cmp(&style(b)));
+
+        for group in self.order.chunk_by(|a, b| style(a) == style(b)) {
+            let (color, alpha) = style(&group[0]);
+            if self.fill_style.as_ref() != Some(color) {
+                self.context.set_fill_style_str(color);
+                self.fill_style = Some(color.clone());
+            }
+            if self.alpha != Some(alpha) {
+                self.context
+                    .set_global_alpha((alpha as f32 / ALPHA_LEVELS) as f64);
+                self.alpha = Some(alpha);
+            }
+
+            for i in group {
+                let fetti = confetti.get(*i as usize);
+                let [a, b, c, d, e, f] = appearance
+                    .geometry(&fetti, viewport, self.rng.range(2.0, 3.0))
+                    .matrix();
+                let xs = [e, e + a, e + c, e + a + c];
+                let ys = [f, f + b, f + d, f + b + d];
+                let [min_x, min_y, max_x, max_y] =
+                    self.dirty
+                        .get_or_insert([f32::MAX, f32::MAX, f32::MIN, f32::MIN]);
+                *min_x = xs.into_iter().fold(*min_x, f32::min);
+                *min_y = ys.into_iter().fold(*min_y, f32::min);
+                *max_x = xs.into_iter().fold(*max_x, f32::max);
+                *max_y = ys.into_iter().fold(*max_y, f32::max);
+                self.transformed = true;
+                let _ = self
+                    .context
+                    .set_transform(a as f64, b as f64, c as f64, d as f64, e as f64, f as f64);
+                self.context.fill_with_path_2d(match fetti.shape {
+                    Shape::Circle => &self.circle,
+                    Shape::Square => &self.square,
+                });
+            }
+        }
+
+        match appearance.mask {
+            Some(mask) => self.composite(mask, viewport),
+            None => self.mask = None,
+        }
+    }
+
+    fn debug(
+        &mut self,
+        viewport: Viewport,
+        unit_scale: (f32, f32),
+        emitters: &[Emitter],
+        stats: &ConfettiStats,
+    ) {
+        self.end();
+        debug::draw(&self.context, viewport, unit_scale, emitters, stats);
+        // The overlay may cover the whole canvas, and changes the context's state.
+        self.dirty = Some([0.0, 0.0, viewport.width, viewport.height]);
+        self.fill_style = None;
+        self.alpha = None;
+    }
+
+    fn end(&mut self) {
+        // Clearing needs the identity transform.
+        if self.transformed {
+            self.transformed = false;
+            let _ = self.context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        }
+    }
+}
+
+/// Positions a pool of `<span>`s, one per particle.
+#[cfg(feature = "dom")]
+pub(crate) struct DomRenderer {
+    document: Document,
+    container: Element,
+    max_particles: usize,
+    spans: Vec<HtmlElement>,
+    /// How many of `spans` are drawn this frame.
+    used: usize,
+    /// How many of `spans` are visible.
+    visible: usize,
+    /// Jitters particle sizes.
+    rng: Rng,
+}
+
+#[cfg(feature = "dom")]
+impl DomRenderer {
+    /// Returns `None` without a document to create elements in.
+    pub(crate) fn new(container: &Element, max_particles: usize, seed: u64) -> Option<Self> {
+        Some(Self {
+            document: container.owner_document()?,
+            container: container.clone(),
+            max_particles,
+            spans: Vec::new(),
+            used: 0,
+            visible: 0,
+            rng: Rng::new(seed),
+        })
+    }
+}
+
+#[cfg(feature = "dom")]
+impl Renderer for DomRenderer {
+    fn begin(&mut self, _viewport: Viewport) {
+        self.used = 0;
+    }
+
+    fn draw(&mut self, appearance: &Appearance, viewport: Viewport, confetti: &Particles) {
+        let lifespan = appearance.lifespan;
+        let start = self.used;
+        let count = (start + confetti.len()).min(self.max_particles);
+        while self.spans.len() < count {
+            let Ok(span) = self.document.create_element("span") else {
+                break;
+            };
+            // Map a 1px square onto the particle's bounding quad.
+            let _ = span.set_attribute(
+                "style",
+                "position: absolute; left: 0; top: 0; width: 1px; height: 1px; \
+                transform-origin: 0 0;",
+            );
+            let _ = self.container.append_child(&span);
+            self.spans.push(span.unchecked_into());
+        }
+
+        for (i, fetti) in (start..count).zip(confetti.iter()) {
+            let [a, b, c, d, e, f] = appearance
+                .geometry(&fetti, viewport, self.rng.range(2.0, 3.0))
+                .matrix();
+            let radius = if fetti.shape == Shape::Circle {
+                "50%"
+            } else {
+                "0"
+            };
+            // Through the CSSOM, so colors can't inject other declarations.
+            let style = self.spans[i].style();
+            if i >= self.visible {
+                let _ = style.remove_property("display");
+            }
+            let transform = format!("matrix({a}, {b}, {c}, {d}, {e}, {f})");
+            let _ = style.set_property("transform", &transform);
+            let _ = style.set_property("background", &fetti.color);
+            let _ = style.set_property("opacity", &fetti.alpha(lifespan).to_string());
+            let _ = style.set_property("border-radius", radius);
+        }
+        self.used = count;
+    }
+
+    fn end(&mut self) {
+        for span in &self.spans[self.used..self.visible.max(self.used)] {
+            let _ = span.style().set_property("display", "none");
+        }
+        self.visible = self.used;
+    }
+}
+
+#[cfg(feature = "dom")]
+impl Drop for DomRenderer {
+    fn drop(&mut self) {
+        for span in &self.spans {
+            span.remove();
+        }
+    }
+}
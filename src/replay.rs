@@ -0,0 +1,64 @@
+use crate::{CannonConfig, FireRequest, ParticleInit};
+
+/// Seed and bursts of an animation, to replay it identically later or on another client with
+/// the same props, see [`ConfettiHandle::start_recording`](crate::ConfettiHandle::start_recording).
+/// With the `serde` feature, it is serializable, e.g. to send it to other clients.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfettiRecording {
+    /// Seed of particle randomness.
+    pub seed: u64,
+    /// Bursts fired by a [`ConfettiHandle`](crate::ConfettiHandle), in order, with the
+    /// simulated time they were emitted, in milliseconds since the recording started.
+    pub fires: Vec<(u64, RecordedFire)>,
+}
+
+/// A [`FireRequest`] in a [`ConfettiRecording`]. Replaying it doesn't call
+/// [`CannonProps::on_burst`](crate::CannonProps::on_burst) of cannons that weren't children.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RecordedFire {
+    /// See [`FireRequest::All`].
+    All,
+    /// See [`FireRequest::Cannon`].
+    Cannon(usize),
+    /// See [`FireRequest::With`].
+    With(CannonConfig),
+    /// See [`FireRequest::Particles`].
+    Particles(Vec<ParticleInit>),
+}
+
+impl From<FireRequest> for RecordedFire {
+    fn from(fire: FireRequest) -> Self {
+        match fire {
+            FireRequest::All => Self::All,
+            FireRequest::Cannon(i) => Self::Cannon(i),
+            FireRequest::With(cannon) => Self::With(CannonConfig::from(&*cannon)),
+            FireRequest::Particles(particles) => Self::Particles(particles),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{ConfettiRecording, RecordedFire};
+    use crate::{CannonConfig, ParticleInit};
+
+    #[test]
+    fn json_round_trip() {
+        let recording = ConfettiRecording {
+            seed: 42,
+            fires: vec![
+                (0, RecordedFire::All),
+                (100, RecordedFire::Cannon(1)),
+                (250, RecordedFire::With(CannonConfig::default())),
+                (400, RecordedFire::Particles(vec![ParticleInit::default()])),
+            ],
+        };
+        let json = serde_json::to_string(&recording).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ConfettiRecording>(&json).unwrap(),
+            recording
+        );
+    }
+}
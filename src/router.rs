@@ -0,0 +1,20 @@
+//! Celebrating navigation with `yew-router`, e.g. landing on an order confirmation page.
+
+use crate::{use_confetti_context, CannonProps};
+use yew::{hook, use_effect_with};
+use yew_router::hooks::use_route;
+use yew_router::Routable;
+
+/// Fire confetti on the nearest [`ConfettiProvider`](crate::ConfettiProvider) whenever
+/// navigation lands on `route`, including the first render. Fires `cannon` if any, otherwise
+/// the provider's cannon. Must be called inside a router.
+#[hook]
+pub fn use_route_celebration<R: Routable + 'static>(route: R, cannon: Option<CannonProps>) {
+    let handle = use_confetti_context();
+    let arrived = use_route::<R>().as_ref() == Some(&route);
+    use_effect_with(arrived, move |&arrived| match (arrived, handle, cannon) {
+        (true, Some(handle), Some(cannon)) => handle.fire_with(cannon),
+        (true, Some(handle), None) => handle.fire(),
+        _ => {}
+    });
+}
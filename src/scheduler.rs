@@ -0,0 +1,83 @@
+use js_sys::wasm_bindgen::{prelude::Closure, JsCast};
+use std::cell::RefCell;
+use std::rc::Rc;
+use web_sys::window;
+
+/// Called with the `requestAnimationFrame` timestamp.
+pub(crate) type Frame = Rc<RefCell<dyn FnMut(f64)>>;
+
+thread_local! {
+    static SCHEDULER: RefCell<Scheduler> = RefCell::new(Scheduler::default());
+}
+
+/// Ticks every [`Confetti`](crate::Confetti) from one `requestAnimationFrame`, keeping them in
+/// phase.
+#[derive(Default)]
+struct Scheduler {
+    /// Called on the next animation frame.
+    queued: Vec<(u64, Frame)>,
+    /// Capacity for the next `queued`, to avoid allocating every frame.
+    spare: Vec<(u64, Frame)>,
+    /// Ids cancelled during [`tick`], whose frames it must skip.
+    cancelled: Vec<u64>,
+    /// Whether [`tick`] is calling frames.
+    ticking: bool,
+    next_id: u64,
+    animation_frame: Option<i32>,
+    callback: Option<Closure<dyn FnMut(f64)>>,
+}
+
+/// Call `frame` once on the next animation frame. Returns an id for [`cancel`].
+pub(crate) fn request(frame: &Frame) -> u64 {
+    SCHEDULER.with_borrow_mut(|scheduler| {
+        scheduler.next_id += 1;
+        let id = scheduler.next_id;
+        scheduler.queued.push((id, Rc::clone(frame)));
+        if scheduler.animation_frame.is_none() {
+            let callback = scheduler.callback.get_or_insert_with(|| Closure::new(tick));
+            // Without animation frames, such as in some embedded webviews, nothing animates.
+            scheduler.animation_frame = window().and_then(|window| {
+                window
+                    .request_animation_frame(callback.as_ref().unchecked_ref())
+                    .ok()
+            });
+        }
+        id
+    })
+}
+
+/// Undo [`request`].
+pub(crate) fn cancel(id: u64) {
+    SCHEDULER.with_borrow_mut(|scheduler| {
+        scheduler.queued.retain(|(queued, _)| *queued != id);
+        if scheduler.ticking {
+            scheduler.cancelled.push(id);
+        }
+        if scheduler.queued.is_empty() {
+            if let Some((animation_frame, window)) = scheduler.animation_frame.take().zip(window())
+            {
+                let _ = window.cancel_animation_frame(animation_frame);
+            }
+        }
+    });
+}
+
+fn tick(raw_time: f64) {
+    let mut queued = SCHEDULER.with_borrow_mut(|scheduler| {
+        scheduler.animation_frame = None;
+        scheduler.ticking = true;
+        let spare = std::mem::take(&mut scheduler.spare);
+        std::mem::replace(&mut scheduler.queued, spare)
+    });
+    // Frames may request the next frame, or cancel frames later in this one.
+    for (id, frame) in queued.drain(..) {
+        if SCHEDULER.with_borrow(|scheduler| !scheduler.cancelled.contains(&id)) {
+            (frame.borrow_mut())(raw_time);
+        }
+    }
+    SCHEDULER.with_borrow_mut(|scheduler| {
+        scheduler.spare = queued;
+        scheduler.cancelled.clear();
+        scheduler.ticking = false;
+    });
+}
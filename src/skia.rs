@@ -0,0 +1,185 @@
+//! Software renderer, so drawing can be snapshot-tested without a browser. Run the golden tests
+//! with `cargo test --features tiny-skia`, and set `UPDATE_GOLDEN=1` to regenerate the images
+//! in `tests/golden` after an intended change.
+
+use crate::core::{Particles, Rng, Shape, Viewport};
+use crate::render::{Appearance, Renderer};
+use tiny_skia::{Color, FillRule, Paint, Path, PathBuilder, Pixmap, Rect, Transform};
+
+/// Fills each particle into a [`Pixmap`], like the canvas renderers.
+pub(crate) struct SkiaRenderer {
+    pub(crate) pixmap: Pixmap,
+    /// Unit square.
+    square: Path,
+    /// Circle inscribed in the unit square.
+    circle: Path,
+    /// Jitters particle sizes.
+    rng: Rng,
+}
+
+impl SkiaRenderer {
+    pub(crate) fn new(width: u32, height: u32, seed: u64) -> Self {
+        let unit = Rect::from_xywh(0.0, 0.0, 1.0, 1.0).unwrap();
+        Self {
+            pixmap: Pixmap::new(width, height).unwrap(),
+            square: PathBuilder::from_rect(unit),
+            circle: PathBuilder::from_oval(unit).unwrap(),
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Renderer for SkiaRenderer {
+    fn begin(&mut self, viewport: Viewport) {
+        let (width, height) = (viewport.width as u32, viewport.height as u32);
+        if (self.pixmap.width(), self.pixmap.height()) != (width, height) {
+            self.pixmap = Pixmap::new(width.max(1), height.max(1)).unwrap();
+        } else {
+            self.pixmap.fill(Color::TRANSPARENT);
+        }
+    }
+
+    fn draw(&mut self, appearance: &Appearance, viewport: Viewport, confetti: &Particles) {
+        let lifespan = appearance.lifespan;
+        for i in 0..confetti.len() {
+            let fetti = confetti.get(i);
+            let [a, b, c, d, e, f] = appearance
+                .geometry(&fetti, viewport, self.rng.range(2.0, 3.0))
+                .matrix();
+            let mut paint = Paint::default();
+            let mut color = parse_color(confetti.color(i));
+            color.apply_opacity(confetti.alpha(i, lifespan));
+            paint.set_color(color);
+            let path = match fetti.shape {
+                Shape::Circle => &self.circle,
+                Shape::Square => &self.square,
+            };
+            self.pixmap.fill_path(
+                path,
+                &paint,
+                FillRule::Winding,
+                Transform::from_row(a, b, c, d, e, f),
+                None,
+            );
+        }
+    }
+}
+
+/// Parse a `#rgb` or `#rrggbb` CSS color, or black.
+fn parse_color(color: &str) -> Color {
+    let digits = color
+        .strip_prefix('#')
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok().map(|n| (hex.len(), n)));
+    let [r, g, b] = match digits {
+        Some((3, n)) => [n >> 8, n >> 4, n].map(|c| (c & 0xf) as u8 * 0x11),
+        Some((6, n)) => [n >> 16, n >> 8, n].map(|c| c as u8),
+        _ => [0; 3],
+    };
+    Color::from_rgba8(r, g, b, 255)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SkiaRenderer;
+    use crate::core::{ClockKey, Emitter, Engine, Mode, ParticleInit, Physics, Shape, Viewport};
+    use crate::render::Renderer;
+    use crate::ConfettiConfig;
+    use implicit_clone::unsync::IString;
+    use tiny_skia::Pixmap;
+
+    const VIEWPORT: Viewport = Viewport {
+        width: 160.0,
+        height: 120.0,
+        scale: 1.0,
+    };
+
+    /// Render `engine` and compare it with `tests/golden/{name}.png`.
+    fn assert_golden(name: &str, engine: &Engine) {
+        let config = ConfettiConfig::default();
+        let mut renderer = SkiaRenderer::new(VIEWPORT.width as u32, VIEWPORT.height as u32, 1);
+        renderer.begin(VIEWPORT);
+        renderer.draw(&config.appearance(), VIEWPORT, &engine.particles);
+        renderer.end();
+
+        let path = format!("{}/tests/golden/{name}.png", env!("CARGO_MANIFEST_DIR"));
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            renderer.pixmap.save_png(&path).unwrap();
+            return;
+        }
+        let golden = Pixmap::load_png(&path)
+            .unwrap_or_else(|e| panic!("{path}: {e}, run with UPDATE_GOLDEN=1 to create it"));
+        assert_eq!(
+            (golden.width(), golden.height()),
+            (renderer.pixmap.width(), renderer.pixmap.height())
+        );
+        // Tolerate floating point differences between platforms.
+        let different = golden
+            .data()
+            .chunks(4)
+            .zip(renderer.pixmap.data().chunks(4))
+            .filter(|(a, b)| a.iter().zip(*b).any(|(a, b)| a.abs_diff(*b) > 3))
+            .count();
+        assert!(
+            different <= golden.data().len() / 4 / 1000,
+            "{different} pixels differ from {path}"
+        );
+    }
+
+    fn engine(emitters: &[Emitter], physics: &Physics, time: u64) -> Engine {
+        let mut engine = Engine::default();
+        engine.reseed(7);
+        engine.sync_clocks(
+            emitters
+                .iter()
+                .enumerate()
+                .map(|(i, emitter)| (ClockKey::Index(i), emitter.mode)),
+        );
+        engine.step(time, emitters, physics);
+        engine
+    }
+
+    #[test]
+    fn burst() {
+        let emitters = [Emitter {
+            y: 0.2,
+            mode: Mode::burst(80),
+            ..Default::default()
+        }];
+        assert_golden("burst", &engine(&emitters, &Physics::default(), 300));
+    }
+
+    #[test]
+    fn shapes() {
+        let physics = Physics::default();
+        let mut engine = engine(&[], &physics, 0);
+        let shapes = [Shape::Circle, Shape::Square];
+        let colors = ["#f00", "#00ff00", "#26ccff", "red"];
+        engine.spawn(
+            (0..8)
+                .map(|i| ParticleInit {
+                    x: 0.1 + 0.1 * i as f32,
+                    y: 0.5,
+                    color: IString::Static(colors[i % colors.len()]),
+                    shape: shapes[i % shapes.len()],
+                    ..Default::default()
+                })
+                .collect(),
+            &physics,
+            0,
+        );
+        assert_golden("shapes", &engine);
+    }
+
+    #[test]
+    fn fade() {
+        let emitters = [Emitter {
+            mode: Mode::burst(40),
+            ..Default::default()
+        }];
+        let physics = Physics {
+            lifespan: 1.0,
+            ..Default::default()
+        };
+        assert_golden("fade", &engine(&emitters, &physics, 600));
+    }
+}
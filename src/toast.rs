@@ -0,0 +1,92 @@
+use crate::celebrate::burst;
+use crate::{CannonProps, Celebrate};
+use js_sys::wasm_bindgen::{prelude::Closure, JsCast};
+use web_sys::window;
+use yew::{
+    function_component, html, use_effect_with, AttrValue, Callback, Children, Html, Properties,
+};
+
+/// Achievement toast options.
+#[derive(Clone, PartialEq, Properties)]
+pub struct AchievementToastProps {
+    /// Headline, e.g. the name of the achievement.
+    pub title: AttrValue,
+    /// Called when the close button is pressed, or the timeout elapses. The toast stays until
+    /// its parent stops rendering it. Changing the callback restarts the timeout, so keep it
+    /// stable, e.g. with `use_callback`.
+    #[prop_or_default]
+    pub on_dismiss: Callback<()>,
+    /// Seconds until dismissed automatically, if any.
+    #[prop_or(Some(5.0))]
+    pub timeout: Option<f32>,
+    /// Cannon that bursts from the toast when it appears, ignoring its `x` and `y`.
+    #[prop_or_else(burst)]
+    pub cannon: CannonProps,
+    /// Stacking order of the toast. The confetti is drawn just above it.
+    #[prop_or(1000)]
+    pub z_index: i32,
+    /// Details below the title.
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// A dismissible toast at the bottom of the window, celebrated with a burst of confetti when it
+/// appears. Render it when an achievement is unlocked, and stop rendering it once dismissed.
+#[function_component(AchievementToast)]
+pub fn achievement_toast(props: &AchievementToastProps) -> Html {
+    {
+        use_effect_with(
+            (props.timeout, props.on_dismiss.clone()),
+            |(timeout, on_dismiss)| {
+                let on_dismiss = on_dismiss.clone();
+                let timer = timeout.zip(window()).and_then(|(timeout, window)| {
+                    let dismiss = Closure::once(move || on_dismiss.emit(()));
+                    let id = window
+                        .set_timeout_with_callback_and_timeout_and_arguments_0(
+                            dismiss.as_ref().unchecked_ref(),
+                            (timeout.max(0.0) * 1000.0) as i32,
+                        )
+                        .ok()?;
+                    Some((window, id, dismiss))
+                });
+                move || {
+                    if let Some((window, id, _dismiss)) = timer {
+                        window.clear_timeout_with_handle(id);
+                    }
+                }
+            },
+        );
+    }
+
+    let style = format!(
+        "position: fixed; left: 50%; bottom: 1rem; transform: translateX(-50%); z-index: {};",
+        props.z_index
+    );
+    let on_close = props.on_dismiss.reform(|_| ());
+
+    html! {
+        <div {style}>
+            <Celebrate when={true} cannon={props.cannon.clone()} z_index={props.z_index.saturating_add(1)}>
+                <div
+                    role="status"
+                    style="display: flex; align-items: flex-start; gap: 1rem; padding: 0.75rem 1rem; \
+                        border-radius: 0.5rem; background-color: #2c3e50; color: white; \
+                        box-shadow: 0 0.25rem 1rem rgba(0, 0, 0, 0.3);"
+                >
+                    <div>
+                        <strong>{props.title.clone()}</strong>
+                        <div>{props.children.clone()}</div>
+                    </div>
+                    <button
+                        aria-label="Dismiss"
+                        onclick={on_close}
+                        style="border: none; background: none; color: inherit; cursor: pointer; \
+                            font-size: 1.25rem; line-height: 1;"
+                    >
+                        {"×"}
+                    </button>
+                </div>
+            </Celebrate>
+        </div>
+    }
+}
@@ -0,0 +1,145 @@
+//! Console warnings for props that are probably mistakes, including those that are clamped,
+//! in debug builds.
+
+use crate::core::{Emitter, ModeImpl};
+use crate::ConfettiProps;
+
+/// Log a warning for each suspicious value of `props` or its cannons' `emitters`, except those
+/// in `logged`, which then holds the current warnings.
+pub(crate) fn warn(props: &ConfettiProps, emitters: &[Emitter], logged: &mut Vec<String>) {
+    let warnings = warnings(props, emitters);
+    for warning in &warnings {
+        if !logged.contains(warning) {
+            web_sys::console::warn_1(&format!("yew_confetti: {warning}").into());
+        }
+    }
+    *logged = warnings;
+}
+
+fn warnings(props: &ConfettiProps, emitters: &[Emitter]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mut check = |ok: bool, warning: &dyn Fn() -> String| {
+        if !ok {
+            warnings.push(warning());
+        }
+    };
+    let physics = props.raw_physics();
+    check(physics.decay > 0.0 && physics.decay <= 1.0, &|| {
+        format!("decay {} isn't in (0, 1], so it's clamped", physics.decay)
+    });
+    check(physics.lifespan > 0.0, &|| {
+        format!(
+            "lifespan {} isn't positive, so it's clamped",
+            physics.lifespan
+        )
+    });
+    check(props.scalar > 0.0, &|| {
+        format!(
+            "scalar {} isn't positive, so particles are invisible",
+            props.scalar
+        )
+    });
+    if let Some(transform) = props.transform {
+        check(
+            transform.scale.is_finite() && transform.scale != 0.0,
+            &|| format!("transform scale {} is clamped", transform.scale),
+        );
+    }
+    check(
+        props.auto_size || (props.width > 0 && props.height > 0),
+        &|| format!("size {}x{} is empty", props.width, props.height),
+    );
+    check(props.max_particles > 0, &|| {
+        "max_particles is 0, so no particles are shown".to_owned()
+    });
+    check(props.fps_cap != Some(0), &|| {
+        "fps_cap 0 is ignored".to_owned()
+    });
+    if let Some(threshold) = props.emit_on_visible {
+        check((0.0..=1.0).contains(&threshold), &|| {
+            format!("emit_on_visible {threshold} isn't in [0, 1]")
+        });
+    }
+
+    for (i, emitter) in emitters.iter().enumerate() {
+        // Slightly outside the canvas is a common way to aim in from an edge.
+        let far = |position: f32| !(-1.0..=2.0).contains(&position);
+        check(!far(emitter.x) && !far(emitter.y), &|| {
+            format!(
+                "cannon {i} at ({}, {}) is far outside the canvas, which spans 0 to 1",
+                emitter.x, emitter.y
+            )
+        });
+        check(
+            !emitter.path.iter().any(|&(x, y)| far(x) || far(y)),
+            &|| format!("cannon {i} has path points far outside the canvas, which spans 0 to 1"),
+        );
+        let unordered = emitter.keyframes.iter().enumerate().any(|(j, a)| {
+            emitter.keyframes[j + 1..]
+                .iter()
+                .any(|b| b.track == a.track && b.time < a.time)
+        });
+        check(!unordered, &|| {
+            format!("cannon {i} has keyframes out of order, which skips some of them")
+        });
+        check(!emitter.colors.is_empty(), &|| {
+            format!("cannon {i} has no colors")
+        });
+        check(!emitter.shapes.is_empty(), &|| {
+            format!("cannon {i} has no shapes")
+        });
+        check(emitter.spread >= 0.0, &|| {
+            format!(
+                "cannon {i} has negative spread {}, which is clamped to 0",
+                emitter.spread
+            )
+        });
+        match emitter.mode.0 {
+            ModeImpl::Continuous { rate, .. } | ModeImpl::Drag { rate } => {
+                check(rate <= 1000, &|| {
+                    format!("cannon {i} rate {rate} is clamped to the maximum of 1000 per second")
+                })
+            }
+            ModeImpl::Repeating { period, .. } => check(period > 0, &|| {
+                format!("cannon {i} repeats with a period of 0, which is clamped to a millisecond")
+            }),
+            _ => {}
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::warnings;
+    use crate::core::{Emitter, Mode, ModeImpl};
+    use crate::ConfettiProps;
+    use implicit_clone::unsync::IArray;
+    use yew::props;
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(warnings(&props!(ConfettiProps {}), &[Emitter::default()]).is_empty());
+    }
+
+    #[test]
+    fn suspicious_values() {
+        let props = props!(ConfettiProps {
+            decay: 1.5,
+            lifespan: -1.0,
+        });
+        let emitter = Emitter {
+            x: 5.0,
+            colors: IArray::Static(&[]),
+            // Unlike the constructor, deserializing doesn't clamp the period.
+            mode: Mode(ModeImpl::Repeating {
+                count: 10,
+                delay: 0,
+                period: 0,
+            }),
+            ..Default::default()
+        };
+        let warnings = warnings(&props, &[emitter]);
+        assert_eq!(warnings.len(), 5, "{warnings:?}");
+    }
+}
@@ -0,0 +1,121 @@
+//! Recording the animation to video, see [`ConfettiHandle::record`](crate::ConfettiHandle::record).
+
+use crate::ConfettiError;
+use js_sys::wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use web_sys::{window, Blob, BlobEvent, HtmlCanvasElement, MediaRecorder, MediaRecorderOptions};
+
+/// Container of recorded videos.
+const MIME_TYPE: &str = "video/webm";
+
+/// Resolves to a WebM video once a [`ConfettiHandle::record`](crate::ConfettiHandle::record)
+/// finishes.
+pub struct VideoRecording(Rc<RefCell<VideoInner>>);
+
+#[derive(Default)]
+struct VideoInner {
+    result: Option<Result<Blob, ConfettiError>>,
+    waker: Option<Waker>,
+    /// Kept alive until the video is available.
+    on_data: Option<Closure<dyn FnMut(BlobEvent)>>,
+}
+
+impl VideoInner {
+    fn finish(&mut self, result: Result<Blob, ConfettiError>) {
+        self.result = Some(result);
+        self.on_data = None;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl VideoRecording {
+    pub(crate) fn failed(error: ConfettiError) -> Self {
+        let inner = VideoInner {
+            result: Some(Err(error)),
+            ..Default::default()
+        };
+        Self(Rc::new(RefCell::new(inner)))
+    }
+}
+
+impl Future for VideoRecording {
+    type Output = Result<Blob, ConfettiError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut inner = self.0.borrow_mut();
+        match inner.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                inner.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Describe a JS exception.
+fn video_error(error: JsValue) -> ConfettiError {
+    let message = error
+        .dyn_ref::<js_sys::Error>()
+        .map(|e| String::from(e.message()))
+        .or_else(|| error.as_string())
+        .unwrap_or_else(|| "unknown error".to_owned());
+    ConfettiError::Video(message)
+}
+
+/// Record `duration` seconds of `canvas`.
+pub(crate) fn record(canvas: &HtmlCanvasElement, duration: f32) -> VideoRecording {
+    let Some(window) = window() else {
+        return VideoRecording::failed(ConfettiError::Video("no window".to_owned()));
+    };
+    let recorder = match create(canvas) {
+        Ok(recorder) => recorder,
+        Err(error) => return VideoRecording::failed(video_error(error)),
+    };
+    let inner = Rc::new(RefCell::new(VideoInner::default()));
+    let on_data = {
+        let inner = Rc::clone(&inner);
+        Closure::<dyn FnMut(BlobEvent)>::new(move |event: BlobEvent| {
+            let result = event
+                .data()
+                .ok_or_else(|| ConfettiError::Video("no data".to_owned()));
+            inner.borrow_mut().finish(result);
+        })
+    };
+    recorder.set_ondataavailable(Some(on_data.as_ref().unchecked_ref()));
+    inner.borrow_mut().on_data = Some(on_data);
+    let on_error = {
+        let inner = Rc::clone(&inner);
+        Closure::once_into_js(move |error: JsValue| {
+            inner.borrow_mut().finish(Err(video_error(error)));
+        })
+    };
+    recorder.set_onerror(Some(on_error.unchecked_ref()));
+    if let Err(error) = recorder.start() {
+        inner.borrow_mut().finish(Err(video_error(error)));
+        return VideoRecording(inner);
+    }
+    let stop = Closure::once_into_js(move || {
+        // Only fails if already stopped, e.g. due to an error.
+        let _ = recorder.stop();
+    });
+    let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+        stop.unchecked_ref(),
+        (duration.max(0.0) * 1000.0) as i32,
+    );
+    VideoRecording(inner)
+}
+
+/// Create a recorder of `canvas`.
+fn create(canvas: &HtmlCanvasElement) -> Result<MediaRecorder, JsValue> {
+    let stream = canvas.capture_stream()?;
+    let options = MediaRecorderOptions::new();
+    options.set_mime_type(MIME_TYPE);
+    MediaRecorder::new_with_media_stream_and_media_recorder_options(&stream, &options)
+}
@@ -0,0 +1,325 @@
+use crate::core::{Particles, Rng, Shape, Viewport};
+use crate::render::{Appearance, Renderer};
+use crate::Listener;
+use implicit_clone::unsync::IString;
+use js_sys::wasm_bindgen::JsCast;
+use js_sys::Float32Array;
+use std::collections::HashMap;
+use web_sys::{
+    window, CanvasRenderingContext2d, HtmlCanvasElement, WebGl2RenderingContext as Gl, WebGlBuffer,
+    WebGlProgram, WebGlShader, WebGlUniformLocation, WebGlVertexArrayObject,
+};
+
+const VERTEX_SHADER: &str = r#"#version 300 es
+uniform vec2 u_resolution;
+in vec4 a_corners_0;
+in vec4 a_corners_1;
+in vec4 a_color;
+in float a_circle;
+out vec4 v_color;
+out vec2 v_uv;
+flat out float v_circle;
+
+void main() {
+    // Triangle strip through the corners, which are in order around the perimeter.
+    vec2 corner;
+    if (gl_VertexID == 0) {
+        corner = a_corners_0.xy;
+        v_uv = vec2(-1.0, -1.0);
+    } else if (gl_VertexID == 1) {
+        corner = a_corners_0.zw;
+        v_uv = vec2(1.0, -1.0);
+    } else if (gl_VertexID == 2) {
+        corner = a_corners_1.zw;
+        v_uv = vec2(-1.0, 1.0);
+    } else {
+        corner = a_corners_1.xy;
+        v_uv = vec2(1.0, 1.0);
+    }
+    v_color = a_color;
+    v_circle = a_circle;
+    gl_Position = vec4(corner / u_resolution * vec2(2.0, -2.0) + vec2(-1.0, 1.0), 0.0, 1.0);
+}
+"#;
+
+const FRAGMENT_SHADER: &str = r#"#version 300 es
+precision mediump float;
+in vec4 v_color;
+in vec2 v_uv;
+flat in float v_circle;
+out vec4 color;
+
+void main() {
+    if (v_circle > 0.5 && dot(v_uv, v_uv) > 1.0) {
+        discard;
+    }
+    color = vec4(v_color.rgb * v_color.a, v_color.a);
+}
+"#;
+
+/// Floats per instance: 4 corners, rgba, and whether it's a circle.
+const STRIDE: usize = 8 + 4 + 1;
+
+/// Draws all particles as instanced quads in one draw call.
+pub(crate) struct WebGlRenderer {
+    gl: Gl,
+    program: WebGlProgram,
+    buffer: WebGlBuffer,
+    vertex_array: WebGlVertexArrayObject,
+    resolution: WebGlUniformLocation,
+    instances: Vec<f32>,
+    /// Reused to upload `instances`.
+    array: Float32Array,
+    /// Parsed CSS colors.
+    colors: HashMap<IString, [f32; 3]>,
+    /// Normalizes CSS colors that aren't hex.
+    scratch: Option<CanvasRenderingContext2d>,
+    /// Jitters particle sizes.
+    rng: Rng,
+    canvas: HtmlCanvasElement,
+    seed: u64,
+    /// Whether the context was lost, so resources must be recreated once it's restored.
+    lost: bool,
+    /// Allows the context to be restored.
+    _context_lost: Listener,
+}
+
+impl WebGlRenderer {
+    /// Whether WebGL2 works, probed once on a throwaway canvas, because a canvas that got a
+    /// WebGL context can't fall back to the 2D context.
+    pub(crate) fn supported() -> bool {
+        thread_local! {
+            static SUPPORTED: bool = window()
+                .and_then(|window| window.document())
+                .and_then(|document| document.create_element("canvas").ok())
+                .and_then(|canvas| WebGlRenderer::new(canvas.unchecked_ref(), 0))
+                .is_some();
+        }
+        SUPPORTED.with(|supported| *supported)
+    }
+
+    /// Returns `None` if WebGL2 is unavailable.
+    pub(crate) fn new(canvas: &HtmlCanvasElement, seed: u64) -> Option<Self> {
+        let gl = canvas
+            .get_context("webgl2")
+            .ok()
+            .flatten()?
+            .dyn_into::<Gl>()
+            .ok()?;
+
+        let vertex = compile(&gl, Gl::VERTEX_SHADER, VERTEX_SHADER)?;
+        let fragment = compile(&gl, Gl::FRAGMENT_SHADER, FRAGMENT_SHADER)?;
+        let program = gl.create_program()?;
+        gl.attach_shader(&program, &vertex);
+        gl.attach_shader(&program, &fragment);
+        gl.link_program(&program);
+        gl.delete_shader(Some(&vertex));
+        gl.delete_shader(Some(&fragment));
+        if !gl
+            .get_program_parameter(&program, Gl::LINK_STATUS)
+            .as_bool()
+            .unwrap_or(false)
+        {
+            gl.delete_program(Some(&program));
+            return None;
+        }
+        let resolution = gl.get_uniform_location(&program, "u_resolution")?;
+
+        let buffer = gl.create_buffer()?;
+        let vertex_array = gl.create_vertex_array()?;
+        gl.bind_vertex_array(Some(&vertex_array));
+        gl.bind_buffer(Gl::ARRAY_BUFFER, Some(&buffer));
+        let mut offset = 0;
+        for (name, size) in [
+            ("a_corners_0", 4),
+            ("a_corners_1", 4),
+            ("a_color", 4),
+            ("a_circle", 1),
+        ] {
+            let location = gl.get_attrib_location(&program, name) as u32;
+            gl.enable_vertex_attrib_array(location);
+            gl.vertex_attrib_pointer_with_i32(
+                location,
+                size,
+                Gl::FLOAT,
+                false,
+                (STRIDE * 4) as i32,
+                offset * 4,
+            );
+            gl.vertex_attrib_divisor(location, 1);
+            offset += size;
+        }
+        gl.bind_vertex_array(None);
+
+        gl.enable(Gl::BLEND);
+        gl.blend_func(Gl::ONE, Gl::ONE_MINUS_SRC_ALPHA);
+
+        Some(Self {
+            gl,
+            program,
+            buffer,
+            vertex_array,
+            resolution,
+            instances: Vec::new(),
+            array: Float32Array::new_with_length(0),
+            colors: HashMap::new(),
+            scratch: None,
+            rng: Rng::new(seed),
+            canvas: canvas.clone(),
+            seed,
+            lost: false,
+            _context_lost: Listener::new(canvas.clone().into(), "webglcontextlost", |event| {
+                event.prevent_default();
+            }),
+        })
+    }
+
+    /// Parse a CSS color into rgb components.
+    fn color(&mut self, color: &IString) -> [f32; 3] {
+        if let Some(rgb) = self.colors.get(color) {
+            return *rgb;
+        }
+        let rgb = parse_hex(color)
+            .or_else(|| {
+                // The canvas normalizes any CSS color to hex or `rgba(...)`.
+                if self.scratch.is_none() {
+                    self.scratch = window()?
+                        .document()?
+                        .create_element("canvas")
+                        .ok()?
+                        .unchecked_into::<HtmlCanvasElement>()
+                        .get_context("2d")
+                        .ok()
+                        .flatten()
+                        .map(JsCast::unchecked_into);
+                }
+                let scratch = self.scratch.as_ref()?;
+                scratch.set_fill_style_str("#000000");
+                scratch.set_fill_style_str(color);
+                let normalized = scratch.fill_style().as_string()?;
+                parse_hex(&normalized).or_else(|| parse_rgba(&normalized))
+            })
+            .unwrap_or([0.0; 3]);
+        self.colors.insert(color.clone(), rgb);
+        rgb
+    }
+}
+
+impl Renderer for WebGlRenderer {
+    fn begin(&mut self, viewport: Viewport) {
+        if self.gl.is_context_lost() {
+            self.lost = true;
+            return;
+        }
+        if self.lost {
+            let Some(restored) = Self::new(&self.canvas, self.seed) else {
+                return;
+            };
+            *self = restored;
+        }
+        self.gl
+            .viewport(0, 0, viewport.width as i32, viewport.height as i32);
+        self.gl.clear_color(0.0, 0.0, 0.0, 0.0);
+        self.gl.clear(Gl::COLOR_BUFFER_BIT);
+    }
+
+    fn draw(&mut self, appearance: &Appearance, viewport: Viewport, confetti: &Particles) {
+        let lifespan = appearance.lifespan;
+        if confetti.is_empty() || self.lost {
+            return;
+        }
+
+        let mut instances = std::mem::take(&mut self.instances);
+        instances.clear();
+        for fetti in confetti.iter() {
+            instances.extend(
+                appearance
+                    .geometry(&fetti, viewport, self.rng.range(2.0, 3.0))
+                    .corners()
+                    .as_flattened(),
+            );
+            instances.extend(self.color(&fetti.color));
+            instances.push(fetti.alpha(lifespan));
+            instances.push(if fetti.shape == Shape::Circle {
+                1.0
+            } else {
+                0.0
+            });
+        }
+        if self.array.length() < instances.len() as u32 {
+            self.array = Float32Array::new_with_length(instances.len().next_power_of_two() as u32);
+        }
+        self.array
+            .subarray(0, instances.len() as u32)
+            .copy_from(&instances);
+
+        self.gl.use_program(Some(&self.program));
+        self.gl
+            .uniform2f(Some(&self.resolution), viewport.width, viewport.height);
+        self.gl.bind_vertex_array(Some(&self.vertex_array));
+        self.gl.bind_buffer(Gl::ARRAY_BUFFER, Some(&self.buffer));
+        self.gl
+            .buffer_data_with_array_buffer_view_and_src_offset_and_length(
+                Gl::ARRAY_BUFFER,
+                &self.array,
+                Gl::STREAM_DRAW,
+                0,
+                instances.len() as u32,
+            );
+        self.gl
+            .draw_arrays_instanced(Gl::TRIANGLE_STRIP, 0, 4, confetti.len() as i32);
+        self.gl.bind_vertex_array(None);
+        self.instances = instances;
+    }
+}
+
+impl Drop for WebGlRenderer {
+    fn drop(&mut self) {
+        self.gl.delete_vertex_array(Some(&self.vertex_array));
+        self.gl.delete_buffer(Some(&self.buffer));
+        self.gl.delete_program(Some(&self.program));
+    }
+}
+
+/// Returns `None` if compilation fails, e.g. because the context was lost.
+fn compile(gl: &Gl, kind: u32, source: &str) -> Option<WebGlShader> {
+    let shader = gl.create_shader(kind)?;
+    gl.shader_source(&shader, source);
+    gl.compile_shader(&shader);
+    if !gl
+        .get_shader_parameter(&shader, Gl::COMPILE_STATUS)
+        .as_bool()
+        .unwrap_or(false)
+    {
+        gl.delete_shader(Some(&shader));
+        return None;
+    }
+    Some(shader)
+}
+
+/// Parse `#rgb` or `#rrggbb`.
+fn parse_hex(color: &str) -> Option<[f32; 3]> {
+    let hex = color.strip_prefix('#')?;
+    let digit = |i: usize| u8::from_str_radix(hex.get(i..i + 1)?, 16).ok();
+    let [r, g, b] = match hex.len() {
+        3 => [digit(0)? * 17, digit(1)? * 17, digit(2)? * 17],
+        6 => [
+            digit(0)? * 16 + digit(1)?,
+            digit(2)? * 16 + digit(3)?,
+            digit(4)? * 16 + digit(5)?,
+        ],
+        _ => return None,
+    };
+    Some([r, g, b].map(|c| c as f32 / 255.0))
+}
+
+/// Parse `rgba(r, g, b, a)`, ignoring alpha.
+fn parse_rgba(color: &str) -> Option<[f32; 3]> {
+    let inner = color.strip_prefix("rgba(")?.strip_suffix(')')?;
+    let mut components = inner.split(',').map(|c| c.trim().parse::<f32>().ok());
+    Some([
+        components.next()?? / 255.0,
+        components.next()?? / 255.0,
+        components.next()?? / 255.0,
+    ])
+}